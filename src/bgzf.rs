@@ -0,0 +1,902 @@
+//! Reading and writing of BGZF compressed files (requires the `bgzf` feature,
+//! which also enables `gzip`).
+//!
+//! [BGZF](https://samtools.github.io/hts-specs/SAMv1.pdf) is a variant of gzip
+//! produced by `bgzip`, which consists of independently compressed blocks of
+//! at most 64 KiB. Any position within a BGZF file can be described by a
+//! *virtual offset*, which combines the offset of the compressed block within
+//! the file (upper 48 bits) with the offset within the uncompressed block
+//! (lower 16 bits). This allows seeking directly to a record in a compressed
+//! file.
+//!
+//! Since [`bgzf::Reader`](struct.Reader.html) implements `Seek` (with offsets
+//! in the decompressed data), `seek()` of the FASTA and FASTQ readers works
+//! with BGZF input, and so do the indexed readers of
+//! [`fasta::index`](../fasta/index/index.html) and
+//! [`fastq::index`](../fastq/index/index.html). Indexes of the uncompressed
+//! file can be used for the compressed one:
+//!
+//! ```no_run
+//! use seq_io::bgzf;
+//! use seq_io::fastq::index::{Index, IndexedReader};
+//! use seq_io::fastq::{Reader, Record};
+//!
+//! let index = Index::from_path("seqs.fastq.idx").unwrap();
+//! let reader = Reader::new(bgzf::Reader::from_path("seqs.fastq.gz").unwrap());
+//! let mut reader = IndexedReader::new(reader, index);
+//! let record = reader.get_by_id(b"read_1").unwrap().unwrap();
+//! ```
+//!
+//! In addition, the FASTA and FASTQ readers provide `virtual_position()` and
+//! `seek_virtual()` if they read from a `bgzf::Reader`, which use virtual
+//! offsets instead:
+//!
+//! ```no_run
+//! use seq_io::bgzf;
+//! use seq_io::fastq::{Reader, Record};
+//!
+//! let mut reader = Reader::new(bgzf::Reader::from_path("seqs.fastq.gz").unwrap());
+//!
+//! // remember the virtual offset of the second record
+//! reader.next().unwrap().unwrap();
+//! reader.next().unwrap().unwrap();
+//! let pos = reader.virtual_position().unwrap();
+//!
+//! // (...) read more records, then jump back
+//! reader.seek_virtual(&pos).unwrap();
+//! let record = reader.next().unwrap().unwrap();
+//! ```
+//!
+//! **Note:** The byte offsets returned by `position()` of the FASTA and FASTQ
+//! readers, as well as `ErrorPosition::byte`, always refer to the
+//! *decompressed* data, also with BGZF input and after `seek_virtual()`.
+//! `Position` does not store virtual offsets, only the positions returned by
+//! `virtual_position()` do, and these can only be used with `seek_virtual()`.
+//! Seeking to a decompressed offset with `seek()` is slower if the target lies
+//! beyond the data read so far, since the block headers up to this position
+//! have to be read (see [`Reader`](struct.Reader.html)).
+//!
+//! # Writing
+//!
+//! [`bgzf::Writer`](struct.Writer.html) compresses the output in the calling
+//! thread, and can report the virtual offset of the next byte to be written.
+//! [`bgzf::ParallelWriter`](struct.ParallelWriter.html) compresses the blocks
+//! in a thread pool, which is considerably faster. Both can be used with the
+//! FASTA and FASTQ writers:
+//!
+//! ```no_run
+//! use seq_io::bgzf;
+//! use seq_io::fastq::{Reader, Writer};
+//!
+//! let mut reader = Reader::from_path("seqs.fastq").unwrap();
+//! let output = bgzf::ParallelWriter::new(std::fs::File::create("seqs.fastq.gz").unwrap(), 4);
+//! let mut writer = Writer::new(output);
+//!
+//! while let Some(record) = reader.next() {
+//!     writer.write_record(&record.unwrap()).unwrap();
+//! }
+//!
+//! // writes the remaining data and the end-of-file marker block
+//! writer.into_inner().unwrap().finish().unwrap();
+//! ```
+
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::File;
+use std::io::{self, Read, Seek, Write};
+use std::mem;
+use std::path::Path;
+use std::sync::{mpsc, Mutex};
+use std::thread;
+
+use flate2::{Compress, Compression, Crc, Decompress, FlushCompress, FlushDecompress, Status};
+
+/// Maximum size of a BGZF block (compressed or decompressed)
+pub const MAX_BLOCK_SIZE: usize = 1 << 16;
+
+// Fixed part of the gzip header up to and including XLEN
+const HEADER_LEN: usize = 12;
+
+// Maximum amount of uncompressed data per written block (same as in htslib),
+// which leaves room for incompressible data
+const BLOCK_DATA_SIZE: usize = 0xff00;
+
+// Default compression level
+const DEFAULT_LEVEL: u32 = 6;
+
+// Default amount of decompressed data, for which block offsets are kept
+const DEFAULT_WINDOW: u64 = 1 << 28;
+
+// Minimum distance between the blocks remembered for seeking (in decompressed data)
+const CHECKPOINT_DIST: u64 = 1 << 20;
+
+/// Empty block, which marks the end of a BGZF file
+pub const EOF_BLOCK: [u8; 28] = [
+    0x1f, 0x8b, 8, 4, 0, 0, 0, 0, 0, 0xff, 6, 0, b'B', b'C', 2, 0, 0x1b, 0, 3, 0, 0, 0, 0, 0, 0, 0,
+    0, 0,
+];
+
+/// Combines the offset of a compressed block and the offset within the
+/// uncompressed block to a virtual offset.
+#[inline]
+pub fn virtual_offset(block_offset: u64, within_block: u16) -> u64 {
+    (block_offset << 16) | within_block as u64
+}
+
+/// Splits a virtual offset into the offset of the compressed block
+/// and the offset within the uncompressed block.
+#[inline]
+pub fn split_virtual_offset(voffset: u64) -> (u64, u16) {
+    (voffset >> 16, (voffset & 0xffff) as u16)
+}
+
+/// Decoder of BGZF compressed data, which keeps track of the block offsets
+/// and therefore knows the virtual offset of any position in the decompressed
+/// data that has been read recently.
+///
+/// Block offsets are kept for the last 256 MiB of decompressed data
+/// (configurable with [`set_window_size`](#method.set_window_size)), older
+/// ones are discarded while reading.
+///
+/// If the underlying reader implements `Seek`, this reader implements `Seek`
+/// as well. The offsets refer to the *decompressed* data, they are translated
+/// to block offsets using the blocks seen so far. One block per MiB of
+/// decompressed data is remembered for this purpose. Seeking beyond the
+/// blocks read so far is done by skipping through the block headers, without
+/// decompressing the data. Seeking requires the BGZF data to start at the
+/// start of the underlying reader, and seeking relative to the end is not
+/// supported.
+pub struct Reader<R> {
+    inner: R,
+    // decompressed data of the current block
+    data: Vec<u8>,
+    // read position within `data`
+    data_pos: usize,
+    // compressed data of the current block
+    cdata: Vec<u8>,
+    // file offset of the current block
+    block_offset: u64,
+    // file offset of the next block
+    next_block_offset: u64,
+    // decompressed offset of `data[0]`
+    data_start: u64,
+    // (decompressed offset, block offset) of the blocks read recently
+    blocks: VecDeque<(u64, u64)>,
+    // amount of decompressed data, for which block offsets are kept
+    window_size: u64,
+    // (decompressed offset, block offset) of blocks spaced at least
+    // `CHECKPOINT_DIST` apart, used for seeking
+    checkpoints: Vec<(u64, u64)>,
+    decompress: Decompress,
+}
+
+impl Reader<File> {
+    /// Opens a BGZF compressed file
+    #[inline]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> io::Result<Reader<File>> {
+        File::open(path).map(Reader::new)
+    }
+}
+
+impl<R: Read> Reader<R> {
+    /// Creates a new BGZF decoder. The input is expected to be positioned at
+    /// the start of a block (usually the file start).
+    #[inline]
+    pub fn new(inner: R) -> Reader<R> {
+        Reader {
+            inner,
+            data: Vec::with_capacity(MAX_BLOCK_SIZE),
+            data_pos: 0,
+            cdata: Vec::with_capacity(MAX_BLOCK_SIZE),
+            block_offset: 0,
+            next_block_offset: 0,
+            data_start: 0,
+            blocks: VecDeque::new(),
+            window_size: DEFAULT_WINDOW,
+            checkpoints: vec![],
+            decompress: Decompress::new(false),
+        }
+    }
+
+    /// Sets the amount of decompressed data before the current read position
+    /// (in bytes), for which the block offsets are kept (default: 256 MiB).
+    /// Virtual offsets of positions further back cannot be obtained anymore.
+    /// When used with a FASTA or FASTQ reader, the size should not be smaller
+    /// than the reader's buffer, otherwise `virtual_position()` may return `None`.
+    #[inline]
+    pub fn set_window_size(mut self, size: u64) -> Self {
+        self.window_size = size;
+        self
+    }
+
+    /// Returns the virtual offset of the next byte to be read
+    #[inline]
+    pub fn virtual_offset(&self) -> u64 {
+        if self.data_pos < self.data.len() {
+            virtual_offset(self.block_offset, self.data_pos as u16)
+        } else {
+            virtual_offset(self.next_block_offset, 0)
+        }
+    }
+
+    /// Returns the offset of the next byte to be read within the decompressed data
+    #[inline]
+    pub fn offset(&self) -> u64 {
+        self.data_start + self.data_pos as u64
+    }
+
+    /// Translates an offset within the decompressed data (see `offset()`) into
+    /// a virtual offset. This is only possible for offsets within the blocks
+    /// that have been read recently (see [`set_window_size`](#method.set_window_size)),
+    /// and which were not discarded using `forget_before()`. Otherwise, `None`
+    /// is returned.
+    pub fn virtual_offset_at(&self, offset: u64) -> Option<u64> {
+        let end = self.data_start + self.data.len() as u64;
+        if offset == end {
+            return Some(virtual_offset(self.next_block_offset, 0));
+        }
+        if offset > end {
+            return None;
+        }
+        let i = self.blocks.partition_point(|&(start, _)| start <= offset);
+        if i == 0 {
+            return None;
+        }
+        let (start, block_offset) = self.blocks[i - 1];
+        let within = offset - start;
+        debug_assert!(within < MAX_BLOCK_SIZE as u64);
+        Some(virtual_offset(block_offset, within as u16))
+    }
+
+    /// Discards the block offsets of all blocks ending before the given
+    /// offset in the decompressed data, their virtual offsets cannot
+    /// be obtained anymore.
+    pub fn forget_before(&mut self, offset: u64) {
+        let i = self.blocks.partition_point(|&(start, _)| start <= offset);
+        if i > 1 {
+            self.blocks.drain(..i - 1);
+        }
+    }
+
+    /// Returns a reference to the underlying reader
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the underlying reader
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    // Reads the next block, returns false at EOF
+    fn read_block(&mut self) -> io::Result<bool> {
+        self.data_start += self.data.len() as u64;
+        self.data.clear();
+        self.data_pos = 0;
+        self.block_offset = self.next_block_offset;
+
+        let (bsize, rest) = match self.read_header()? {
+            Some(sizes) => sizes,
+            None => return Ok(false),
+        };
+        self.cdata.clear();
+        self.cdata.resize(rest, 0);
+        self.inner.read_exact(&mut self.cdata)?;
+        self.next_block_offset = self.block_offset + bsize as u64 + 1;
+
+        let (cdata, footer) = self.cdata.split_at(rest - 8);
+        let crc = u32::from_le_bytes([footer[0], footer[1], footer[2], footer[3]]);
+        let isize = u32::from_le_bytes([footer[4], footer[5], footer[6], footer[7]]) as usize;
+        if isize > MAX_BLOCK_SIZE {
+            return Err(invalid_data(
+                "invalid BGZF block: uncompressed size too large",
+            ));
+        }
+        self.data.reserve(isize);
+        self.decompress.reset(false);
+        let status = self
+            .decompress
+            .decompress_vec(cdata, &mut self.data, FlushDecompress::Finish)
+            .map_err(|e| invalid_data(&format!("BGZF decompression error: {}", e)))?;
+        if status != Status::StreamEnd || self.data.len() != isize {
+            return Err(invalid_data("invalid BGZF block: corrupt data"));
+        }
+        let mut c = Crc::new();
+        c.update(&self.data);
+        if c.sum() != crc {
+            return Err(invalid_data("invalid BGZF block: CRC mismatch"));
+        }
+        if !self.data.is_empty() {
+            self.blocks.push_back((self.data_start, self.block_offset));
+            let end = self.data_start + self.data.len() as u64;
+            self.forget_before(end.saturating_sub(self.window_size));
+            self.add_checkpoint(self.data_start, self.block_offset);
+        }
+        Ok(true)
+    }
+
+    // Reads the block header, returns the block size minus 1 (BSIZE) and the
+    // size of the remaining block data, or `None` at EOF
+    fn read_header(&mut self) -> io::Result<Option<(usize, usize)>> {
+        let mut header = [0; HEADER_LEN];
+        if !read_exact_or_eof(&mut self.inner, &mut header)? {
+            return Ok(None);
+        }
+        if header[0] != 0x1f || header[1] != 0x8b || header[2] != 8 || header[3] & 4 == 0 {
+            return Err(invalid_data("invalid BGZF block header"));
+        }
+        let xlen = u16::from_le_bytes([header[10], header[11]]) as usize;
+        self.cdata.clear();
+        self.cdata.resize(xlen, 0);
+        self.inner.read_exact(&mut self.cdata)?;
+        let bsize = find_bsize(&self.cdata)
+            .ok_or_else(|| invalid_data("BGZF block size (BC) field not found"))?
+            as usize;
+        let rest = (bsize + 1)
+            .checked_sub(HEADER_LEN + xlen)
+            .filter(|r| *r >= 8)
+            .ok_or_else(|| invalid_data("invalid BGZF block size"))?;
+        Ok(Some((bsize, rest)))
+    }
+
+    fn add_checkpoint(&mut self, start: u64, block_offset: u64) {
+        let i = self.checkpoints.partition_point(|&(s, _)| s <= start);
+        if i == 0 || start - self.checkpoints[i - 1].0 >= CHECKPOINT_DIST {
+            self.checkpoints.insert(i, (start, block_offset));
+        }
+    }
+
+    // Returns the last known (decompressed offset, block offset) pair,
+    // for which `f` returns true (the file start if there is none)
+    fn known_block<F>(&self, f: F) -> (u64, u64)
+    where
+        F: Fn(&(u64, u64)) -> bool,
+    {
+        let i = self.checkpoints.partition_point(&f);
+        let checkpoint = if i > 0 {
+            self.checkpoints[i - 1]
+        } else {
+            (0, 0)
+        };
+        let i = self.blocks.partition_point(&f);
+        if i > 0 {
+            checkpoint.max(self.blocks[i - 1])
+        } else {
+            checkpoint
+        }
+    }
+}
+
+impl<R: Read + Seek> Reader<R> {
+    /// Seeks to the given virtual offset. The block offsets of recently read
+    /// blocks (see [`virtual_offset_at`](#method.virtual_offset_at)) are
+    /// discarded. If the block was not seen before, the block headers are
+    /// read from the closest known block on in order to determine the offset
+    /// in the decompressed data (`offset()`).
+    pub fn seek_virtual(&mut self, voffset: u64) -> io::Result<()> {
+        let (block_offset, within) = split_virtual_offset(voffset);
+        let (mut start, mut offset) = self.known_block(|&(_, b)| b <= block_offset);
+        while offset < block_offset {
+            match self.block_sizes(offset)? {
+                Some((csize, size)) => {
+                    offset += csize;
+                    start += size;
+                    self.add_checkpoint(start, offset);
+                }
+                None => break,
+            }
+        }
+        if offset != block_offset {
+            return Err(invalid_data("invalid BGZF virtual offset"));
+        }
+        self.load_block(block_offset, start)?;
+        if within as usize > self.data.len() {
+            return Err(invalid_data("invalid BGZF virtual offset"));
+        }
+        self.data_pos = within as usize;
+        Ok(())
+    }
+
+    // Reads the block at the given file offset, which starts at `start` in the
+    // decompressed data
+    fn load_block(&mut self, block_offset: u64, start: u64) -> io::Result<()> {
+        self.inner.seek(io::SeekFrom::Start(block_offset))?;
+        self.data.clear();
+        self.data_pos = 0;
+        self.data_start = start;
+        self.blocks.clear();
+        self.next_block_offset = block_offset;
+        self.read_block()?;
+        Ok(())
+    }
+
+    // Returns the compressed and decompressed size of the block at the given
+    // file offset without decompressing it, or `None` at EOF
+    fn block_sizes(&mut self, block_offset: u64) -> io::Result<Option<(u64, u64)>> {
+        self.inner.seek(io::SeekFrom::Start(block_offset))?;
+        let (bsize, rest) = match self.read_header()? {
+            Some(sizes) => sizes,
+            None => return Ok(None),
+        };
+        self.inner.seek(io::SeekFrom::Current(rest as i64 - 4))?;
+        let mut isize = [0; 4];
+        self.inner.read_exact(&mut isize)?;
+        Ok(Some((bsize as u64 + 1, u32::from_le_bytes(isize) as u64)))
+    }
+}
+
+impl<R: Read + Seek> Seek for Reader<R> {
+    /// Seeks to an offset in the decompressed data. `SeekFrom::End` is
+    /// not supported.
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            io::SeekFrom::Start(o) => Some(o),
+            io::SeekFrom::Current(d) => {
+                let offset = self.offset();
+                if d >= 0 {
+                    offset.checked_add(d as u64)
+                } else {
+                    offset.checked_sub(d.unsigned_abs())
+                }
+            }
+            io::SeekFrom::End(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "BGZF reader: seeking relative to the end is not supported",
+                ))
+            }
+        };
+        let target = target.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative offset",
+            )
+        })?;
+        let end = self.data_start + self.data.len() as u64;
+        if target >= self.data_start && target <= end {
+            self.data_pos = (target - self.data_start) as usize;
+            return Ok(target);
+        }
+        // find the block containing the offset
+        let (mut start, mut offset) = self.known_block(|&(s, _)| s <= target);
+        while let Some((csize, size)) = self.block_sizes(offset)? {
+            if start + size > target {
+                break;
+            }
+            offset += csize;
+            start += size;
+            self.add_checkpoint(start, offset);
+        }
+        self.load_block(offset, start)?;
+        if self.data.is_empty() {
+            // beyond the end of the data
+            self.data_start = target;
+        } else {
+            self.data_pos = (target - start) as usize;
+        }
+        Ok(target)
+    }
+}
+
+impl<R: Read> Read for Reader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.data_pos == self.data.len() {
+            if !self.read_block()? {
+                return Ok(0);
+            }
+        }
+        let n = buf.len().min(self.data.len() - self.data_pos);
+        buf[..n].copy_from_slice(&self.data[self.data_pos..self.data_pos + n]);
+        self.data_pos += n;
+        Ok(n)
+    }
+}
+
+/// BGZF encoder, which compresses the data in the calling thread. The
+/// blocks are at most 64 KiB in size, and `flush()` always ends the current block.
+///
+/// The end-of-file marker is written by [`finish()`](#method.finish), or
+/// when the writer is dropped (ignoring errors).
+pub struct Writer<W: Write> {
+    inner: Option<W>,
+    // uncompressed data of the current block
+    data: Vec<u8>,
+    // compressed block
+    cdata: Vec<u8>,
+    compress: Compress,
+    // file offset of the current block
+    block_offset: u64,
+}
+
+impl Writer<File> {
+    /// Creates a new BGZF compressed file
+    #[inline]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> io::Result<Writer<File>> {
+        File::create(path).map(Writer::new)
+    }
+}
+
+impl<W: Write> Writer<W> {
+    /// Creates a new BGZF encoder with the default compression level (6)
+    #[inline]
+    pub fn new(inner: W) -> Writer<W> {
+        Writer::with_level(inner, DEFAULT_LEVEL)
+    }
+
+    /// Creates a new BGZF encoder with the given compression level (0-9)
+    #[inline]
+    pub fn with_level(inner: W, level: u32) -> Writer<W> {
+        Writer {
+            inner: Some(inner),
+            data: Vec::with_capacity(BLOCK_DATA_SIZE),
+            cdata: Vec::with_capacity(MAX_BLOCK_SIZE),
+            compress: Compress::new(Compression::new(level), false),
+            block_offset: 0,
+        }
+    }
+
+    /// Returns the virtual offset of the next byte to be written
+    #[inline]
+    pub fn virtual_offset(&self) -> u64 {
+        virtual_offset(self.block_offset, self.data.len() as u16)
+    }
+
+    /// Returns a reference to the underlying writer
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        self.inner.as_ref().unwrap()
+    }
+
+    /// Returns a mutable reference to the underlying writer
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        self.inner.as_mut().unwrap()
+    }
+
+    /// Writes the remaining data and the end-of-file marker block, and
+    /// returns the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.write_eof()?;
+        Ok(self.inner.take().unwrap())
+    }
+
+    fn write_block(&mut self) -> io::Result<()> {
+        if self.data.is_empty() {
+            return Ok(());
+        }
+        self.cdata.clear();
+        compress_block(&mut self.compress, &self.data, &mut self.cdata)?;
+        self.inner.as_mut().unwrap().write_all(&self.cdata)?;
+        self.block_offset += self.cdata.len() as u64;
+        self.data.clear();
+        Ok(())
+    }
+
+    fn write_eof(&mut self) -> io::Result<()> {
+        self.write_block()?;
+        let inner = self.inner.as_mut().unwrap();
+        inner.write_all(&EOF_BLOCK)?;
+        inner.flush()
+    }
+}
+
+impl<W: Write> Write for Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.data.len() == BLOCK_DATA_SIZE {
+            self.write_block()?;
+        }
+        let n = buf.len().min(BLOCK_DATA_SIZE - self.data.len());
+        self.data.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.write_block()?;
+        self.inner.as_mut().unwrap().flush()
+    }
+}
+
+impl<W: Write> Drop for Writer<W> {
+    fn drop(&mut self) {
+        if self.inner.is_some() {
+            let _ = self.write_eof();
+        }
+    }
+}
+
+// Message to the thread of `ParallelWriter`
+enum BlockMsg {
+    // block of data to compress
+    Data(Vec<u8>),
+    // flush the output, the sender is notified when done (and dropped
+    // without notification if an error occurred)
+    Flush(mpsc::Sender<()>),
+}
+
+/// BGZF encoder, which compresses the blocks in a thread pool.
+///
+/// The data is collected in blocks of at most 64 KiB, which are passed to
+/// a background thread that owns the underlying writer. This thread distributes
+/// the blocks to a pool of worker threads for compression, and writes
+/// the compressed blocks in the original order.
+///
+/// Since writing happens in the background, an error may only be returned
+/// by a later call to `write()`, or by [`finish()`](#method.finish). `flush()`
+/// ends the current block and waits until the background thread has written
+/// all pending blocks and flushed the underlying writer, returning any error
+/// that occurred.
+/// The end-of-file marker is written by `finish()`, or when the writer is
+/// dropped (ignoring errors).
+pub struct ParallelWriter<W: Write + Send + 'static> {
+    data: Vec<u8>,
+    tx: Option<mpsc::SyncSender<BlockMsg>>,
+    // used buffers returned from the background thread
+    recycle_rx: mpsc::Receiver<Vec<u8>>,
+    handle: Option<thread::JoinHandle<io::Result<W>>>,
+}
+
+impl ParallelWriter<File> {
+    /// Creates a new BGZF compressed file using `n_threads` compression threads
+    #[inline]
+    pub fn from_path<P: AsRef<Path>>(path: P, n_threads: u32) -> io::Result<ParallelWriter<File>> {
+        File::create(path).map(|f| ParallelWriter::new(f, n_threads))
+    }
+}
+
+impl<W: Write + Send + 'static> ParallelWriter<W> {
+    /// Creates a new BGZF encoder using `n_threads` compression threads
+    /// and the default compression level (6)
+    #[inline]
+    pub fn new(inner: W, n_threads: u32) -> ParallelWriter<W> {
+        ParallelWriter::with_level(inner, n_threads, DEFAULT_LEVEL)
+    }
+
+    /// Creates a new BGZF encoder using `n_threads` compression threads
+    /// and the given compression level (0-9)
+    pub fn with_level(inner: W, n_threads: u32, level: u32) -> ParallelWriter<W> {
+        assert!(n_threads > 0);
+        let (tx, rx) = mpsc::sync_channel(n_threads as usize);
+        let (recycle_tx, recycle_rx) = mpsc::channel();
+        let handle = thread::spawn(move || {
+            compress_parallel(inner, n_threads, Compression::new(level), rx, recycle_tx)
+        });
+        ParallelWriter {
+            data: Vec::with_capacity(BLOCK_DATA_SIZE),
+            tx: Some(tx),
+            recycle_rx,
+            handle: Some(handle),
+        }
+    }
+
+    /// Writes the remaining data and the end-of-file marker block, waits
+    /// for the background thread to finish and returns the underlying writer
+    pub fn finish(mut self) -> io::Result<W> {
+        self.send_block()?;
+        self.join()
+    }
+
+    fn send_block(&mut self) -> io::Result<()> {
+        if self.data.is_empty() {
+            return Ok(());
+        }
+        let mut buf = self.recycle_rx.try_recv().unwrap_or_default();
+        buf.clear();
+        let data = mem::replace(&mut self.data, buf);
+        self.send(BlockMsg::Data(data))
+    }
+
+    fn send(&mut self, msg: BlockMsg) -> io::Result<()> {
+        let res = match self.tx.as_ref() {
+            Some(tx) => tx.send(msg),
+            None => return Err(stopped()),
+        };
+        if res.is_err() {
+            // the background thread stopped due to an error
+            return Err(self.join().err().unwrap_or_else(stopped));
+        }
+        Ok(())
+    }
+
+    fn join(&mut self) -> io::Result<W> {
+        self.tx = None;
+        match self.handle.take() {
+            Some(handle) => handle
+                .join()
+                .unwrap_or_else(|e| std::panic::resume_unwind(e)),
+            None => Err(stopped()),
+        }
+    }
+}
+
+impl<W: Write + Send + 'static> Write for ParallelWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.data.len() == BLOCK_DATA_SIZE {
+            self.send_block()?;
+        }
+        if self.data.capacity() < BLOCK_DATA_SIZE {
+            self.data.reserve_exact(BLOCK_DATA_SIZE - self.data.len());
+        }
+        let n = buf.len().min(BLOCK_DATA_SIZE - self.data.len());
+        self.data.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.send_block()?;
+        let (done_tx, done_rx) = mpsc::channel();
+        self.send(BlockMsg::Flush(done_tx))?;
+        if done_rx.recv().is_err() {
+            // the background thread stopped due to an error
+            return Err(self.join().err().unwrap_or_else(stopped));
+        }
+        Ok(())
+    }
+}
+
+impl<W: Write + Send + 'static> Drop for ParallelWriter<W> {
+    fn drop(&mut self) {
+        if self.handle.is_some() {
+            let _ = self.send_block();
+            let _ = self.join();
+        }
+    }
+}
+
+fn stopped() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "BGZF writer stopped after an error")
+}
+
+// Compressed block (or error) sent back by the compression threads,
+// together with the uncompressed buffer for reuse
+type DoneMsg = (u64, io::Result<Vec<u8>>, Vec<u8>);
+
+// Runs in the background thread of `ParallelWriter`
+fn compress_parallel<W: Write>(
+    mut inner: W,
+    n_threads: u32,
+    level: Compression,
+    rx: mpsc::Receiver<BlockMsg>,
+    recycle_tx: mpsc::Sender<Vec<u8>>,
+) -> io::Result<W> {
+    let queue_len = 2 * n_threads as u64;
+    let compressors = Mutex::new(vec![]);
+    let (done_tx, done_rx) = mpsc::channel::<DoneMsg>();
+    let mut pool = scoped_threadpool::Pool::new(n_threads);
+
+    pool.scoped(|scope| -> io::Result<()> {
+        let compressors = &compressors;
+        let mut sent = 0;
+        let mut written = 0;
+        let mut done = BTreeMap::new();
+
+        // Receives one compressed block, and writes all blocks that are next in order
+        let mut write_next = |written: &mut u64, inner: &mut W| -> io::Result<()> {
+            let (i, cdata, data) = done_rx.recv().unwrap();
+            done.insert(i, cdata);
+            recycle_tx.send(data).ok();
+            while let Some(cdata) = done.remove(written) {
+                inner.write_all(&cdata?)?;
+                *written += 1;
+            }
+            Ok(())
+        };
+
+        for msg in rx {
+            match msg {
+                BlockMsg::Data(data) => {
+                    while sent - written >= queue_len {
+                        write_next(&mut written, &mut inner)?;
+                    }
+                    let done_tx = done_tx.clone();
+                    let i = sent;
+                    scope.execute(move || {
+                        let mut compress = compressors
+                            .lock()
+                            .unwrap()
+                            .pop()
+                            .unwrap_or_else(|| Compress::new(level, false));
+                        let mut cdata = Vec::with_capacity(MAX_BLOCK_SIZE);
+                        let res = compress_block(&mut compress, &data, &mut cdata).map(|_| cdata);
+                        compressors.lock().unwrap().push(compress);
+                        done_tx.send((i, res, data)).ok();
+                    });
+                    sent += 1;
+                }
+                BlockMsg::Flush(done) => {
+                    while written < sent {
+                        write_next(&mut written, &mut inner)?;
+                    }
+                    inner.flush()?;
+                    done.send(()).ok();
+                }
+            }
+        }
+        while written < sent {
+            write_next(&mut written, &mut inner)?;
+        }
+        Ok(())
+    })?;
+
+    inner.write_all(&EOF_BLOCK)?;
+    inner.flush()?;
+    Ok(inner)
+}
+
+// Compresses the data (at most `BLOCK_DATA_SIZE` bytes) into a BGZF block,
+// which is appended to `out`
+fn compress_block(compress: &mut Compress, data: &[u8], out: &mut Vec<u8>) -> io::Result<()> {
+    debug_assert!(data.len() <= BLOCK_DATA_SIZE);
+    let start = out.len();
+    // header with a BC extra field (BSIZE is filled in later)
+    out.extend_from_slice(&[
+        0x1f, 0x8b, 8, 4, 0, 0, 0, 0, 0, 0xff, 6, 0, b'B', b'C', 2, 0, 0, 0,
+    ]);
+    out.reserve(MAX_BLOCK_SIZE);
+    compress.reset();
+    let status = compress
+        .compress_vec(data, out, FlushCompress::Finish)
+        .map_err(|e| io::Error::new(io::ErrorKind::Other, e))?;
+    if status != Status::StreamEnd {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            "BGZF compression error: block too large",
+        ));
+    }
+    let mut crc = Crc::new();
+    crc.update(data);
+    out.extend_from_slice(&crc.sum().to_le_bytes());
+    out.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    let bsize = out.len() - start - 1;
+    if bsize >= MAX_BLOCK_SIZE {
+        return Err(io::Error::new(
+            io::ErrorKind::Other,
+            "BGZF compression error: block too large",
+        ));
+    }
+    out[start + 16..start + 18].copy_from_slice(&(bsize as u16).to_le_bytes());
+    Ok(())
+}
+
+fn find_bsize(extra: &[u8]) -> Option<u16> {
+    let mut rest = extra;
+    while rest.len() >= 4 {
+        let slen = u16::from_le_bytes([rest[2], rest[3]]) as usize;
+        let field = rest.get(4..4 + slen)?;
+        if rest[0] == b'B' && rest[1] == b'C' && slen == 2 {
+            return Some(u16::from_le_bytes([field[0], field[1]]));
+        }
+        rest = &rest[4 + slen..];
+    }
+    None
+}
+
+// Reads the whole buffer, returns false if EOF is reached immediately
+fn read_exact_or_eof<R: Read>(reader: &mut R, buf: &mut [u8]) -> io::Result<bool> {
+    let mut n = 0;
+    while n < buf.len() {
+        match reader.read(&mut buf[n..]) {
+            Ok(0) if n == 0 => return Ok(false),
+            Ok(0) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "truncated BGZF block",
+                ))
+            }
+            Ok(k) => n += k,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(true)
+}
+
+fn invalid_data(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
@@ -0,0 +1,452 @@
+//! Transparent reading of compressed input.
+//!
+//! The decoders are each enabled by a cargo feature:
+//!
+//! | format | feature |
+//! |--------|---------|
+//! | gzip (including BGZF) | `gzip` |
+//! | BGZF (`.bgz` files) | `bgzf` |
+//! | bzip2 | `bzip2` |
+//! | xz | `xz` |
+//! | zstd | `zstd` |
+//!
+//! The `from_path_auto()` constructors of
+//! [`fasta::Reader`](../fasta/struct.Reader.html#method.from_path_auto) and
+//! [`fastq::Reader`](../fastq/struct.Reader.html#method.from_path_auto)
+//! detect the compression format from the first bytes of the file
+//! (the file extension is not considered).
+//! If the required feature is not enabled, an error of kind
+//! `io::ErrorKind::Unsupported` is returned.
+//!
+//! Compressed data can also be read from any other source (such as `stdin`), either
+//! with [`auto_reader`](fn.auto_reader.html) or using the format-specific constructors
+//! such as `fastq::Reader::from_gzip()`.
+//!
+//! ```no_run
+//! use seq_io::fastq::{Reader, Record};
+//!
+//! let mut reader = Reader::from_path_auto("seqs.fastq.gz").unwrap();
+//!
+//! while let Some(record) = reader.next() {
+//!     println!("{}", record.unwrap().id().unwrap());
+//! }
+//! ```
+//!
+//! # Compressed output
+//!
+//! [`Encoder`](enum.Encoder.html) compresses the output of the FASTA and FASTQ
+//! writers in the same formats (with the same features), as well as in the BGZF
+//! format (`bgzf` feature, see also [`bgzf::ParallelWriter`](../bgzf/struct.ParallelWriter.html)
+//! for multithreaded compression). `Writer::from_path_auto()` chooses
+//! the format based on the file extension (see [`Format::from_path`](enum.Format.html#method.from_path));
+//! `Writer::from_path_auto_bgzf()` writes `.gz` files in the BGZF format instead
+//! of plain gzip. The compressed stream has to be completed with `Writer::finish()`:
+//!
+//! ```no_run
+//! use seq_io::fastq::{Reader, Writer};
+//!
+//! let mut reader = Reader::from_path_auto("seqs.fastq.gz").unwrap();
+//! let mut writer = Writer::from_path_auto("filtered.fastq.zst").unwrap();
+//!
+//! while let Some(record) = reader.next() {
+//!     writer.write_record(&record.unwrap()).unwrap();
+//! }
+//! writer.finish().unwrap();
+//! ```
+
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+/// Compression format of the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// No (known) compression
+    Plain,
+    Gzip,
+    /// BGZF (blocked gzip), which is a variant of gzip. `detect()` does not
+    /// distinguish it from gzip, but `from_path()` returns it for `.bgz` files
+    /// if the `bgzf` feature is enabled.
+    Bgzf,
+    Bzip2,
+    Xz,
+    Zstd,
+}
+
+/// Number of bytes required to detect all formats
+const MAGIC_LEN: usize = 6;
+
+impl Format {
+    /// Determines the compression format from the magic bytes at the start of the input.
+    /// `Format::Plain` is returned if the format is not recognized.
+    pub fn detect(magic: &[u8]) -> Format {
+        if magic.starts_with(&[0x1f, 0x8b]) {
+            Format::Gzip
+        } else if magic.starts_with(b"BZh") {
+            Format::Bzip2
+        } else if magic.starts_with(&[0xfd, b'7', b'z', b'X', b'Z', 0x00]) {
+            Format::Xz
+        } else if magic.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Format::Zstd
+        } else {
+            Format::Plain
+        }
+    }
+
+    /// Determines the compression format from the extension of a file name
+    /// (`.gz`, `.bgz`, `.bz2`, `.xz` or `.zst`). `Format::Plain` is returned
+    /// for all other extensions. `.bgz` files are `Format::Bgzf` if the `bgzf`
+    /// feature is enabled, and `Format::Gzip` otherwise.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Format {
+        match path.as_ref().extension().and_then(|e| e.to_str()) {
+            Some("gz") => Format::Gzip,
+            #[cfg(feature = "bgzf")]
+            Some("bgz") => Format::Bgzf,
+            #[cfg(not(feature = "bgzf"))]
+            Some("bgz") => Format::Gzip,
+            Some("bz2") => Format::Bzip2,
+            Some("xz") => Format::Xz,
+            Some("zst") => Format::Zstd,
+            _ => Format::Plain,
+        }
+    }
+
+    /// Returns the name of the cargo feature required for reading the format
+    /// (`None` for plain input).
+    pub fn feature(self) -> Option<&'static str> {
+        match self {
+            Format::Plain => None,
+            Format::Gzip => Some("gzip"),
+            Format::Bgzf => Some("bgzf"),
+            Format::Bzip2 => Some("bzip2"),
+            Format::Xz => Some("xz"),
+            Format::Zstd => Some("zstd"),
+        }
+    }
+}
+
+impl fmt::Display for Format {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match *self {
+            Format::Plain => "uncompressed",
+            Format::Gzip => "gzip",
+            Format::Bgzf => "BGZF",
+            Format::Bzip2 => "bzip2",
+            Format::Xz => "xz",
+            Format::Zstd => "zstd",
+        };
+        f.write_str(s)
+    }
+}
+
+/// Boxed reader returned by [`auto_reader`](fn.auto_reader.html) and
+/// the `from_path_auto()` constructors.
+pub type AutoReader = Box<dyn Read + Send>;
+
+/// Detects the compression format of the input and wraps it in the appropriate decoder.
+/// The first bytes are read for detecting the format, they are not lost but
+/// passed on to the decoder.
+pub fn auto_reader<R>(reader: R) -> io::Result<AutoReader>
+where
+    R: Read + Send + 'static,
+{
+    let (format, reader) = detect(reader)?;
+    decoder(reader, format)
+}
+
+/// Reader returned by [`detect`](fn.detect.html), which yields the bytes read
+/// for detecting the format, followed by the rest of the input.
+pub type Peeked<R> = io::Chain<io::Cursor<Vec<u8>>, R>;
+
+/// Reads the first bytes of the input and determines the compression format.
+/// Returns the format along with a reader that yields the complete input,
+/// including the bytes already read.
+pub fn detect<R: Read>(mut reader: R) -> io::Result<(Format, Peeked<R>)> {
+    let mut magic = vec![0; MAGIC_LEN];
+    let mut n = 0;
+    while n < MAGIC_LEN {
+        match reader.read(&mut magic[n..]) {
+            Ok(0) => break,
+            Ok(k) => n += k,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    magic.truncate(n);
+    let format = Format::detect(&magic);
+    Ok((format, io::Cursor::new(magic).chain(reader)))
+}
+
+/// Wraps a reader in the decoder for the given format.
+/// Returns an error if support for the format is not enabled.
+pub fn decoder<R>(reader: R, format: Format) -> io::Result<AutoReader>
+where
+    R: Read + Send + 'static,
+{
+    Ok(match format {
+        Format::Plain => Box::new(reader),
+        #[cfg(feature = "gzip")]
+        Format::Gzip => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+        #[cfg(feature = "bgzf")]
+        Format::Bgzf => Box::new(crate::bgzf::Reader::new(reader)),
+        #[cfg(feature = "bzip2")]
+        Format::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+        #[cfg(feature = "xz")]
+        Format::Xz => Box::new(xz2::read::XzDecoder::new_multi_decoder(reader)),
+        #[cfg(feature = "zstd")]
+        Format::Zstd => Box::new(zstd::stream::read::Decoder::new(reader)?),
+        #[allow(unreachable_patterns)]
+        _ => return Err(unsupported(format)),
+    })
+}
+
+// Returns an error if support for the format is not enabled
+pub(crate) fn check_supported(format: Format) -> io::Result<()> {
+    let enabled = match format {
+        Format::Plain => true,
+        Format::Gzip => cfg!(feature = "gzip"),
+        Format::Bgzf => cfg!(feature = "bgzf"),
+        Format::Bzip2 => cfg!(feature = "bzip2"),
+        Format::Xz => cfg!(feature = "xz"),
+        Format::Zstd => cfg!(feature = "zstd"),
+    };
+    if enabled {
+        Ok(())
+    } else {
+        Err(unsupported(format))
+    }
+}
+
+fn unsupported(format: Format) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "{} compression is not supported, the '{}' feature must be enabled",
+            format,
+            format.feature().unwrap_or_default()
+        ),
+    )
+}
+
+/// Encoder of compressed output, which can be used with the FASTA and FASTQ
+/// writers. After writing, [`finish()`](#method.finish) has to be called
+/// in order to complete the compressed stream.
+pub enum Encoder<W: Write> {
+    /// Uncompressed output
+    Plain(W),
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::write::GzEncoder<W>),
+    #[cfg(feature = "bgzf")]
+    Bgzf(crate::bgzf::Writer<W>),
+    #[cfg(feature = "bzip2")]
+    Bzip2(bzip2::write::BzEncoder<W>),
+    #[cfg(feature = "xz")]
+    Xz(xz2::write::XzEncoder<W>),
+    #[cfg(feature = "zstd")]
+    Zstd(zstd::stream::write::Encoder<'static, W>),
+}
+
+impl<W: Write> Encoder<W> {
+    /// Wraps a writer in the encoder for the given format. The compression
+    /// level is the format-specific default if `None`.
+    /// Returns an error if support for the format is not enabled.
+    #[allow(unused_variables)]
+    pub fn new(writer: W, format: Format, level: Option<u32>) -> io::Result<Encoder<W>> {
+        Ok(match format {
+            Format::Plain => Encoder::Plain(writer),
+            #[cfg(feature = "gzip")]
+            Format::Gzip => Encoder::Gzip(flate2::write::GzEncoder::new(
+                writer,
+                level.map(flate2::Compression::new).unwrap_or_default(),
+            )),
+            #[cfg(feature = "bgzf")]
+            Format::Bgzf => Encoder::bgzf(writer, level),
+            #[cfg(feature = "bzip2")]
+            Format::Bzip2 => Encoder::Bzip2(bzip2::write::BzEncoder::new(
+                writer,
+                level.map(bzip2::Compression::new).unwrap_or_default(),
+            )),
+            #[cfg(feature = "xz")]
+            Format::Xz => Encoder::Xz(xz2::write::XzEncoder::new(writer, level.unwrap_or(6))),
+            #[cfg(feature = "zstd")]
+            Format::Zstd => Encoder::Zstd(zstd::stream::write::Encoder::new(
+                writer,
+                level.unwrap_or(0) as i32,
+            )?),
+            #[allow(unreachable_patterns)]
+            _ => return Err(unsupported(format)),
+        })
+    }
+
+    /// Creates a BGZF encoder (requires the `bgzf` feature), see also
+    /// [`bgzf::Writer`](../bgzf/struct.Writer.html).
+    #[cfg(feature = "bgzf")]
+    pub fn bgzf(writer: W, level: Option<u32>) -> Encoder<W> {
+        Encoder::Bgzf(crate::bgzf::Writer::with_level(writer, level.unwrap_or(6)))
+    }
+
+    /// Completes the compressed stream and returns the underlying writer
+    pub fn finish(self) -> io::Result<W> {
+        match self {
+            Encoder::Plain(w) => Ok(w),
+            #[cfg(feature = "gzip")]
+            Encoder::Gzip(w) => w.finish(),
+            #[cfg(feature = "bgzf")]
+            Encoder::Bgzf(w) => w.finish(),
+            #[cfg(feature = "bzip2")]
+            Encoder::Bzip2(w) => w.finish(),
+            #[cfg(feature = "xz")]
+            Encoder::Xz(w) => w.finish(),
+            #[cfg(feature = "zstd")]
+            Encoder::Zstd(w) => w.finish(),
+        }
+    }
+}
+
+impl<W: Write> Write for Encoder<W> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match *self {
+            Encoder::Plain(ref mut w) => w.write(buf),
+            #[cfg(feature = "gzip")]
+            Encoder::Gzip(ref mut w) => w.write(buf),
+            #[cfg(feature = "bgzf")]
+            Encoder::Bgzf(ref mut w) => w.write(buf),
+            #[cfg(feature = "bzip2")]
+            Encoder::Bzip2(ref mut w) => w.write(buf),
+            #[cfg(feature = "xz")]
+            Encoder::Xz(ref mut w) => w.write(buf),
+            #[cfg(feature = "zstd")]
+            Encoder::Zstd(ref mut w) => w.write(buf),
+        }
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        match *self {
+            Encoder::Plain(ref mut w) => w.flush(),
+            #[cfg(feature = "gzip")]
+            Encoder::Gzip(ref mut w) => w.flush(),
+            #[cfg(feature = "bgzf")]
+            Encoder::Bgzf(ref mut w) => w.flush(),
+            #[cfg(feature = "bzip2")]
+            Encoder::Bzip2(ref mut w) => w.flush(),
+            #[cfg(feature = "xz")]
+            Encoder::Xz(ref mut w) => w.flush(),
+            #[cfg(feature = "zstd")]
+            Encoder::Zstd(ref mut w) => w.flush(),
+        }
+    }
+}
+
+/// Implements the `from_path_auto()` and format-specific constructors
+/// for the FASTA and FASTQ readers.
+macro_rules! compressed_reader_impl {
+    () => {
+        impl Reader<$crate::compression::AutoReader> {
+            /// Creates a reader from a file path, which may be compressed.
+            /// The compression format is detected from the first bytes of the file.
+            /// See the [`compression`](../compression/index.html) module for the
+            /// supported formats and the required cargo features.
+            #[inline]
+            pub fn from_path_auto<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+                Self::from_path_auto_with_capacity(path, BUFSIZE)
+            }
+
+            /// Creates a reader from a file path (which may be compressed)
+            /// with a given (initial) buffer capacity.
+            #[inline]
+            pub fn from_path_auto_with_capacity<P: AsRef<Path>>(
+                path: P,
+                cap: usize,
+            ) -> io::Result<Self> {
+                let rdr = $crate::compression::auto_reader(File::open(path)?)?;
+                Ok(Reader::with_capacity(rdr, cap))
+            }
+        }
+
+        #[cfg(feature = "gzip")]
+        impl<R: io::Read> Reader<flate2::read::MultiGzDecoder<R>> {
+            /// Creates a reader of gzip compressed input (requires the `gzip` feature).
+            /// Multi-member files (including BGZF) are read until the end.
+            #[inline]
+            pub fn from_gzip(reader: R) -> Self {
+                Reader::new(flate2::read::MultiGzDecoder::new(reader))
+            }
+        }
+
+        #[cfg(feature = "bzip2")]
+        impl<R: io::Read> Reader<bzip2::read::MultiBzDecoder<R>> {
+            /// Creates a reader of bzip2 compressed input (requires the `bzip2` feature).
+            #[inline]
+            pub fn from_bzip2(reader: R) -> Self {
+                Reader::new(bzip2::read::MultiBzDecoder::new(reader))
+            }
+        }
+
+        #[cfg(feature = "xz")]
+        impl<R: io::Read> Reader<xz2::read::XzDecoder<R>> {
+            /// Creates a reader of xz compressed input (requires the `xz` feature).
+            #[inline]
+            pub fn from_xz(reader: R) -> Self {
+                Reader::new(xz2::read::XzDecoder::new_multi_decoder(reader))
+            }
+        }
+
+        #[cfg(feature = "zstd")]
+        impl<R: io::Read> Reader<zstd::stream::read::Decoder<'static, io::BufReader<R>>> {
+            /// Creates a reader of zstd compressed input (requires the `zstd` feature).
+            #[inline]
+            pub fn from_zstd(reader: R) -> io::Result<Self> {
+                zstd::stream::read::Decoder::new(reader).map(Reader::new)
+            }
+        }
+    };
+}
+
+/// Implements `from_path_auto()` and `finish()` for the FASTA and FASTQ writers.
+macro_rules! compressed_writer_impl {
+    () => {
+        impl Writer<$crate::compression::Encoder<File>> {
+            /// Creates a writer to a new file, which is compressed according to the
+            /// file extension (see [`compression::Format::from_path`](../compression/enum.Format.html#method.from_path)).
+            /// `.gz` files are written in the gzip format, `.bgz` files in the BGZF
+            /// format if the `bgzf` feature is enabled (gzip otherwise).
+            /// Returns an error if support for the format is not enabled.
+            /// [`finish()`](#method.finish) has to be called after writing.
+            pub fn from_path_auto<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+                let format = $crate::compression::Format::from_path(&path);
+                Self::from_path_format(path, format)
+            }
+
+            /// Like [`from_path_auto()`](#method.from_path_auto), but `.gz` files
+            /// are written in the BGZF format, which can be read by all gzip
+            /// decoders, but also allows random access (requires the `bgzf` feature).
+            #[cfg(feature = "bgzf")]
+            pub fn from_path_auto_bgzf<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+                let format = match $crate::compression::Format::from_path(&path) {
+                    $crate::compression::Format::Gzip => $crate::compression::Format::Bgzf,
+                    f => f,
+                };
+                Self::from_path_format(path, format)
+            }
+
+            fn from_path_format<P: AsRef<Path>>(
+                path: P,
+                format: $crate::compression::Format,
+            ) -> io::Result<Self> {
+                $crate::compression::check_supported(format)?;
+                let encoder = $crate::compression::Encoder::new(File::create(path)?, format, None)?;
+                Ok(Writer::new(encoder))
+            }
+        }
+
+        impl<W: io::Write> Writer<$crate::compression::Encoder<W>> {
+            /// Flushes the buffer, completes the compressed stream and returns
+            /// the underlying writer.
+            pub fn finish(self) -> io::Result<W> {
+                self.into_inner()?.finish()
+            }
+        }
+    };
+}
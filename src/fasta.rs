@@ -31,14 +31,17 @@
 //! # Details on parsing behaviour
 //!
 //! * The parser handles UNIX (LF) and Windows (CRLF) line endings, but not old
-//!   Mac-style (CR) endings. However, FASTA writing currently always uses UNIX
-//!   line endings.
+//!   Mac-style (CR) endings. The record `write` methods and writing functions
+//!   always use UNIX line endings, [`Writer`](struct.Writer.html) can be configured
+//!   to use Windows line endings.
 //! * Empty lines are allowed anywhere in the file, they will just be ignored.
 //!   The first non-empty line must start with `>`, indicating the first header.
 //! * Whitespace at the end of header and sequence lines is never removed.
 //! * If two consecutive FASTA header lines (starting with `>`) are encountered
 //!   without intermediate sequence line, the first record will have an empty
 //!   sequence. The same is true if the input ends with a header line.
+//!   Such records can be turned into errors with
+//!   [`Reader::set_allow_empty`](struct.Reader.html#method.set_allow_empty).
 //! * Empty input will result in `None` being returned immediately by
 //!   `fasta::Reader::next()` and in empty iterators for `RecordsIter` /
 //!   `RecordsIntoIter`.
@@ -55,11 +58,22 @@ use std::slice;
 use std::str::{self, Utf8Error};
 
 use buffer_redux;
-use memchr::Memchr;
+use memchr::{memchr, memchr_iter, memrchr, Memchr};
 
+use super::header::HeaderConfig;
 use super::policy::{BufPolicy, StdPolicy};
+use super::recovery::{Recovery, RecoveryStats, Skipped};
+use super::validation::Validation;
+use super::writer::{Head, WriterBase};
 use super::*;
 
+pub use super::ErrorPosition;
+
+pub mod index;
+mod slice_reader;
+
+pub use self::slice_reader::{SliceReader, SliceRecord, SliceSeqLines};
+
 type DefaultPolicy = StdPolicy;
 
 const BUFSIZE: usize = 64 * 1024;
@@ -81,6 +95,10 @@ enum State {
     /// This occurs after `seek()`. Opposite to `Incomplete`, parsing of the
     /// current record has not started.
     Positioned,
+    /// The sequence of the current record is read in chunks after `next_stream()`.
+    /// `search_pos` points to the next unread byte, and `buf_pos.start`
+    /// corresponds to the input offset `position.byte + stream.bytes`.
+    Streaming,
     /// Parsing finished
     Finished,
 }
@@ -90,9 +108,33 @@ pub struct Reader<R: io::Read, P = DefaultPolicy> {
     buf_reader: buffer_redux::BufReader<R>,
     buf_pos: BufferPosition,
     position: Position,
+    // index of the current record
     search_pos: usize,
     state: State,
     buf_policy: P,
+    allow_empty: bool,
+    validation: Option<Validation>,
+    header: HeaderConfig,
+    recovery: Option<Recovery<Error>>,
+    stream: StreamState,
+    last_head: LastHead,
+}
+
+// Header and progress of a record read with `next_stream()`
+#[derive(Debug, Default)]
+struct StreamState {
+    head: Vec<u8>,
+    // number of line breaks and bytes of the record, which were already
+    // consumed from the buffer
+    lines: u64,
+    bytes: u64,
+}
+
+impl StreamState {
+    fn reset(&mut self) {
+        self.lines = 0;
+        self.bytes = 0;
+    }
 }
 
 impl<R> Reader<R, DefaultPolicy>
@@ -133,6 +175,12 @@ where
             search_pos: 0,
             state: State::New,
             buf_policy: StdPolicy,
+            allow_empty: true,
+            validation: None,
+            header: HeaderConfig::default(),
+            recovery: None,
+            last_head: LastHead::default(),
+            stream: StreamState::default(),
         }
     }
 }
@@ -167,6 +215,10 @@ impl Reader<File, DefaultPolicy> {
     }
 }
 
+compressed_reader_impl!();
+
+paired_reader_impl!(DefaultPolicy);
+
 impl<R, P> Reader<R, P>
 where
     R: io::Read,
@@ -182,9 +234,69 @@ where
             search_pos: self.search_pos,
             state: self.state,
             buf_policy: policy,
+            allow_empty: self.allow_empty,
+            validation: self.validation,
+            header: self.header,
+            recovery: self.recovery,
+            last_head: self.last_head,
+            stream: self.stream,
         }
     }
 
+    /// Sets whether records without sequence are allowed (default: true).
+    /// If set to false, `Error::EmptyRecord` is returned for a header line
+    /// that is directly followed by another header, and `Error::UnexpectedEnd`
+    /// if the input ends with a header line. Empty lines are ignored.
+    #[inline]
+    pub fn set_allow_empty(mut self, allow: bool) -> Self {
+        self.allow_empty = allow;
+        self
+    }
+
+    /// Sets how the header line is split into ID and description (see the
+    /// [`header`](../header/index.html) module). The settings apply to records
+    /// returned by `next()` as well as records in a `RecordSet`.
+    #[inline]
+    pub fn set_header_config(mut self, config: HeaderConfig) -> Self {
+        self.header = config;
+        self
+    }
+
+    /// Enables validation of the records with the given settings (see the
+    /// [`validation`](../validation/index.html) module).
+    #[inline]
+    pub fn set_validation(mut self, validation: Validation) -> Self {
+        self.validation = Some(validation);
+        self
+    }
+
+    /// Enables recovery from malformed input (see the
+    /// [`recovery`](../recovery/index.html) module). If the first non-empty
+    /// line does not start with `>`, all lines before the first header are
+    /// skipped and passed to `handler` together with the `InvalidStart` error,
+    /// which is not returned anymore. Records without sequence are
+    /// skipped in the same way if they are not allowed
+    /// (see [`set_allow_empty`](#method.set_allow_empty)), as well as records
+    /// failing [validation](#method.set_validation).
+    #[inline]
+    pub fn set_recovery<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(Skipped<Error>) + Send + Sync + 'static,
+    {
+        self.recovery = Some(Recovery::new(Box::new(handler)));
+        self
+    }
+
+    /// Returns the number of errors recovered from and the number of skipped
+    /// bytes so far. These are always zero if recovery is not enabled.
+    #[inline]
+    pub fn recovery_stats(&self) -> RecoveryStats {
+        self.recovery
+            .as_ref()
+            .map(|r| r.stats())
+            .unwrap_or_default()
+    }
+
     /// Returns the `BufPolicy` of the reader
     #[inline]
     pub fn policy(&self) -> &P {
@@ -208,7 +320,33 @@ where
     /// ```
     #[allow(clippy::should_implement_trait)]
     #[inline]
-    pub fn next(&mut self) -> Option<Result<RefRecord, Error>> {
+    pub fn next(&mut self) -> Option<Result<RefRecord<'_>, Error>> {
+        loop {
+            try_opt!(self.find_next()?);
+            match self.check_record() {
+                Ok(()) => break,
+                Err(e) => {
+                    try_opt!(self.skip_invalid(e));
+                    if self.state != State::Finished {
+                        self.state = State::Positioned;
+                    }
+                }
+            }
+        }
+
+        Some(Ok(RefRecord {
+            buffer: self.get_buf(),
+            buf_pos: &self.buf_pos,
+            header: self.header,
+        }))
+    }
+
+    // Searches the next record, to which `buf_pos` points afterwards
+    #[inline]
+    fn find_next(&mut self) -> Option<Result<(), Error>> {
+        if self.state == State::Streaming {
+            try_opt!(self.skip_stream());
+        }
         // after next(), the state is always Parsing or Finished
         match self.state {
             State::New => {
@@ -227,6 +365,7 @@ where
                 self.increment_record();
             }
             State::Incomplete => {}
+            State::Streaming => unreachable!(),
         };
 
         if self.state != State::Incomplete {
@@ -242,10 +381,188 @@ where
             }
         }
 
-        Some(Ok(RefRecord {
-            buffer: self.get_buf(),
-            buf_pos: &self.buf_pos,
-        }))
+        Some(Ok(()))
+    }
+
+    /// Returns the next record as [`SeqStream`](struct.SeqStream.html), which
+    /// provides the header and reads the sequence in chunks. Only the header
+    /// line has to fit into the buffer, sequences of any size can be read with
+    /// memory usage bounded by the buffer capacity. If the sequence is not
+    /// read to the end, the rest is skipped by the next call to `next_stream()`,
+    /// `next()` or `read_record_set()`.
+    ///
+    /// [Validation](#method.set_validation) and
+    /// [`set_allow_empty(false)`](#method.set_allow_empty) do not apply to
+    /// streamed records.
+    ///
+    /// # Example:
+    ///
+    /// ```
+    /// use seq_io::fasta::{Reader, Record};
+    ///
+    /// let fasta = b">chr1
+    /// ACGT
+    /// ACGT
+    /// >chr2
+    /// TGCA
+    /// ";
+    ///
+    /// // small buffer capacity
+    /// let mut reader = Reader::with_capacity(&fasta[..], 8);
+    ///
+    /// let mut stream = reader.next_stream().unwrap().unwrap();
+    /// assert_eq!(stream.head(), b"chr1");
+    /// let mut seq_len = 0;
+    /// while let Some(chunk) = stream.next_chunk() {
+    ///     seq_len += chunk.unwrap().len();
+    /// }
+    /// assert_eq!(seq_len, 8);
+    ///
+    /// // streaming can be mixed with next()
+    /// let record = reader.next().unwrap().unwrap();
+    /// assert_eq!(record.id(), Ok("chr2"));
+    /// ```
+    pub fn next_stream(&mut self) -> Option<Result<SeqStream<'_, R, P>, Error>> {
+        if !try_opt!(self.start_stream()) {
+            return None;
+        }
+        Some(Ok(SeqStream { reader: self }))
+    }
+
+    // Moves to the start of the next record and reads its header line for
+    // streaming. Returns false if at the end of the input.
+    fn start_stream(&mut self) -> Result<bool, Error> {
+        if self.state == State::Streaming {
+            self.skip_stream()?;
+        }
+        match self.state {
+            State::New => {
+                if !self.init()? {
+                    return Ok(false);
+                }
+            }
+            State::Finished => return Ok(false),
+            State::Parsing => self.increment_record(),
+            State::Positioned | State::Incomplete => {
+                // an incomplete record is searched again from the start
+                self.buf_pos.seq_pos.clear();
+            }
+            State::Streaming => unreachable!(),
+        }
+        self.search_pos = self.buf_pos.start;
+        self.stream.reset();
+
+        // the header line has to fit into the buffer
+        let head_end = loop {
+            let buf = self.get_buf();
+            let start = self.buf_pos.start;
+            if let Some(i) = memchr(b'\n', &buf[start..]) {
+                break start + i;
+            }
+            if buf.len() < self.buf_reader.capacity() {
+                // EOF
+                if start == buf.len() {
+                    self.state = State::Finished;
+                    return Ok(false);
+                }
+                break buf.len();
+            }
+            if start > 0 {
+                self.make_room();
+            } else {
+                self.grow()?;
+            }
+            fill_buf(&mut self.buf_reader)?;
+        };
+
+        let start = self.buf_pos.start;
+        let head = trim_cr(&self.buf_reader.buffer()[start + 1..head_end]);
+        self.stream.head.clear();
+        self.stream.head.extend_from_slice(head);
+        self.search_pos = head_end;
+        self.state = State::Streaming;
+        Ok(true)
+    }
+
+    // Returns the buffer range of the next sequence chunk of the current
+    // streamed record, or `None` at the end of the record. Afterwards, the
+    // state is Positioned (next record found) or Finished.
+    fn next_stream_chunk(&mut self) -> Result<Option<(usize, usize)>, Error> {
+        while self.state == State::Streaming {
+            let buf = self.buf_reader.buffer();
+            let eof = buf.len() < self.buf_reader.capacity();
+            let p = self.search_pos;
+            if p == buf.len() {
+                if eof {
+                    self.state = State::Finished;
+                    return Ok(None);
+                }
+                self.refill_stream(p)?;
+                continue;
+            }
+            if buf[p] == b'\n' {
+                match buf.get(p + 1) {
+                    Some(&b'>') => self.end_stream(p + 1),
+                    Some(_) => {
+                        self.search_pos += 1;
+                        self.stream.lines += 1;
+                    }
+                    None if eof => self.state = State::Finished,
+                    // the next byte is not yet known
+                    None => self.refill_stream(p)?,
+                }
+                continue;
+            }
+            let (end, next) = match memchr(b'\n', &buf[p..]) {
+                Some(i) => (p + trim_cr(&buf[p..p + i]).len(), p + i),
+                // A '\r' at the end of the buffer may be followed by '\n',
+                // it is kept in the buffer and searched again.
+                None if !eof && buf.last() == Some(&b'\r') => (buf.len() - 1, buf.len() - 1),
+                None => (p + trim_cr(&buf[p..]).len(), buf.len()),
+            };
+            if end > p {
+                self.search_pos = next;
+                return Ok(Some((p, end)));
+            }
+            if next == p {
+                self.refill_stream(p)?;
+            } else {
+                self.search_pos = next;
+            }
+        }
+        Ok(None)
+    }
+
+    // Consumes the buffer up to `keep_from` while streaming and refills it
+    fn refill_stream(&mut self, keep_from: usize) -> Result<(), Error> {
+        self.stream.bytes += (keep_from - self.buf_pos.start) as u64;
+        self.last_head.consume(self.buf_reader.buffer(), keep_from);
+        self.buf_reader.consume(keep_from);
+        self.buf_reader.make_room();
+        self.buf_pos.start = 0;
+        self.search_pos -= keep_from;
+        fill_buf(&mut self.buf_reader)?;
+        Ok(())
+    }
+
+    // Ends streaming, the next record starts at `next_start` in the buffer
+    fn end_stream(&mut self, next_start: usize) {
+        self.position.byte += self.stream.bytes + (next_start - self.buf_pos.start) as u64;
+        // the last line break is not yet counted
+        self.position.line += self.stream.lines + 1;
+        self.stream.reset();
+        self.last_head.set_copied(&self.stream.head);
+        self.position.record += 1;
+        self.buf_pos.start = next_start;
+        self.buf_pos.seq_pos.clear();
+        self.search_pos = next_start;
+        self.state = State::Positioned;
+    }
+
+    // Skips the remaining sequence of a streamed record
+    fn skip_stream(&mut self) -> Result<(), Error> {
+        while self.next_stream_chunk()?.is_some() {}
+        Ok(())
     }
 
     /// Updates a [RecordSet](struct.RecordSet.html) with new data.
@@ -273,6 +590,9 @@ where
         n_records: Option<usize>,
     ) -> Option<Result<(), Error>> {
         debug_assert!(n_records.unwrap_or(usize::MAX) > 0);
+        if self.state == State::Streaming {
+            try_opt!(self.skip_stream());
+        }
         // after read_record_set(), the state is always Positioned, Parsing or Finished
         match self.state {
             State::New => {
@@ -296,6 +616,7 @@ where
                 // Positioned: A seek() to a position reachable from within the current buffer
                 //  was done.
             }
+            State::Streaming => unreachable!(),
         };
 
         rset.npos = 0;
@@ -332,6 +653,10 @@ where
                     break;
                 }
             }
+            if let Err(e) = self.check_record() {
+                try_opt!(self.skip_invalid(e));
+                continue;
+            }
             // at least one record must be present as a whole in the buffer
             if let Some(pos) = rset.positions.get_mut(rset.npos) {
                 pos.update(&self.buf_pos);
@@ -348,33 +673,201 @@ where
             }
         }
 
+        if rset.npos == 0 {
+            // only empty records were skipped in recovery mode
+            return None;
+        }
+
         rset.buffer.clear();
         rset.buffer.extend(self.get_buf());
+        rset.header = self.header;
         Some(Ok(()))
     }
 
+    // Returns an error if the current record has no sequence, but empty
+    // records are not allowed, or if it fails validation.
+    #[inline]
+    fn check_record(&self) -> Result<(), Error> {
+        if self.allow_empty && self.validation.is_none() {
+            return Ok(());
+        }
+        let rec = RefRecord {
+            buffer: self.get_buf(),
+            buf_pos: &self.buf_pos,
+            header: self.header,
+        };
+        if let Some(validation) = self.validation.as_ref() {
+            if validation.check_header && rec.head().is_empty() {
+                return Err(Error::EmptyHeader {
+                    pos: self.get_error_pos(0, None),
+                });
+            }
+            for (i, line) in rec.seq_lines().enumerate() {
+                if let Some(j) = validation.find_invalid_seq(line) {
+                    return Err(Error::InvalidSeq {
+                        found: line[j],
+                        pos: self.get_error_pos(i as u64 + 1, Some(j + 1)),
+                    });
+                }
+            }
+        }
+        if self.allow_empty || rec.seq_lines().any(|l| !l.is_empty()) {
+            return Ok(());
+        }
+        let pos = self.get_error_pos(0, None);
+        if self.state == State::Finished {
+            Err(Error::UnexpectedEnd { pos })
+        } else {
+            Err(Error::EmptyRecord { pos })
+        }
+    }
+
+    #[inline(never)]
+    fn get_error_pos(&self, line_offset: u64, column: Option<usize>) -> ErrorPosition {
+        let rec = RefRecord {
+            buffer: self.get_buf(),
+            buf_pos: &self.buf_pos,
+            header: self.header,
+        };
+        let id = rec.id_bytes();
+        ErrorPosition {
+            line: self.position.line + line_offset,
+            column,
+            record_line: self.position.line,
+            byte: self.position.byte,
+            record: self.position.record,
+            id: if id.is_empty() {
+                None
+            } else {
+                Some(String::from_utf8_lossy(id).into())
+            },
+            last_id: self.last_head.id(self.get_buf(), &self.header),
+        }
+    }
+
+    // Returns the error if recovery is not enabled, otherwise reports
+    // the current (invalid) record as skipped and moves to the next one.
+    #[inline(never)]
+    fn skip_invalid(&mut self, error: Error) -> Result<(), Error> {
+        let recovery = match self.recovery.as_mut() {
+            Some(r) => r,
+            None => {
+                self.state = State::Finished;
+                return Err(error);
+            }
+        };
+        // the last record extends to the end of the buffer
+        let end = if self.state == State::Finished {
+            self.buf_reader.buffer().len()
+        } else {
+            self.search_pos
+        };
+        let start = self.position.byte;
+        recovery.report(Skipped {
+            error,
+            line: self.position.line,
+            start,
+            end: start + (end - self.buf_pos.start) as u64,
+        });
+        if self.state != State::Finished {
+            self.advance();
+        }
+        Ok(())
+    }
+
     // moves to the first record positon, ignoring newline characters
     #[inline(never)]
     fn init(&mut self) -> Result<bool, Error> {
         if let Some((line_num, pos, byte)) = self.first_byte()? {
             if byte == b'>' {
                 self.buf_pos.start = pos;
-                self.position.byte = pos as u64;
-                self.position.line = line_num as u64;
+                // the position may have been set with `set_input_offset()`
+                self.position.byte += pos as u64;
+                self.position.line += line_num as u64;
                 self.search_pos = pos + 1;
                 return Ok(true);
             } else {
-                self.state = State::Finished;
-                return Err(Error::InvalidStart {
-                    line: line_num,
+                let error = Error::InvalidStart {
                     found: byte,
-                });
+                    pos: ErrorPosition {
+                        line: self.position.line + line_num as u64,
+                        column: Some(1),
+                        record_line: self.position.line + line_num as u64,
+                        byte: self.position.byte + pos as u64,
+                        record: self.position.record,
+                        id: None,
+                        last_id: None,
+                    },
+                };
+                if self.recovery.is_some() {
+                    return self.skip_to_header(error, line_num, pos);
+                }
+                self.state = State::Finished;
+                return Err(error);
             }
         }
         self.state = State::Finished;
         Ok(false)
     }
 
+    // Skips all lines before the first header line in recovery mode and
+    // reports the error. `pos` is the buffer offset of the first non-empty
+    // line. Returns false if no header was found.
+    #[inline(never)]
+    fn skip_to_header(
+        &mut self,
+        error: Error,
+        line_num: usize,
+        mut pos: usize,
+    ) -> Result<bool, Error> {
+        self.position.byte += pos as u64;
+        self.position.line += line_num as u64;
+        let line = self.position.line;
+        let start = self.position.byte;
+        let found = loop {
+            let buf = self.get_buf();
+            let eof = buf.len() < self.buf_reader.capacity();
+            let header = memchr_iter(b'\n', &buf[pos..])
+                .map(|i| pos + i + 1)
+                .find(|&i| buf.get(i) == Some(&b'>'));
+            // Keep the last line break in the buffer if the following byte
+            // is not yet known, otherwise discard all data.
+            let end = match header {
+                Some(h) => h,
+                None if eof => buf.len(),
+                None => match memrchr(b'\n', buf) {
+                    Some(k) if k > 0 && k >= pos => k,
+                    _ => buf.len(),
+                },
+            };
+            self.position.line += memchr_iter(b'\n', &buf[pos..end]).count() as u64;
+            self.position.byte += (end - pos) as u64;
+            if header.is_some() {
+                break Some(end);
+            }
+            if eof {
+                break None;
+            }
+            self.buf_reader.consume(end);
+            self.buf_reader.make_room();
+            fill_buf(&mut self.buf_reader)?;
+            pos = 0;
+        };
+        self.recovery.as_mut().unwrap().report(Skipped {
+            error,
+            line,
+            start,
+            end: self.position.byte,
+        });
+        if let Some(h) = found {
+            self.buf_pos.start = h;
+            self.search_pos = h + 1;
+            return Ok(true);
+        }
+        self.state = State::Finished;
+        Ok(false)
+    }
+
     fn first_byte(&mut self) -> Result<Option<(usize, usize, u8)>, Error> {
         let mut line_num = 0;
 
@@ -404,6 +897,15 @@ where
 
     // Sets starting points for next position
     fn increment_record(&mut self) {
+        if let Some(&head_end) = self.buf_pos.seq_pos.first() {
+            self.last_head.set(self.buf_pos.start + 1, head_end);
+        }
+        self.position.record += 1;
+        self.advance();
+    }
+
+    // Moves to the next record without counting the current one
+    fn advance(&mut self) {
         self.position.line += self.buf_pos.seq_pos.len() as u64;
         self.position.byte += (self.search_pos - self.buf_pos.start) as u64;
         self.buf_pos.start = self.search_pos;
@@ -496,6 +998,7 @@ where
     // move incomplete bytes to start of buffer
     fn make_room(&mut self) {
         let consumed = self.buf_pos.start;
+        self.last_head.consume(self.buf_reader.buffer(), consumed);
         self.buf_reader.consume(consumed);
         self.buf_reader.make_room();
         self.buf_pos.start = 0;
@@ -526,21 +1029,31 @@ where
     /// // second position
     /// reader.next().unwrap();
     ///
-    /// assert_eq!(reader.position(), Some(&Position::new(3, 10)));
+    /// assert_eq!(reader.position(), Some(&Position::with_record(3, 10, 1)));
     /// # }
     /// ```
     ///
     /// **Note:** After [read_record_set](Self::read_record_set), `position()`
     /// returns the position of the *next record after* the last record in the
     /// record set.
+    /// After [next_stream](Self::next_stream), the position of the streamed
+    /// record is returned.
     #[inline]
     pub fn position(&self) -> Option<&Position> {
-        if self.buf_pos.is_new() {
+        if self.buf_pos.is_new() && self.state != State::Streaming {
             return None;
         }
         Some(&self.position)
     }
 
+    // Sets the number of lines, bytes and records preceding the input, which is used if
+    // the input is only a part of a file (see `parallel::read_file_chunked`).
+    // Has to be called before reading.
+    pub(crate) fn set_input_offset(&mut self, lines: u64, bytes: u64, records: u64) {
+        debug_assert!(self.state == State::New);
+        self.position = Position::with_record(lines, bytes, records);
+    }
+
     /// Returns a borrowed iterator over all FASTA records. The records
     /// are owned (`OwnedRecord`), this is therefore slower than using
     /// `Reader::next()`.
@@ -571,7 +1084,7 @@ where
     /// # }
     /// ```
     #[inline]
-    pub fn records(&mut self) -> RecordsIter<R, P> {
+    pub fn records(&mut self) -> RecordsIter<'_, R, P> {
         RecordsIter { rdr: self }
     }
 
@@ -593,6 +1106,10 @@ where
     /// If an error was returned before, seeking to that position will return the same error.
     /// The same is not always true with `None`. If there is no newline character at the end of the
     /// file, the last record will be returned instead of `None`.
+    /// Records are counted from the record index of the position
+    /// ([`Position::record`](struct.Position.html#method.record)).
+    /// With the `bgzf` feature, seeking also works with BGZF compressed input
+    /// read by [`bgzf::Reader`](../bgzf/struct.Reader.html).
     ///
     /// # Example
     ///
@@ -624,10 +1141,12 @@ where
     #[inline]
     pub fn seek(&mut self, to: &Position) -> Result<(), Error> {
         // TODO: does not handle unrealistically large buffers
-        let offset = to.byte as i64 - self.position.byte as i64;
+        let offset = to.byte as i64 - (self.position.byte + self.stream.bytes) as i64;
         let pos = self.buf_pos.start as i64 + offset;
         self.position = to.clone();
+        self.last_head.clear();
         self.state = State::Positioned;
+        self.stream.reset();
 
         if pos >= 0 && pos < (self.get_buf().len() as i64) {
             // position reachable within buffer -> no actual seeking necessary
@@ -644,6 +1163,85 @@ where
     }
 }
 
+#[cfg(feature = "bgzf")]
+impl<R, P> Reader<crate::bgzf::Reader<R>, P>
+where
+    R: io::Read + Seek,
+    P: BufPolicy,
+{
+    /// Returns the current position like [`position()`](#method.position), but
+    /// the byte offset is a BGZF virtual offset, which can be used with
+    /// [`seek_virtual()`](#method.seek_virtual).
+    /// Requires the `bgzf` feature.
+    pub fn virtual_position(&mut self) -> Option<Position> {
+        let pos = self.position()?.clone();
+        let buf_start = pos.byte - self.buf_pos.start as u64;
+        let bgzf = self.buf_reader.get_mut();
+        bgzf.forget_before(buf_start);
+        bgzf.virtual_offset_at(pos.byte)
+            .map(|voffset| Position::with_record(pos.line, voffset, pos.record))
+    }
+
+    /// Seeks to a position obtained with
+    /// [`virtual_position()`](#method.virtual_position). Afterwards,
+    /// [`position()`](#method.position) reports the offset in the decompressed
+    /// data, like before (see the [`bgzf`](../bgzf/index.html) module).
+    /// Requires the `bgzf` feature.
+    pub fn seek_virtual(&mut self, to: &Position) -> Result<(), Error> {
+        let bgzf = self.buf_reader.get_mut();
+        bgzf.seek_virtual(to.byte)?;
+        let offset = bgzf.offset();
+        let buffered = self.buf_reader.buf_len();
+        self.buf_reader.consume(buffered);
+        self.position = Position::with_record(to.line, offset, to.record);
+        self.last_head.clear();
+        self.state = State::Positioned;
+        self.stream.reset();
+        fill_buf(&mut self.buf_reader)?;
+        self.search_pos = 0;
+        self.buf_pos.reset(0);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<R, P> Reader<R, P>
+where
+    R: io::Read + Send,
+    P: BufPolicy + Send,
+{
+    /// Returns a rayon `ParallelIterator` over record sets, which are filled
+    /// by [`read_record_set`](#method.read_record_set) and processed by the
+    /// threads of the current rayon pool. The order of the record sets
+    /// is not preserved. See also
+    /// [`parallel::par_record_sets`](../parallel/fn.par_record_sets.html).
+    /// Requires the `rayon` feature.
+    #[inline]
+    pub fn par_record_sets(
+        self,
+    ) -> rayon::iter::IterBridge<crate::parallel::RecordSets<Reader<R, P>>> {
+        crate::parallel::par_record_sets(self)
+    }
+
+    /// Returns a rayon `ParallelIterator` over owned records, which are
+    /// read in batches like with [`par_record_sets`](#method.par_record_sets).
+    /// Requires the `rayon` feature.
+    pub fn par_records(
+        self,
+    ) -> impl rayon::iter::ParallelIterator<Item = Result<OwnedRecord, Error>> {
+        use rayon::iter::{Either, ParallelIterator};
+        self.par_record_sets().flat_map_iter(|rset| match rset {
+            Ok(rset) => Either::Left(
+                rset.into_iter()
+                    .map(|r| Ok(r.to_owned_record()))
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            ),
+            Err(e) => Either::Right(iter::once(Err(e))),
+        })
+    }
+}
+
 /// Borrowed iterator of `OwnedRecord`
 pub struct RecordsIter<'a, R, P = DefaultPolicy>
 where
@@ -684,17 +1282,77 @@ where
     }
 }
 
-/// Holds line number and byte offset of a FASTA record
+/// A FASTA record, whose sequence is read in chunks (see
+/// [`Reader::next_stream`](struct.Reader.html#method.next_stream))
+pub struct SeqStream<'a, R: io::Read, P = DefaultPolicy> {
+    reader: &'a mut Reader<R, P>,
+}
+
+impl<'a, R, P> SeqStream<'a, R, P>
+where
+    R: io::Read,
+    P: BufPolicy,
+{
+    /// Returns the header line of the record
+    #[inline]
+    pub fn head(&self) -> &[u8] {
+        &self.reader.stream.head
+    }
+
+    /// Returns the ID of the record, according to the
+    /// [header settings](struct.Reader.html#method.set_header_config) of the reader
+    #[inline]
+    pub fn id_bytes(&self) -> &[u8] {
+        self.reader.header.id(self.head())
+    }
+
+    /// Returns the ID of the record as string slice
+    #[inline]
+    pub fn id(&self) -> Result<&str, Utf8Error> {
+        str::from_utf8(self.id_bytes())
+    }
+
+    /// Returns the description of the record, if present
+    #[inline]
+    pub fn desc_bytes(&self) -> Option<&[u8]> {
+        self.reader.header.desc(self.head())
+    }
+
+    /// Returns the description of the record as string slice, if present
+    #[inline]
+    pub fn desc(&self) -> Option<Result<&str, Utf8Error>> {
+        self.desc_bytes().map(str::from_utf8)
+    }
+
+    /// Returns the next chunk of the sequence, or `None` at the end of the
+    /// record. Chunks never contain line breaks, and they are never empty.
+    /// A sequence line may be split into several chunks.
+    #[inline]
+    pub fn next_chunk(&mut self) -> Option<Result<&[u8], Error>> {
+        let (start, end) = try_opt!(self.reader.next_stream_chunk())?;
+        Some(Ok(&self.reader.get_buf()[start..end]))
+    }
+}
+
+/// Holds line number, byte offset and index of a FASTA record
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Position {
     line: u64,
     byte: u64,
+    record: u64,
 }
 
 impl Position {
+    /// Creates a position of the first record (record index 0)
     #[inline]
     pub fn new(line: u64, byte: u64) -> Position {
-        Position { line, byte }
+        Position::with_record(line, byte, 0)
+    }
+
+    /// Creates a position with the given index of the record (0-based)
+    #[inline]
+    pub fn with_record(line: u64, byte: u64, record: u64) -> Position {
+        Position { line, byte, record }
     }
 
     /// Line number (starting with 1)
@@ -708,6 +1366,12 @@ impl Position {
     pub fn byte(&self) -> u64 {
         self.byte
     }
+
+    /// Index of the record (0-based), which is restored by `seek()`
+    #[inline]
+    pub fn record(&self) -> u64 {
+        self.record
+    }
 }
 
 /// FASTA parsing error
@@ -717,10 +1381,33 @@ pub enum Error {
     Io(io::Error),
     /// First non-empty line does not start with `>`
     InvalidStart {
-        /// line number (1-based)
-        line: usize,
         /// byte that was found instead
         found: u8,
+        /// Position of the line. `ErrorPosition::id` will be `None`.
+        pos: ErrorPosition,
+    },
+    /// Header line directly followed by another header
+    /// (only if empty records are not allowed)
+    EmptyRecord {
+        /// Position of the record
+        pos: ErrorPosition,
+    },
+    /// The input ends with a header line (only if empty records are not allowed)
+    UnexpectedEnd {
+        /// Position of the last record
+        pos: ErrorPosition,
+    },
+    /// Sequence byte not part of the alphabet (only with validation)
+    InvalidSeq {
+        /// Byte found
+        found: u8,
+        /// Position within file
+        pos: ErrorPosition,
+    },
+    /// Empty header line (only with validation)
+    EmptyHeader {
+        /// Position of the record
+        pos: ErrorPosition,
     },
     /// Size limit of buffer was reached, which happens if `policy::BufPolicy::grow_to()` returned
     /// `None`. This does not happen with the default `struct.DoubleUntil.html` policy.
@@ -732,12 +1419,29 @@ impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Error::Io(ref e) => e.fmt(f),
-            Error::InvalidStart { line, found } => write!(
+            Error::InvalidStart { found, ref pos } => write!(
+                f,
+                "FASTA parse error: expected '>' but found '{}' at file start ({}).",
+                (found as char).escape_default(),
+                pos
+            ),
+            Error::EmptyRecord { ref pos } => {
+                write!(f, "FASTA parse error: record without sequence ({}).", pos)
+            }
+            Error::UnexpectedEnd { ref pos } => write!(
                 f,
-                "FASTA parse error: expected '>' but found '{}' at file start, line {}.",
+                "FASTA parse error: unexpected end of input after header line ({}).",
+                pos
+            ),
+            Error::InvalidSeq { found, ref pos } => write!(
+                f,
+                "FASTA validation error: invalid sequence byte '{}' ({}).",
                 (found as char).escape_default(),
-                line
+                pos
             ),
+            Error::EmptyHeader { ref pos } => {
+                write!(f, "FASTA validation error: empty header line ({}).", pos)
+            }
             Error::BufferLimit => write!(f, "FASTA parse error: buffer limit reached."),
         }
     }
@@ -840,6 +1544,26 @@ pub trait Record {
         let mut h = str::from_utf8(self.head())?.splitn(2, ' ');
         Ok((h.next().unwrap(), h.next()))
     }
+
+    /// Parses the header line as Illumina read name
+    /// (see the [`illumina`](../illumina/index.html) module)
+    #[inline]
+    fn illumina_header(&self) -> Result<illumina::IlluminaHeader<'_>, illumina::Error> {
+        illumina::IlluminaHeader::parse(self.head())
+    }
+
+    /// Returns an iterator over the `key=value` and SAM-style tags in the
+    /// description (see the [`tags`](../tags/index.html) module)
+    #[inline]
+    fn tags(&self) -> tags::Tags<'_> {
+        tags::Tags::new(self.desc_bytes().unwrap_or_default())
+    }
+
+    /// Returns the first tag with the given name from the description
+    #[inline]
+    fn tag(&self, key: &[u8]) -> Option<tags::Tag<'_>> {
+        self.tags().get(key)
+    }
 }
 
 /// A FASTA record that borrows data from a buffer.
@@ -847,6 +1571,7 @@ pub trait Record {
 pub struct RefRecord<'a> {
     buffer: &'a [u8],
     buf_pos: &'a BufferPosition,
+    header: HeaderConfig,
 }
 
 impl Record for RefRecord<'_> {
@@ -872,6 +1597,27 @@ impl Record for RefRecord<'_> {
         }
     }
 
+    #[inline]
+    fn id_bytes(&self) -> &[u8] {
+        self.header.id(self.head())
+    }
+
+    #[inline]
+    fn desc_bytes(&self) -> Option<&[u8]> {
+        self.header.desc(self.head())
+    }
+
+    #[inline]
+    fn id_desc_bytes(&self) -> (&[u8], Option<&[u8]>) {
+        self.header.split(self.head())
+    }
+
+    #[inline]
+    fn id_desc(&self) -> Result<(&str, Option<&str>), Utf8Error> {
+        let (id, desc) = self.id_desc_bytes();
+        Ok((str::from_utf8(id)?, desc.map(str::from_utf8).transpose()?))
+    }
+
     #[inline]
     fn write<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
         write_head(&mut writer, self.head())?;
@@ -888,7 +1634,7 @@ impl Record for RefRecord<'_> {
 impl RefRecord<'_> {
     /// Return an iterator over all sequence lines in the data
     #[inline]
-    pub fn seq_lines(&self) -> SeqLines {
+    pub fn seq_lines(&self) -> SeqLines<'_> {
         SeqLines {
             data: self.buffer,
             len: self.buf_pos.seq_pos.len() - 1,
@@ -912,7 +1658,7 @@ impl RefRecord<'_> {
     /// (equivalent to calling `RefRecord::seq()`). If there are multiple
     /// lines, an owned copy will be created (equivalent to `RefRecord::owned_seq()`).
     #[inline]
-    pub fn full_seq(&self) -> Cow<[u8]> {
+    pub fn full_seq(&self) -> Cow<'_, [u8]> {
         if self.num_seq_lines() == 1 {
             // only one line
             self.seq().into()
@@ -1032,6 +1778,8 @@ pub struct RecordSet {
     buffer: Vec<u8>,
     positions: Vec<BufferPosition>,
     npos: usize,
+    #[serde(default)]
+    header: HeaderConfig,
 }
 
 impl RecordSet {
@@ -1068,6 +1816,7 @@ impl<'a> iter::IntoIterator for &'a RecordSet {
     fn into_iter(self) -> Self::IntoIter {
         RecordSetIter {
             buffer: &self.buffer,
+            header: self.header,
             pos: self.positions.iter().take(self.npos),
         }
     }
@@ -1076,6 +1825,7 @@ impl<'a> iter::IntoIterator for &'a RecordSet {
 /// Iterator over record sets
 pub struct RecordSetIter<'a> {
     buffer: &'a [u8],
+    header: HeaderConfig,
     pos: iter::Take<slice::Iter<'a, BufferPosition>>,
 }
 
@@ -1087,10 +1837,112 @@ impl<'a> Iterator for RecordSetIter<'a> {
         self.pos.next().map(|p| RefRecord {
             buffer: self.buffer,
             buf_pos: p,
+            header: self.header,
         })
     }
 }
 
+/// Buffered FASTA writer with configurable output format.
+///
+/// By default, sequences are written on a single line, and UNIX line endings
+/// are used.
+///
+/// # Example
+///
+/// ```
+/// use seq_io::fasta::{Reader, Writer};
+/// use seq_io::LineEnding;
+///
+/// let input = b">id desc\nACGT\nACGT\n";
+///
+/// let mut reader = Reader::new(&input[..]);
+/// let mut writer = Writer::new(vec![])
+///     .set_line_width(Some(3))
+///     .set_line_ending(LineEnding::CrLf)
+///     .set_write_desc(false);
+///
+/// while let Some(record) = reader.next() {
+///     writer.write_record(&record.unwrap()).unwrap();
+/// }
+/// assert_eq!(writer.num_records(), 1);
+/// assert_eq!(writer.num_bases(), 8);
+///
+/// let output = writer.into_inner().unwrap();
+/// assert_eq!(&output[..], b">id\r\nACG\r\nTAC\r\nGT\r\n");
+/// ```
+pub struct Writer<W: io::Write> {
+    base: WriterBase<W>,
+}
+
+impl<W: io::Write> Writer<W> {
+    /// Creates a new writer with a buffer size of 64 KiB
+    #[inline]
+    pub fn new(writer: W) -> Writer<W> {
+        Writer::with_capacity(BUFSIZE, writer)
+    }
+
+    /// Creates a new writer with a given buffer capacity
+    #[inline]
+    pub fn with_capacity(capacity: usize, writer: W) -> Writer<W> {
+        Writer {
+            base: WriterBase::with_capacity(capacity, writer),
+        }
+    }
+
+    /// Sets the maximum width of sequence lines. `None` (the default) writes
+    /// every sequence on a single line.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width is `Some(0)`.
+    #[inline]
+    pub fn set_line_width(mut self, width: Option<usize>) -> Self {
+        assert!(width != Some(0), "line width must be > 0");
+        self.base.line_width = width;
+        self
+    }
+
+    writer_base_impl!();
+
+    /// Writes a record. The sequence is written according to the configured
+    /// line width, regardless of the line structure of the input.
+    #[inline]
+    pub fn write_record<R: Record + ?Sized>(&mut self, record: &R) -> io::Result<()> {
+        self.write(record.head(), record.seq())
+    }
+
+    /// Writes data (not necessarily stored in a `Record` instance).
+    /// `seq` may contain line breaks, which are removed.
+    #[inline]
+    pub fn write(&mut self, head: &[u8], seq: &[u8]) -> io::Result<()> {
+        self.write_inner(&Head::Line(head), seq)
+    }
+
+    /// Writes data with the ID and description parts of the header supplied
+    /// separately.
+    #[inline]
+    pub fn write_parts(&mut self, id: &[u8], desc: Option<&[u8]>, seq: &[u8]) -> io::Result<()> {
+        self.write_inner(&Head::Parts(id, desc), seq)
+    }
+
+    fn write_inner(&mut self, head: &Head, seq: &[u8]) -> io::Result<()> {
+        self.base.write_head(b'>', head)?;
+        let n = self.base.write_lines(seq)?;
+        self.base.record_written(n);
+        Ok(())
+    }
+}
+
+impl Writer<File> {
+    /// Creates a writer to a new file (an existing file will be truncated)
+    #[inline]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> io::Result<Writer<File>> {
+        File::create(path).map(Writer::new)
+    }
+}
+
+compressed_writer_impl!();
+
 /// Writes data (not necessarily stored in a `Record` instance) to the FASTA format.
 #[inline]
 pub fn write_to<W>(mut writer: W, head: &[u8], seq: &[u8]) -> io::Result<()>
@@ -0,0 +1,461 @@
+//! Reading and writing of FASTA indices (`.fai`), as well as random access to
+//! subsequences of indexed FASTA files.
+//!
+//! The index format is compatible with `samtools faidx`. Each line describes
+//! one sequence with the following tab-separated columns:
+//! name, sequence length, byte offset of the first base, number of bases per line
+//! and number of bytes per line (including the line terminator).
+//! Indexing therefore requires that all sequence lines of a record (except the last one)
+//! have the same length.
+//!
+//! # Example
+//!
+//! ```
+//! use seq_io::fasta::Reader;
+//! use seq_io::fasta::index::{Index, IndexedReader};
+//! use std::io::Cursor;
+//!
+//! let fasta = b">chr1 desc
+//! ACGTA
+//! CGTAC
+//! GT
+//! >chr2
+//! TTTT
+//! ";
+//!
+//! // build the index
+//! let index = Index::build(&mut Reader::new(&fasta[..])).unwrap();
+//! let mut fai = vec![];
+//! index.write(&mut fai).unwrap();
+//! assert_eq!(&fai, b"chr1\t12\t11\t5\t6\nchr2\t4\t32\t4\t5\n");
+//!
+//! // fetch a region (1-based, inclusive coordinates like with samtools)
+//! let mut reader = IndexedReader::new(Cursor::new(&fasta[..]), index);
+//! let mut seq = vec![];
+//! reader.fetch_region("chr1:4-8", &mut seq).unwrap();
+//! assert_eq!(&seq, b"TACGT");
+//! ```
+
+use std::collections::HashMap;
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, Seek};
+use std::path::Path;
+use std::str;
+
+use super::{Reader, Record};
+use crate::policy::BufPolicy;
+use crate::trim_cr;
+
+/// Entry of a FASTA index, describing one sequence
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexRecord {
+    /// Sequence name (ID)
+    pub name: String,
+    /// Sequence length
+    pub len: u64,
+    /// Byte offset of the first base within the file
+    pub offset: u64,
+    /// Number of bases per line
+    pub line_bases: u64,
+    /// Number of bytes per line, including the line terminator
+    pub line_width: u64,
+}
+
+impl IndexRecord {
+    /// Returns the byte offset of the base at the given (0-based) position
+    #[inline]
+    pub fn base_offset(&self, pos: u64) -> u64 {
+        if self.line_bases == 0 {
+            return self.offset;
+        }
+        self.offset + pos / self.line_bases * self.line_width + pos % self.line_bases
+    }
+}
+
+/// FASTA index
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Index {
+    records: Vec<IndexRecord>,
+    names: HashMap<String, usize>,
+}
+
+impl Index {
+    /// Builds an index by parsing all records of a FASTA reader.
+    /// Returns an error if the sequence lines of a record have unequal lengths,
+    /// or if a sequence name is present more than once.
+    pub fn build<R, P>(reader: &mut Reader<R, P>) -> Result<Index, Error>
+    where
+        R: io::Read,
+        P: BufPolicy,
+    {
+        let mut index = Index::default();
+        while let Some(rec) = reader.next() {
+            let rec = rec?;
+            let name = String::from_utf8_lossy(rec.id_bytes()).into_owned();
+            let head_len = rec.buf_pos.seq_pos[0] + 1 - rec.buf_pos.start;
+
+            // (bases, bytes) of each sequence line
+            let mut lines: Vec<(u64, u64)> = rec
+                .seq_lines()
+                .zip(rec.buf_pos.seq_pos.windows(2))
+                .map(|(l, w)| (l.len() as u64, (w[1] - w[0]) as u64))
+                .collect();
+            // empty lines at the end are ignored
+            while lines.last().map(|l| l.0 == 0).unwrap_or(false) {
+                lines.pop();
+            }
+            let (line_bases, line_width) = lines.first().cloned().unwrap_or((0, 0));
+            let ragged = lines.iter().enumerate().find(|&(i, &(bases, width))| {
+                if i + 1 < lines.len() {
+                    bases != line_bases || width != line_width
+                } else {
+                    bases > line_bases
+                }
+            });
+            let ragged_line = ragged.map(|(i, _)| i as u64);
+            let len = lines.iter().map(|l| l.0).sum();
+
+            let pos = reader.position().unwrap();
+            if let Some(i) = ragged_line {
+                return Err(Error::RaggedLines {
+                    name,
+                    line: pos.line() + 1 + i,
+                });
+            }
+            index.push(IndexRecord {
+                name,
+                len,
+                offset: pos.byte() + head_len as u64,
+                line_bases,
+                line_width,
+            })?;
+        }
+        Ok(index)
+    }
+
+    /// Builds the index of a FASTA file
+    pub fn build_from_path<P: AsRef<Path>>(path: P) -> Result<Index, Error> {
+        Index::build(&mut Reader::from_path(path)?)
+    }
+
+    /// Reads an index in the `.fai` format
+    pub fn read<R: BufRead>(reader: R) -> Result<Index, Error> {
+        let mut index = Index::default();
+        for (i, line) in reader.lines().enumerate() {
+            let line = line?;
+            let line = trim_cr(line.as_bytes());
+            if line.is_empty() {
+                continue;
+            }
+            let invalid = || Error::InvalidIndex { line: i as u64 + 1 };
+            let fields: Vec<_> = line.split(|b| *b == b'\t').collect();
+            if fields.len() < 5 {
+                return Err(invalid());
+            }
+            let num = |f: &[u8]| -> Result<u64, Error> {
+                str::from_utf8(f)
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .ok_or_else(invalid)
+            };
+            index.push(IndexRecord {
+                name: String::from_utf8_lossy(fields[0]).into_owned(),
+                len: num(fields[1])?,
+                offset: num(fields[2])?,
+                line_bases: num(fields[3])?,
+                line_width: num(fields[4])?,
+            })?;
+        }
+        Ok(index)
+    }
+
+    /// Reads an index file in the `.fai` format
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Index, Error> {
+        Index::read(io::BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the index in the `.fai` format
+    pub fn write<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        for r in &self.records {
+            writeln!(
+                writer,
+                "{}\t{}\t{}\t{}\t{}",
+                r.name, r.len, r.offset, r.line_bases, r.line_width
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Writes the index to a `.fai` file
+    pub fn to_path<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = io::BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        io::Write::flush(&mut writer)
+    }
+
+    /// Returns all index entries in the order of the FASTA file
+    #[inline]
+    pub fn records(&self) -> &[IndexRecord] {
+        &self.records
+    }
+
+    /// Returns the index entry of the given sequence
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&IndexRecord> {
+        self.names.get(name).map(|i| &self.records[*i])
+    }
+
+    /// Returns the number of indexed sequences
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Returns true if the index contains no sequences
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    fn push(&mut self, record: IndexRecord) -> Result<(), Error> {
+        if self.names.contains_key(&record.name) {
+            return Err(Error::DuplicateName(record.name));
+        }
+        self.names.insert(record.name.clone(), self.records.len());
+        self.records.push(record);
+        Ok(())
+    }
+}
+
+/// Sequence region with 0-based, half-open coordinates
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// Sequence name
+    pub name: String,
+    /// Start coordinate (0-based)
+    pub start: u64,
+    /// End coordinate (exclusive), `None` means the end of the sequence
+    pub end: Option<u64>,
+}
+
+impl Region {
+    #[inline]
+    pub fn new(name: &str, start: u64, end: Option<u64>) -> Region {
+        Region {
+            name: name.to_string(),
+            start,
+            end,
+        }
+    }
+
+    /// Parses a region string as used by `samtools faidx`: `name`, `name:start`
+    /// or `name:start-end`. The coordinates are 1-based and inclusive,
+    /// and may contain commas (`chr1:1,000-2,000`).
+    pub fn parse(region: &str) -> Result<Region, Error> {
+        let invalid = || Error::InvalidRegion(region.to_string());
+        let (name, range) = match region.rfind(':') {
+            Some(i) => (&region[..i], Some(&region[i + 1..])),
+            None => (region, None),
+        };
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        let range = match range {
+            Some(r) => r,
+            None => return Ok(Region::new(name, 0, None)),
+        };
+        let num = |s: &str| -> Result<u64, Error> {
+            s.replace(',', "").parse::<u64>().map_err(|_| invalid())
+        };
+        let (start, end) = match range.find('-') {
+            Some(i) => (num(&range[..i])?, Some(num(&range[i + 1..])?)),
+            None => (num(range)?, None),
+        };
+        if start == 0 || end.map(|e| e < start).unwrap_or(false) {
+            return Err(invalid());
+        }
+        Ok(Region::new(name, start - 1, end))
+    }
+}
+
+/// Reader providing random access to the sequences of an indexed FASTA file
+pub struct IndexedReader<R> {
+    reader: R,
+    index: Index,
+    buf: Vec<u8>,
+}
+
+impl IndexedReader<File> {
+    /// Opens an indexed FASTA file. The index is expected to be found in
+    /// `<path>.fai`.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<IndexedReader<File>, Error> {
+        let mut fai_path = path.as_ref().as_os_str().to_owned();
+        fai_path.push(".fai");
+        let index = Index::from_path(fai_path)?;
+        Ok(IndexedReader::new(File::open(path)?, index))
+    }
+}
+
+impl<R> IndexedReader<R>
+where
+    R: io::Read + Seek,
+{
+    #[inline]
+    pub fn new(reader: R, index: Index) -> IndexedReader<R> {
+        IndexedReader {
+            reader,
+            index,
+            buf: vec![],
+        }
+    }
+
+    /// Returns the index
+    #[inline]
+    pub fn index(&self) -> &Index {
+        &self.index
+    }
+
+    /// Fetches a subsequence given 0-based, half-open coordinates, and writes it to
+    /// `out` (after clearing it). Only the bytes of the requested range are read.
+    /// The end coordinate is truncated to the sequence length.
+    pub fn fetch(
+        &mut self,
+        name: &str,
+        start: u64,
+        end: u64,
+        out: &mut Vec<u8>,
+    ) -> Result<(), Error> {
+        let rec = self
+            .index
+            .get(name)
+            .ok_or_else(|| Error::UnknownSequence(name.to_string()))?;
+        out.clear();
+        let end = end.min(rec.len);
+        if start >= end {
+            return Ok(());
+        }
+        let from = rec.base_offset(start);
+        let to = rec.base_offset(end - 1) + 1;
+        self.reader.seek(io::SeekFrom::Start(from))?;
+        self.buf.clear();
+        self.buf.resize((to - from) as usize, 0);
+        self.reader.read_exact(&mut self.buf)?;
+        out.extend(self.buf.iter().filter(|&&b| b != b'\n' && b != b'\r'));
+        if out.len() as u64 != end - start {
+            return Err(Error::Mismatch(name.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Fetches the whole sequence of a record
+    pub fn fetch_all(&mut self, name: &str, out: &mut Vec<u8>) -> Result<(), Error> {
+        self.fetch(name, 0, u64::MAX, out)
+    }
+
+    /// Fetches a region given as string (`name:start-end`, see
+    /// [`Region::parse`](struct.Region.html#method.parse)).
+    /// If the whole string matches a sequence name, the whole sequence is returned
+    /// (useful if names contain `:`).
+    pub fn fetch_region(&mut self, region: &str, out: &mut Vec<u8>) -> Result<(), Error> {
+        if self.index.get(region).is_some() {
+            return self.fetch_all(region, out);
+        }
+        let region = Region::parse(region)?;
+        self.fetch(
+            &region.name,
+            region.start,
+            region.end.unwrap_or(u64::MAX),
+            out,
+        )
+    }
+
+    /// Returns the underlying reader
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// FASTA index error
+#[derive(Debug)]
+pub enum Error {
+    /// io::Error
+    Io(io::Error),
+    /// Error parsing the FASTA file
+    Fasta(super::Error),
+    /// The sequence lines of a record have different lengths
+    RaggedLines {
+        /// Sequence name
+        name: String,
+        /// Line number (1-based) of the first line that differs in length
+        line: u64,
+    },
+    /// A sequence name occurs more than once
+    DuplicateName(String),
+    /// Invalid line in an index file
+    InvalidIndex {
+        /// line number (1-based)
+        line: u64,
+    },
+    /// The index entry of a sequence does not match the FASTA file
+    Mismatch(String),
+    /// Invalid region string
+    InvalidRegion(String),
+    /// Sequence not found in index
+    UnknownSequence(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref e) => e.fmt(f),
+            Error::Fasta(ref e) => e.fmt(f),
+            Error::RaggedLines { ref name, line } => write!(
+                f,
+                "FASTA index error: sequence lines of '{}' have different lengths (line {}).",
+                name, line
+            ),
+            Error::DuplicateName(ref name) => {
+                write!(f, "FASTA index error: duplicate sequence name '{}'.", name)
+            }
+            Error::InvalidIndex { line } => {
+                write!(f, "FASTA index error: invalid index line {}.", line)
+            }
+            Error::Mismatch(ref name) => write!(
+                f,
+                "FASTA index error: index entry of '{}' does not match the FASTA file.",
+                name
+            ),
+            Error::InvalidRegion(ref r) => write!(f, "FASTA index error: invalid region '{}'.", r),
+            Error::UnknownSequence(ref name) => {
+                write!(f, "FASTA index error: sequence '{}' not found.", name)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    #[inline]
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+impl From<super::Error> for Error {
+    #[inline]
+    fn from(e: super::Error) -> Error {
+        Error::Fasta(e)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Fasta(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,448 @@
+use std::borrow::Cow;
+use std::io;
+use std::str::{self, Utf8Error};
+
+use memchr::{memchr, memchr_iter, memmem, memrchr};
+
+use super::{
+    write_head, write_seq_iter, write_wrap_seq_iter, Error, ErrorPosition, OwnedRecord, Position,
+    Record,
+};
+use crate::header::HeaderConfig;
+use crate::trim_cr;
+
+/// FASTA parser working directly on a byte slice.
+///
+/// In contrast to [`Reader`](struct.Reader.html), there is no buffering and
+/// no copying involved: the returned [`SliceRecord`](struct.SliceRecord.html)s
+/// borrow from the input slice and not from the reader. Therefore,
+/// `SliceReader` implements `Iterator`, and the records can be kept in a
+/// `Vec` while parsing continues. The input can be a file read into memory
+/// at once or a memory-mapped file (see the [`mmap`](../mmap/index.html)
+/// module, which requires the `memmap` feature).
+///
+/// Parsing follows the same rules as `Reader`. Sequence validation and
+/// error recovery are not available.
+///
+/// # Example
+///
+/// ```
+/// use seq_io::fasta::{Record, SliceReader};
+///
+/// let fasta = b">id1
+/// ACGT
+/// >id2
+/// TGCA
+/// GGCC
+/// ";
+///
+/// let records: Vec<_> = SliceReader::new(fasta)
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(records.len(), 2);
+/// assert_eq!(records[1].id(), Ok("id2"));
+/// assert_eq!(records[1].full_seq().as_ref(), b"TGCAGGCC");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SliceReader<'a> {
+    data: &'a [u8],
+    // start of the current record, or of the next one (if `started` is false)
+    start: usize,
+    // start of the next record (if any)
+    next_start: Option<usize>,
+    // number of lines of the current record
+    num_lines: u64,
+    position: Position,
+    started: bool,
+    finished: bool,
+    allow_empty: bool,
+    header: HeaderConfig,
+    // header of the last record returned
+    last_head: Option<&'a [u8]>,
+}
+
+impl<'a> SliceReader<'a> {
+    /// Creates a new reader for the given slice
+    #[inline]
+    pub fn new(data: &'a [u8]) -> SliceReader<'a> {
+        SliceReader {
+            data,
+            start: 0,
+            next_start: None,
+            num_lines: 0,
+            position: Position::new(0, 0),
+            started: false,
+            finished: false,
+            allow_empty: true,
+            header: HeaderConfig::default(),
+            last_head: None,
+        }
+    }
+
+    /// Sets whether records without any sequence are allowed (default: true),
+    /// see [`Reader::set_allow_empty`](struct.Reader.html#method.set_allow_empty).
+    #[inline]
+    pub fn set_allow_empty(mut self, allow: bool) -> Self {
+        self.allow_empty = allow;
+        self
+    }
+
+    /// Sets the header parsing configuration of the returned records
+    /// (see [`header`](../header/index.html) module).
+    #[inline]
+    pub fn set_header_config(mut self, config: HeaderConfig) -> Self {
+        self.header = config;
+        self
+    }
+
+    /// Returns the position of the current record (the record returned by
+    /// the last call to `next()`), or `None` if no record was read yet.
+    #[inline]
+    pub fn position(&self) -> Option<&Position> {
+        if !self.started {
+            return None;
+        }
+        Some(&self.position)
+    }
+
+    /// Returns the remaining input after the current record
+    #[inline]
+    pub fn remaining(&self) -> &'a [u8] {
+        if !self.started {
+            return self.data;
+        }
+        &self.data[self.next_start.unwrap_or(self.data.len())..]
+    }
+
+    fn parse_next(&mut self) -> Result<Option<SliceRecord<'a>>, Error> {
+        if self.started {
+            match self.next_start {
+                Some(next) => {
+                    self.position.line += self.num_lines;
+                    self.position.byte += (next - self.start) as u64;
+                    self.position.record += 1;
+                    self.start = next;
+                }
+                None => return Ok(None),
+            }
+        } else if !self.init()? {
+            return Ok(None);
+        }
+
+        let rec = self.search();
+        if !self.allow_empty && rec.seq_lines().all(|l| l.is_empty()) {
+            let pos = self.get_error_pos(&rec);
+            if self.next_start.is_none() {
+                return Err(Error::UnexpectedEnd { pos });
+            }
+            return Err(Error::EmptyRecord { pos });
+        }
+        self.last_head = Some(rec.head);
+        Ok(Some(rec))
+    }
+
+    // moves to the first record positon, ignoring empty lines
+    fn init(&mut self) -> Result<bool, Error> {
+        self.started = true;
+        let mut pos = 0;
+        for (line_num, line) in self.data.split(|b| *b == b'\n').enumerate() {
+            if !line.is_empty() && line != b"\r" {
+                if line[0] != b'>' {
+                    return Err(Error::InvalidStart {
+                        found: line[0],
+                        pos: ErrorPosition {
+                            line: line_num as u64 + 1,
+                            column: Some(1),
+                            record_line: line_num as u64 + 1,
+                            byte: pos as u64,
+                            record: 0,
+                            id: None,
+                            last_id: None,
+                        },
+                    });
+                }
+                self.start = pos;
+                self.position = Position::new(line_num as u64 + 1, pos as u64);
+                return Ok(true);
+            }
+            pos += line.len() + 1;
+        }
+        Ok(false)
+    }
+
+    // Searches the end of the record starting at `self.start`
+    fn search(&mut self) -> SliceRecord<'a> {
+        let data = self.data;
+        let start = self.start;
+        let len = data.len();
+        // `head_end` is the line break after the header, `end` the line break
+        // before the next record (or the end of the input)
+        let (head_end, end) = match memchr(b'\n', &data[start..]) {
+            Some(i) => {
+                let head_end = start + i;
+                match memmem::find(&data[head_end..], b"\n>") {
+                    Some(j) => {
+                        self.next_start = Some(head_end + j + 1);
+                        (head_end, head_end + j)
+                    }
+                    None => {
+                        self.next_start = None;
+                        let end = if data[len - 1] == b'\n' { len - 1 } else { len };
+                        (head_end, end)
+                    }
+                }
+            }
+            None => {
+                self.next_start = None;
+                (len, len)
+            }
+        };
+        let (seq, num_seq_lines) = if end > head_end {
+            let seq = &data[head_end + 1..end];
+            (seq, memchr_iter(b'\n', seq).count() + 1)
+        } else {
+            (&b""[..], 0)
+        };
+        self.num_lines = num_seq_lines as u64 + 1;
+        SliceRecord {
+            head: trim_cr(&data[start + 1..head_end]),
+            seq,
+            num_seq_lines,
+            raw: &data[start..end],
+            header: self.header,
+        }
+    }
+
+    fn get_error_pos(&self, rec: &SliceRecord) -> ErrorPosition {
+        let id = rec.id_bytes();
+        ErrorPosition {
+            line: self.position.line,
+            column: None,
+            record_line: self.position.line,
+            byte: self.position.byte,
+            record: self.position.record,
+            id: if id.is_empty() {
+                None
+            } else {
+                Some(String::from_utf8_lossy(id).into())
+            },
+            last_id: crate::last_id(self.last_head, &self.header),
+        }
+    }
+}
+
+impl<'a> Iterator for SliceReader<'a> {
+    type Item = Result<SliceRecord<'a>, Error>;
+
+    /// Searches the next FASTA record and returns a
+    /// [`SliceRecord`](struct.SliceRecord.html) that borrows its data from
+    /// the input slice. After an error, no more records are returned.
+    #[inline]
+    fn next(&mut self) -> Option<Result<SliceRecord<'a>, Error>> {
+        if self.finished {
+            return None;
+        }
+        match self.parse_next() {
+            Ok(Some(rec)) => Some(Ok(rec)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// A FASTA record returned by [`SliceReader`](struct.SliceReader.html), which
+/// borrows its data from the input slice (lifetime `'a`), independently of
+/// the reader.
+#[derive(Debug, Clone, Copy)]
+pub struct SliceRecord<'a> {
+    head: &'a [u8],
+    // raw sequence lines, the last line is not trimmed
+    seq: &'a [u8],
+    num_seq_lines: usize,
+    raw: &'a [u8],
+    header: HeaderConfig,
+}
+
+impl Record for SliceRecord<'_> {
+    #[inline]
+    fn head(&self) -> &[u8] {
+        self.head
+    }
+
+    /// Return the FASTA sequence as byte slice.
+    /// Like with `RefRecord`, this is the **raw** sequence, which may contain
+    /// line breaks. Use `seq_lines()` or `full_seq()` to obtain the sequence
+    /// without line breaks.
+    #[inline]
+    fn seq(&self) -> &[u8] {
+        trim_cr(self.seq)
+    }
+
+    #[inline]
+    fn id_bytes(&self) -> &[u8] {
+        self.header.id(self.head)
+    }
+
+    #[inline]
+    fn desc_bytes(&self) -> Option<&[u8]> {
+        self.header.desc(self.head)
+    }
+
+    #[inline]
+    fn id_desc_bytes(&self) -> (&[u8], Option<&[u8]>) {
+        self.header.split(self.head)
+    }
+
+    #[inline]
+    fn id_desc(&self) -> Result<(&str, Option<&str>), Utf8Error> {
+        let (id, desc) = self.id_desc_bytes();
+        Ok((str::from_utf8(id)?, desc.map(str::from_utf8).transpose()?))
+    }
+
+    #[inline]
+    fn write<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        write_head(&mut writer, self.head)?;
+        write_seq_iter(&mut writer, self.seq_lines())
+    }
+
+    #[inline]
+    fn write_wrap<W: io::Write>(&self, mut writer: W, wrap: usize) -> io::Result<()> {
+        write_head(&mut writer, self.head)?;
+        write_wrap_seq_iter(&mut writer, self.seq_lines(), wrap)
+    }
+}
+
+impl<'a> SliceRecord<'a> {
+    /// Return the header line with the lifetime of the input slice
+    #[inline]
+    pub fn head_slice(&self) -> &'a [u8] {
+        self.head
+    }
+
+    /// Return the raw sequence with the lifetime of the input slice
+    #[inline]
+    pub fn seq_slice(&self) -> &'a [u8] {
+        trim_cr(self.seq)
+    }
+
+    /// Return an iterator over all sequence lines in the data
+    #[inline]
+    pub fn seq_lines(&self) -> SliceSeqLines<'a> {
+        SliceSeqLines {
+            data: self.seq,
+            len: self.num_seq_lines,
+        }
+    }
+
+    /// Returns the number of sequence lines.
+    /// Equivalent to `self.seq_lines().len()`
+    #[inline]
+    pub fn num_seq_lines(&self) -> usize {
+        self.num_seq_lines
+    }
+
+    /// Returns the full sequence. If the sequence consists of a single line,
+    /// then the sequence will be borrowed from the input slice. If there are
+    /// multiple lines, an owned copy will be created.
+    #[inline]
+    pub fn full_seq(&self) -> Cow<'a, [u8]> {
+        if self.num_seq_lines == 1 {
+            trim_cr(self.seq).into()
+        } else {
+            self.owned_seq().into()
+        }
+    }
+
+    /// Returns the sequence as owned `Vec` without line endings
+    #[inline]
+    pub fn owned_seq(&self) -> Vec<u8> {
+        let mut seq = Vec::with_capacity(self.seq.len());
+        for segment in self.seq_lines() {
+            seq.extend(segment);
+        }
+        seq
+    }
+
+    /// Creates an owned copy of the record.
+    #[inline]
+    pub fn to_owned_record(&self) -> OwnedRecord {
+        OwnedRecord {
+            head: self.head.to_vec(),
+            seq: self.owned_seq(),
+        }
+    }
+
+    /// Writes a record to the given `io::Write` instance
+    /// by just writing the unmodified input, which is faster than `SliceRecord::write`
+    #[inline]
+    pub fn write_unchanged<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.raw)?;
+        if self.raw.last() != Some(&b'\n') {
+            writer.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the sequence lines of a FASTA record returned by
+/// [`SliceReader`](struct.SliceReader.html).
+#[derive(Debug, Clone)]
+pub struct SliceSeqLines<'a> {
+    data: &'a [u8],
+    len: usize,
+}
+
+impl<'a> Iterator for SliceSeqLines<'a> {
+    type Item = &'a [u8];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        if self.len == 0 {
+            return Some(trim_cr(self.data));
+        }
+        let pos = memchr(b'\n', self.data).unwrap();
+        let line = &self.data[..pos];
+        self.data = &self.data[pos + 1..];
+        Some(trim_cr(line))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a> DoubleEndedIterator for SliceSeqLines<'a> {
+    #[inline]
+    fn next_back(&mut self) -> Option<&'a [u8]> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        if self.len == 0 {
+            return Some(trim_cr(self.data));
+        }
+        let pos = memrchr(b'\n', self.data).unwrap();
+        let line = &self.data[pos + 1..];
+        self.data = &self.data[..pos];
+        Some(trim_cr(line))
+    }
+}
+
+impl ExactSizeIterator for SliceSeqLines<'_> {
+    #[inline]
+    fn len(&self) -> usize {
+        self.len
+    }
+}
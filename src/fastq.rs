@@ -1,7 +1,32 @@
 //! Efficient FASTQ reading and writing
 //!
-
-use memchr::memchr;
+//! # Multi-line FASTQ
+//!
+//! By default, the parser expects every record to consist of exactly four lines.
+//! Wrapped FASTQ files, in which sequences and qualities may span several lines,
+//! can be read after enabling [`Reader::set_multiline`](struct.Reader.html#method.set_multiline).
+//! Sequence lines are then read until the `+` separator line, and quality lines
+//! until their total length equals the sequence length.
+//!
+//! ```
+//! use seq_io::fastq::{Reader, Record};
+//!
+//! let fastq = b"@id
+//! ACGT
+//! AC
+//! +
+//! IIII
+//! II
+//! ";
+//!
+//! let mut reader = Reader::new(&fastq[..]).set_multiline(true);
+//! let record = reader.next().unwrap().unwrap();
+//! assert_eq!(record.seq_lines().collect::<Vec<_>>(), vec![&b"ACGT"[..], b"AC"]);
+//! assert_eq!(&record.full_qual()[..], b"IIIIII");
+//! ```
+
+use memchr::{memchr, memchr_iter, memrchr};
+use std::borrow::Cow;
 use std::char;
 use std::fs::File;
 use std::io::{self, BufRead, Seek};
@@ -12,11 +37,23 @@ use std::str::{self, Utf8Error};
 
 use buffer_redux;
 
+use super::header::HeaderConfig;
 use super::policy::{BufPolicy, StdPolicy};
+use super::recovery::{Recovery, RecoveryStats, Skipped};
+use super::validation::Validation;
+use super::writer::{Head, WriterBase};
 use super::*;
 
+pub use super::ErrorPosition;
+
 use std::error::Error as StdError;
 
+pub mod index;
+pub mod quality;
+mod slice_reader;
+
+pub use self::slice_reader::{SliceReader, SliceRecord};
+
 type DefaultBufPolicy = StdPolicy;
 
 const BUFSIZE: usize = 64 * 1024;
@@ -55,8 +92,14 @@ pub struct Reader<R: io::Read, P = DefaultBufPolicy> {
     buf_pos: BufferPosition,
     incomplete_pos: Option<RecordPos>,
     position: Position,
+    // index of the current record
     state: State,
     buf_policy: P,
+    multiline: bool,
+    validation: Option<Validation>,
+    header: HeaderConfig,
+    recovery: Option<Recovery<Error>>,
+    last_head: LastHead,
 }
 
 impl<R> Reader<R, DefaultBufPolicy>
@@ -94,6 +137,11 @@ where
             position: Position::new(1, 0),
             state: State::New,
             buf_policy: StdPolicy,
+            multiline: false,
+            validation: None,
+            header: HeaderConfig::default(),
+            recovery: None,
+            last_head: LastHead::default(),
         }
     }
 }
@@ -128,6 +176,10 @@ impl Reader<File, DefaultBufPolicy> {
     }
 }
 
+compressed_reader_impl!();
+
+paired_reader_impl!(DefaultBufPolicy);
+
 impl<R, P> Reader<R, P>
 where
     R: io::Read,
@@ -143,9 +195,87 @@ where
             incomplete_pos: self.incomplete_pos,
             state: self.state,
             buf_policy: policy,
+            multiline: self.multiline,
+            validation: self.validation,
+            header: self.header,
+            recovery: self.recovery,
+            last_head: self.last_head,
         }
     }
 
+    /// Enables or disables parsing of multi-line FASTQ (disabled by default).
+    ///
+    /// In multi-line mode, sequence lines are read until a line starting with `+`
+    /// is found. Quality lines are then read until their total length
+    /// matches the sequence length (at least one quality line is always read).
+    /// `RefRecord::seq()` and `RefRecord::qual()` will then return the raw data
+    /// including line breaks, use `seq_lines()` / `qual_lines()` or
+    /// `full_seq()` / `full_qual()` to obtain the data without line breaks.
+    ///
+    /// Single-line FASTQ is still correctly parsed in this mode, but parsing
+    /// is slower.
+    #[inline]
+    pub fn set_multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+
+    /// Returns true if multi-line parsing is enabled
+    #[inline]
+    pub fn is_multiline(&self) -> bool {
+        self.multiline
+    }
+
+    /// Sets how the header line is split into ID and description (see the
+    /// [`header`](../header/index.html) module). The settings apply to records
+    /// returned by `next()` as well as records in a `RecordSet`.
+    #[inline]
+    pub fn set_header_config(mut self, config: HeaderConfig) -> Self {
+        self.header = config;
+        self
+    }
+
+    /// Enables validation of the records with the given settings (see the
+    /// [`validation`](../validation/index.html) module).
+    #[inline]
+    pub fn set_validation(mut self, validation: Validation) -> Self {
+        self.validation = Some(validation);
+        self
+    }
+
+    /// Enables recovery from malformed records (see the
+    /// [`recovery`](../recovery/index.html) module). Records with an invalid
+    /// start byte or separator, unequal sequence and quality lengths,
+    /// records failing [validation](#method.set_validation) or a
+    /// truncated record at the end of the input are skipped, and parsing
+    /// continues at the next line that looks like the start of a valid record.
+    /// Every skipped range is passed to `handler` instead of being returned
+    /// as error.
+    ///
+    /// In multi-line mode, every line starting with `@` is considered a possible
+    /// record start. Since quality lines may start with `@` as well, a single
+    /// malformed record can result in several reported errors.
+    /// With `read_record_set()`, the buffer may grow instead of being
+    /// emptied while skipping data if records were already found.
+    #[inline]
+    pub fn set_recovery<F>(mut self, handler: F) -> Self
+    where
+        F: FnMut(Skipped<Error>) + Send + Sync + 'static,
+    {
+        self.recovery = Some(Recovery::new(Box::new(handler)));
+        self
+    }
+
+    /// Returns the number of errors recovered from and the number of skipped
+    /// bytes so far. These are always zero if recovery is not enabled.
+    #[inline]
+    pub fn recovery_stats(&self) -> RecoveryStats {
+        self.recovery
+            .as_ref()
+            .map(|r| r.stats())
+            .unwrap_or_default()
+    }
+
     /// Returns the `BufPolicy` of the reader
     #[inline]
     pub fn policy(&self) -> &P {
@@ -169,7 +299,21 @@ where
     /// ```
     #[allow(clippy::should_implement_trait)]
     #[inline]
-    pub fn next(&mut self) -> Option<Result<RefRecord, Error>> {
+    pub fn next(&mut self) -> Option<Result<RefRecord<'_>, Error>> {
+        while let Err(e) = self.find_next()? {
+            try_opt!(self.recover_from(e, true));
+        }
+
+        Some(Ok(RefRecord {
+            buffer: self.get_buf(),
+            buf_pos: &self.buf_pos,
+            header: self.header,
+        }))
+    }
+
+    // Searches the next record, to which `buf_pos` points afterwards
+    #[inline]
+    fn find_next(&mut self) -> Option<Result<(), Error>> {
         // After next(), the state is always Parsing or Finished.
         match self.state {
             State::New => {
@@ -199,10 +343,11 @@ where
             }
         }
 
-        Some(Ok(RefRecord {
-            buffer: self.get_buf(),
-            buf_pos: &self.buf_pos,
-        }))
+        if self.validation.is_some() {
+            try_opt!(self.check_record());
+        }
+
+        Some(Ok(()))
     }
 
     /// Updates a [RecordSet](struct.RecordSet.html) with new data. The contents of the internal
@@ -227,8 +372,20 @@ where
         &mut self,
         rset: &mut RecordSet,
         n_records: Option<usize>,
+    ) -> Option<Result<(), Error>> {
+        self.read_record_set_multiple(rset, n_records, 1)
+    }
+
+    // Reads a record set with a number of records that is a multiple of `multiple`
+    // (unless the end of the input is reached)
+    fn read_record_set_multiple(
+        &mut self,
+        rset: &mut RecordSet,
+        n_records: Option<usize>,
+        multiple: usize,
     ) -> Option<Result<(), Error>> {
         debug_assert!(n_records.unwrap_or(usize::MAX) > 0);
+        debug_assert!(n_records.unwrap_or(0) % multiple == 0);
         // after read_record_set(), the state is always Positioned, Parsing or Finished
         match self.state {
             State::New => {
@@ -261,30 +418,53 @@ where
             if let Some(pos) = self.incomplete_pos.take() {
                 // resume incomplete search after previous read_record_set(), or
                 // after a seek() call.
-                if !try_opt!(self.resume_incomplete_search(pos, is_new)) {
-                    return None;
+                let found = match self.resume_incomplete_search(pos, is_new) {
+                    Ok(found) => found,
+                    Err(e) => {
+                        try_opt!(self.recover_from(e, rset.buf_positions.is_empty()));
+                        continue;
+                    }
+                };
+                if !found {
+                    // End of input: return the records already found
+                    // (if reading an exact number of records)
+                    if rset.buf_positions.is_empty() {
+                        return None;
+                    }
+                    break;
                 }
             } else {
                 // search the next complete record after `next()`, or in
                 // later iterations of this loop
-                if !try_opt!(self.search()) {
+                let found = match self.search() {
+                    Ok(found) => found,
+                    Err(e) => {
+                        try_opt!(self.recover_from(e, rset.buf_positions.is_empty()));
+                        continue;
+                    }
+                };
+                if !found {
                     // At least one record must be present. If not, continue
                     // with `resume_incomplete_search()` in next iteration
                     if rset.buf_positions.is_empty() {
                         continue;
                     }
-                    // Same if an exact number of records is required
-                    if let Some(n) = n_records {
-                        if rset.buf_positions.len() < n {
-                            if is_new {
-                                is_new = false;
-                            }
-                            continue;
-                        }
+                    // Same if an exact number of records is required,
+                    // or the number is not a multiple of `multiple`
+                    let n = rset.buf_positions.len();
+                    if n_records.map(|n_req| n < n_req).unwrap_or(false) || n % multiple != 0 {
+                        is_new = false;
+                        continue;
                     }
                     break;
                 }
             }
+            if self.validation.is_some() {
+                if let Err(e) = self.check_record() {
+                    try_opt!(self.recover_from(e, rset.buf_positions.is_empty()));
+                    continue;
+                }
+            }
             rset.buf_positions.push(self.buf_pos.clone());
             self.increment_record();
             if let Some(n) = n_records {
@@ -294,11 +474,105 @@ where
             }
         }
 
+        if rset.buf_positions.is_empty() {
+            // all remaining data was skipped in recovery mode
+            return None;
+        }
+
         rset.buffer.clear();
         rset.buffer.extend(self.get_buf());
+        rset.header = self.header;
         Some(Ok(()))
     }
 
+    // Returns the error if recovery is not enabled, otherwise skips the current
+    // (invalid) record, reports the error and moves to the start of the next
+    // plausible record. If `make_room` is false, no data is removed from the
+    // buffer, it is enlarged instead.
+    #[inline(never)]
+    fn recover_from(&mut self, error: Error, make_room: bool) -> Result<(), Error> {
+        if self.recovery.is_none() || !error.is_recoverable() {
+            return Err(error);
+        }
+        let line = self.position.line;
+        let start = self.position.byte;
+        let found = self.skip_record(make_room)?;
+        self.incomplete_pos = None;
+        self.state = if found {
+            State::Positioned
+        } else {
+            State::Finished
+        };
+        self.recovery.as_mut().unwrap().report(Skipped {
+            error,
+            line,
+            start,
+            end: self.position.byte,
+        });
+        Ok(())
+    }
+
+    // Moves the record start to the next line that looks like the start of
+    // a FASTQ record. Returns false if the end of the input was reached.
+    fn skip_record(&mut self, make_room: bool) -> Result<bool, Error> {
+        // record starts are searched after the first line break at or after this offset
+        let mut search_start = self.buf_pos.pos.0;
+        loop {
+            let buf = self.get_buf();
+            let eof = buf.len() < self.buf_reader.capacity();
+            let mut found = None;
+            // the whole buffer can be skipped, unless the last line
+            // needs more data in order to be checked
+            let mut keep = buf.len();
+            for line_start in memchr_iter(b'\n', &buf[search_start..]).map(|i| search_start + i + 1)
+            {
+                let is_record = match buf.get(line_start) {
+                    Some(b'@') if self.multiline => Some(true),
+                    Some(b'@') => is_fastq_record(&buf[line_start..], eof),
+                    Some(_) => Some(false),
+                    None => None,
+                };
+                match is_record {
+                    Some(true) => {
+                        found = Some(line_start);
+                        break;
+                    }
+                    Some(false) => {}
+                    None => {
+                        // keep the preceding line break
+                        keep = line_start - 1;
+                        break;
+                    }
+                }
+            }
+
+            if let Some(start) = found {
+                self.skip_to(start);
+                return Ok(true);
+            }
+            if eof {
+                self.skip_to(buf.len());
+                return Ok(false);
+            }
+
+            self.skip_to(keep);
+            if make_room && self.buf_pos.pos.0 > 0 {
+                self.make_room(RecordPos::Head);
+            } else {
+                self.grow()?;
+            }
+            search_start = self.buf_pos.pos.0;
+            fill_buf(&mut self.buf_reader)?;
+        }
+    }
+
+    // Moves the record start forward to `pos`, updating the position
+    fn skip_to(&mut self, pos: usize) {
+        self.position.line += self.count_lines(pos);
+        self.position.byte += (pos - self.buf_pos.pos.0) as u64;
+        self.buf_pos.pos.0 = pos;
+    }
+
     #[inline(never)]
     fn init(&mut self) -> Result<bool, Error> {
         let n = fill_buf(&mut self.buf_reader)?;
@@ -316,15 +590,31 @@ where
 
     // Sets starting points for next position
     fn increment_record(&mut self) {
+        self.last_head
+            .set(self.buf_pos.pos.0 + 1, self.buf_pos.seq - 1);
         self.position.byte += (self.buf_pos.pos.1 + 1 - self.buf_pos.pos.0) as u64;
-        self.position.line += 4;
+        self.position.line += if self.multiline {
+            self.count_lines(self.buf_pos.pos.1) + 1
+        } else {
+            4
+        };
+        self.position.record += 1;
         self.buf_pos.pos.0 = self.buf_pos.pos.1 + 1;
     }
 
+    // Number of line breaks between the start of the current record and `end`
+    fn count_lines(&self, end: usize) -> u64 {
+        memchr_iter(b'\n', &self.get_buf()[self.buf_pos.pos.0..end]).count() as u64
+    }
+
     // Reads the current record and returns true if found.
     // Returns false if incomplete because end of buffer reached,
     // meaning that the last record may be incomplete.
     fn search(&mut self) -> Result<bool, Error> {
+        if self.multiline {
+            return self.search_multiline(false);
+        }
+
         self.buf_pos.seq = unwrap_or!(self.find_line(self.buf_pos.pos.0), {
             self.incomplete_pos = Some(RecordPos::Head);
             return Ok(false);
@@ -353,6 +643,95 @@ where
         memchr(b'\n', &self.get_buf()[search_start..]).map(|pos| search_start + pos + 1)
     }
 
+    // Like `find_line`, but if `eof` is true, the end of the buffer is treated as
+    // the end of the last line (returning the start of a virtual line after it).
+    #[inline]
+    fn find_line_eof(&self, search_start: usize, eof: bool) -> Option<usize> {
+        let bufsize = self.get_buf().len();
+        if search_start > bufsize {
+            return None;
+        }
+        self.find_line(search_start)
+            .or(if eof { Some(bufsize + 1) } else { None })
+    }
+
+    // Searches a multi-line record, always starting from the record start.
+    // Returns true if found, false if incomplete (in which case searching has to
+    // be restarted from the head). If `eof` is true, the end of the buffer is
+    // treated as the end of the input.
+    fn search_multiline(&mut self, eof: bool) -> Result<bool, Error> {
+        let bufsize = self.get_buf().len();
+        let start = self.buf_pos.pos.0;
+        self.incomplete_pos = Some(RecordPos::Head);
+
+        if start >= bufsize {
+            return Ok(false);
+        }
+        let start_byte = self.get_buf()[start];
+        if start_byte != b'@' {
+            let rest = &self.get_buf()[start..];
+            if !eof && rest.split(|c| *c == b'\n').all(|l| trim_cr(l).is_empty()) {
+                // only empty lines, which are allowed at the end of the input
+                return Ok(false);
+            }
+            self.state = State::Finished;
+            return Err(Error::InvalidStart {
+                found: start_byte,
+                pos: self.get_error_pos(0, Some(1), false),
+            });
+        }
+
+        // header
+        self.buf_pos.seq = unwrap_or!(self.find_line_eof(start, eof), {
+            return Ok(false);
+        });
+        // sequence lines until the separator
+        let mut line_start = self.buf_pos.seq;
+        let mut seq_len = 0;
+        loop {
+            if line_start >= bufsize {
+                return Ok(false);
+            }
+            if self.get_buf()[line_start] == b'+' {
+                break;
+            }
+            let next = unwrap_or!(self.find_line_eof(line_start, eof), {
+                return Ok(false);
+            });
+            seq_len += trim_cr(&self.get_buf()[line_start..next - 1]).len();
+            line_start = next;
+        }
+        self.buf_pos.sep = line_start;
+        self.buf_pos.qual = unwrap_or!(self.find_line_eof(self.buf_pos.sep, eof), {
+            return Ok(false);
+        });
+        // quality lines until the length matches
+        line_start = self.buf_pos.qual;
+        let mut qual_len = 0;
+        loop {
+            let next = unwrap_or!(self.find_line_eof(line_start, eof), {
+                return Ok(false);
+            });
+            qual_len += trim_cr(&self.get_buf()[line_start..next - 1]).len();
+            line_start = next;
+            if qual_len >= seq_len {
+                break;
+            }
+        }
+        self.buf_pos.pos.1 = line_start - 1;
+        self.incomplete_pos = None;
+
+        if qual_len != seq_len {
+            self.state = State::Finished;
+            return Err(Error::UnequalLengths {
+                seq: seq_len,
+                qual: qual_len,
+                pos: self.get_error_pos(0, None, true),
+            });
+        }
+        Ok(true)
+    }
+
     // To be called when the end of the buffer is reached and `search` does not find
     // the next record (the buffer *must* be fully searched).
     // Incomplete bytes are moved to the start of the buffer unless make_room is false.
@@ -391,6 +770,10 @@ where
 
     #[inline(never)]
     fn check_end(&mut self, pos: RecordPos) -> Result<bool, Error> {
+        if self.multiline {
+            return self.check_end_multiline();
+        }
+
         if pos == RecordPos::Qual {
             // no line ending at end of last record
             self.buf_pos.pos.1 = self.get_buf().len();
@@ -405,14 +788,36 @@ where
         }
 
         Err(Error::UnexpectedEnd {
-            pos: self.get_error_pos(pos as u64, pos > RecordPos::Head),
+            pos: self.get_error_pos(pos as u64, None, pos > RecordPos::Head),
+        })
+    }
+
+    fn check_end_multiline(&mut self) -> Result<bool, Error> {
+        let rest = &self.get_buf()[self.buf_pos.pos.0..];
+        if rest.split(|c| *c == b'\n').all(|l| trim_cr(l).is_empty()) {
+            return Ok(false);
+        }
+
+        if self.search_multiline(true)? {
+            return Ok(true);
+        }
+
+        let line_offset = self.count_lines(self.get_buf().len());
+        Err(Error::UnexpectedEnd {
+            pos: self.get_error_pos(line_offset, None, line_offset > 0),
         })
     }
 
     // Resumes reading an incomplete record without
     // re-searching positions that were already found.
     // The resulting position may still be incomplete (-> Some(RecordPos)).
+    // In multi-line mode, the whole record is searched again.
     fn search_incomplete(&mut self, pos: RecordPos) -> Result<Option<RecordPos>, Error> {
+        if self.multiline {
+            self.search_multiline(false)?;
+            return Ok(self.incomplete_pos);
+        }
+
         if pos == RecordPos::Head {
             self.buf_pos.seq = unwrap_or!(self.find_line(self.buf_pos.pos.0), {
                 self.incomplete_pos = Some(RecordPos::Head);
@@ -459,6 +864,7 @@ where
     // move incomplete bytes to start of buffer and retry
     fn make_room(&mut self, incomplete_pos: RecordPos) {
         let consumed = self.buf_pos.pos.0;
+        self.last_head.consume(self.buf_reader.buffer(), consumed);
         self.buf_reader.consume(consumed);
         self.buf_reader.make_room();
 
@@ -483,7 +889,7 @@ where
             self.state = State::Finished;
             return Err(Error::InvalidStart {
                 found: start_byte,
-                pos: self.get_error_pos(0, false),
+                pos: self.get_error_pos(0, Some(1), false),
             });
         }
 
@@ -492,7 +898,7 @@ where
             self.state = State::Finished;
             return Err(Error::InvalidSep {
                 found: sep_byte,
-                pos: self.get_error_pos(2, true),
+                pos: self.get_error_pos(2, Some(1), true),
             });
         }
 
@@ -503,28 +909,88 @@ where
             return Err(Error::UnequalLengths {
                 seq: self.buf_pos.seq(self.get_buf()).len(),
                 qual: self.buf_pos.qual(self.get_buf()).len(),
-                pos: self.get_error_pos(0, true),
+                pos: self.get_error_pos(0, None, true),
             });
         }
         Ok(())
     }
 
+    // Checks the current record according to the validation settings
+    #[inline(never)]
+    fn check_record(&mut self) -> Result<(), Error> {
+        if let Some(e) = self.find_invalid() {
+            self.state = State::Finished;
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    fn find_invalid(&self) -> Option<Error> {
+        let validation = self.validation.as_ref().unwrap();
+        let buf = self.get_buf();
+
+        if let Some(i) = validation.find_invalid_seq(self.buf_pos.seq(buf)) {
+            let i = self.buf_pos.seq + i;
+            return Some(Error::InvalidSeq {
+                found: buf[i],
+                pos: self.get_byte_error_pos(i),
+            });
+        }
+
+        if validation.check_sep_id {
+            let sep = trim_cr(&buf[self.buf_pos.sep + 1..self.buf_pos.qual - 1]);
+            let head = self.buf_pos.head(buf);
+            if !sep.is_empty()
+                && sep != head
+                && sep != self.header.untrimmed_id(head)
+                && sep != self.header.id(head)
+            {
+                return Some(Error::SepIdMismatch {
+                    pos: self.get_error_pos(self.count_lines(self.buf_pos.sep), None, true),
+                });
+            }
+        }
+
+        if let Some(i) = validation.find_invalid_qual(self.buf_pos.qual(buf)) {
+            let i = self.buf_pos.qual + i;
+            return Some(Error::InvalidQual {
+                found: buf[i],
+                pos: self.get_byte_error_pos(i),
+            });
+        }
+        None
+    }
+
+    // Error position of the byte at buffer offset `i` within the current record
+    fn get_byte_error_pos(&self, i: usize) -> ErrorPosition {
+        let start = self.buf_pos.pos.0;
+        let line_start = memrchr(b'\n', &self.get_buf()[start..i])
+            .map(|p| start + p + 1)
+            .unwrap_or(start);
+        self.get_error_pos(self.count_lines(i), Some(i - line_start + 1), true)
+    }
+
     #[inline(never)]
-    fn get_error_pos(&self, line_offset: u64, parse_id: bool) -> ErrorPosition {
+    fn get_error_pos(
+        &self,
+        line_offset: u64,
+        column: Option<usize>,
+        parse_id: bool,
+    ) -> ErrorPosition {
         let id = if parse_id && self.buf_pos.seq - self.buf_pos.pos.0 > 1 {
-            let id = self
-                .buf_pos
-                .head(self.get_buf())
-                .split(|b| *b == b' ')
-                .next()
-                .unwrap();
+            let id = self.header.id(self.buf_pos.head(self.get_buf()));
             Some(String::from_utf8_lossy(id).into())
         } else {
             None
         };
         ErrorPosition {
             line: self.position.line + line_offset,
+            column,
+            record_line: self.position.line,
+            byte: self.position.byte,
+            record: self.position.record,
             id,
+            last_id: self.last_head.id(self.get_buf(), &self.header),
         }
     }
 
@@ -552,7 +1018,7 @@ where
     /// // second position
     /// reader.next().unwrap();
     ///
-    /// assert_eq!(reader.position(), &Position::new(5, 17));
+    /// assert_eq!(reader.position(), &Position::with_record(5, 17, 1));
     /// # }
     /// ```
     ///
@@ -564,6 +1030,14 @@ where
         &self.position
     }
 
+    // Sets the number of lines, bytes and records preceding the input, which is used if
+    // the input is only a part of a file (see `parallel::read_file_chunked`).
+    // Has to be called before reading.
+    pub(crate) fn set_input_offset(&mut self, lines: u64, bytes: u64, records: u64) {
+        debug_assert!(self.state == State::New);
+        self.position = Position::with_record(lines + 1, bytes, records);
+    }
+
     /// Returns a borrowed iterator over all FASTQ records. The records
     /// are owned (`OwnedRecord`), this is therefore slower than using
     /// `Reader::next()`.
@@ -598,7 +1072,7 @@ where
     /// # }
     /// ```
     #[inline]
-    pub fn records(&mut self) -> RecordsIter<R, P> {
+    pub fn records(&mut self) -> RecordsIter<'_, R, P> {
         RecordsIter { rdr: self }
     }
 
@@ -621,6 +1095,10 @@ where
     /// If an error was returned before, seeking to that position will return the same error.
     /// The same is not always true with `None`. If there is no newline character at the end of the
     /// file, the last record will be returned instead of `None`.
+    /// Records are counted from the record index of the position
+    /// ([`Position::record`](struct.Position.html#method.record)).
+    /// With the `bgzf` feature, seeking also works with BGZF compressed input
+    /// read by [`bgzf::Reader`](../bgzf/struct.Reader.html).
     ///
     /// # Example
     ///
@@ -658,6 +1136,7 @@ where
         let offset = to.byte as i64 - self.position.byte as i64;
         let pos = self.buf_pos.pos.0 as i64 + offset;
         self.position = to.clone();
+        self.last_head.clear();
         self.incomplete_pos = None;
         self.state = State::Positioned;
 
@@ -674,6 +1153,84 @@ where
     }
 }
 
+#[cfg(feature = "bgzf")]
+impl<R, P> Reader<crate::bgzf::Reader<R>, P>
+where
+    R: io::Read + Seek,
+    P: BufPolicy,
+{
+    /// Returns the current position like [`position()`](#method.position), but
+    /// the byte offset is a BGZF virtual offset, which can be used with
+    /// [`seek_virtual()`](#method.seek_virtual).
+    /// Requires the `bgzf` feature.
+    pub fn virtual_position(&mut self) -> Option<Position> {
+        let pos = self.position.clone();
+        let buf_start = pos.byte - self.buf_pos.pos.0 as u64;
+        let bgzf = self.buf_reader.get_mut();
+        bgzf.forget_before(buf_start);
+        bgzf.virtual_offset_at(pos.byte)
+            .map(|voffset| Position::with_record(pos.line, voffset, pos.record))
+    }
+
+    /// Seeks to a position obtained with
+    /// [`virtual_position()`](#method.virtual_position). Afterwards,
+    /// [`position()`](#method.position) reports the offset in the decompressed
+    /// data, like before (see the [`bgzf`](../bgzf/index.html) module).
+    /// Requires the `bgzf` feature.
+    pub fn seek_virtual(&mut self, to: &Position) -> Result<(), Error> {
+        let bgzf = self.buf_reader.get_mut();
+        bgzf.seek_virtual(to.byte)?;
+        let offset = bgzf.offset();
+        let buffered = self.buf_reader.buf_len();
+        self.buf_reader.consume(buffered);
+        self.position = Position::with_record(to.line, offset, to.record);
+        self.last_head.clear();
+        self.incomplete_pos = None;
+        self.state = State::Positioned;
+        fill_buf(&mut self.buf_reader)?;
+        self.buf_pos.reset(0);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<R, P> Reader<R, P>
+where
+    R: io::Read + Send,
+    P: BufPolicy + Send,
+{
+    /// Returns a rayon `ParallelIterator` over record sets, which are filled
+    /// by [`read_record_set`](#method.read_record_set) and processed by the
+    /// threads of the current rayon pool. The order of the record sets
+    /// is not preserved. See also
+    /// [`parallel::par_record_sets`](../parallel/fn.par_record_sets.html).
+    /// Requires the `rayon` feature.
+    #[inline]
+    pub fn par_record_sets(
+        self,
+    ) -> rayon::iter::IterBridge<crate::parallel::RecordSets<Reader<R, P>>> {
+        crate::parallel::par_record_sets(self)
+    }
+
+    /// Returns a rayon `ParallelIterator` over owned records, which are
+    /// read in batches like with [`par_record_sets`](#method.par_record_sets).
+    /// Requires the `rayon` feature.
+    pub fn par_records(
+        self,
+    ) -> impl rayon::iter::ParallelIterator<Item = Result<OwnedRecord, Error>> {
+        use rayon::iter::{Either, ParallelIterator};
+        self.par_record_sets().flat_map_iter(|rset| match rset {
+            Ok(rset) => Either::Left(
+                rset.into_iter()
+                    .map(|r| Ok(r.to_owned_record()))
+                    .collect::<Vec<_>>()
+                    .into_iter(),
+            ),
+            Err(e) => Either::Right(iter::once(Err(e))),
+        })
+    }
+}
+
 /// Borrowed iterator of `OwnedRecord`
 pub struct RecordsIter<'a, R, P = DefaultBufPolicy>
 where
@@ -714,17 +1271,25 @@ where
     }
 }
 
-/// Holds line number and byte offset of a FASTQ record
+/// Holds line number, byte offset and index of a FASTQ record
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Position {
     line: u64,
     byte: u64,
+    record: u64,
 }
 
 impl Position {
+    /// Creates a position of the first record (record index 0)
     #[inline]
     pub fn new(line: u64, byte: u64) -> Position {
-        Position { line, byte }
+        Position::with_record(line, byte, 0)
+    }
+
+    /// Creates a position with the given index of the record (0-based)
+    #[inline]
+    pub fn with_record(line: u64, byte: u64, record: u64) -> Position {
+        Position { line, byte, record }
     }
 
     /// Line number (starting with 1)
@@ -738,6 +1303,35 @@ impl Position {
     pub fn byte(&self) -> u64 {
         self.byte
     }
+
+    /// Index of the record (0-based), which is restored by `seek()`
+    #[inline]
+    pub fn record(&self) -> u64 {
+        self.record
+    }
+}
+
+// Checks whether `buf` starts with a single-line FASTQ record, of which the third
+// line starts with '+', and the sequence and quality lines have the same length.
+// Returns `None` if more data is needed.
+pub(crate) fn is_fastq_record(buf: &[u8], eof: bool) -> Option<bool> {
+    let mut lines: [&[u8]; 4] = [&[]; 4];
+    let mut rest = buf;
+    for (i, line) in lines.iter_mut().enumerate() {
+        let l = match memchr(b'\n', rest) {
+            Some(end) => {
+                let l = &rest[..end];
+                rest = &rest[end + 1..];
+                l
+            }
+            // the last record may not end with a line break
+            None if eof && i == 3 => rest,
+            None if eof => return Some(false),
+            None => return None,
+        };
+        *line = l.strip_suffix(b"\r").unwrap_or(l);
+    }
+    Some(lines[2].first() == Some(&b'+') && lines[1].len() == lines[3].len())
 }
 
 /// FASTQ parsing error
@@ -773,26 +1367,44 @@ pub enum Error {
         /// Position within file.
         pos: ErrorPosition,
     },
+    /// Sequence byte not part of the alphabet (only with validation)
+    InvalidSeq {
+        /// Byte found
+        found: u8,
+        /// Position within file
+        pos: ErrorPosition,
+    },
+    /// Quality byte outside of the allowed range (only with validation)
+    InvalidQual {
+        /// Byte found
+        found: u8,
+        /// Position within file
+        pos: ErrorPosition,
+    },
+    /// The separator line does not repeat the header or the record ID
+    /// (only with validation)
+    SepIdMismatch {
+        /// Position within file. `ErrorPosition::line` is the line of the separator.
+        pos: ErrorPosition,
+    },
     /// Size limit of buffer was reached, which happens if `policy::BufPolicy::grow_to()` returned
     /// `None`. This does not happen with the default `struct.DoubleUntil.html` policy.
     BufferLimit,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct ErrorPosition {
-    /// Line number where the error occurred (starting with 1)
-    pub line: u64,
-    /// ID of record if available
-    pub id: Option<String>,
-}
-
-impl fmt::Display for ErrorPosition {
-    #[inline]
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        if let Some(id) = self.id.as_ref() {
-            write!(f, "record '{}' at ", id)?;
-        }
-        write!(f, "line {}", self.line)
+impl Error {
+    // Returns true if the reader can continue after this error in recovery mode
+    fn is_recoverable(&self) -> bool {
+        matches!(
+            *self,
+            Error::UnequalLengths { .. }
+                | Error::InvalidStart { .. }
+                | Error::InvalidSep { .. }
+                | Error::UnexpectedEnd { .. }
+                | Error::InvalidSeq { .. }
+                | Error::InvalidQual { .. }
+                | Error::SepIdMismatch { .. }
+        )
     }
 }
 
@@ -821,6 +1433,23 @@ impl fmt::Display for Error {
             Error::UnexpectedEnd { ref pos } => {
                 write!(f, "FASTQ parse error: unexpected end of input ({}).", pos)
             }
+            Error::InvalidSeq { found, ref pos } => write!(
+                f,
+                "FASTQ validation error: invalid sequence byte '{}' ({}).",
+                (found as char).escape_default(),
+                pos
+            ),
+            Error::InvalidQual { found, ref pos } => write!(
+                f,
+                "FASTQ validation error: quality byte '{}' out of range ({}).",
+                (found as char).escape_default(),
+                pos
+            ),
+            Error::SepIdMismatch { ref pos } => write!(
+                f,
+                "FASTQ validation error: separator line does not match the header ({}).",
+                pos
+            ),
             Error::BufferLimit => write!(f, "FASTQ parse error: Buffer limit reached."),
         }
     }
@@ -867,6 +1496,10 @@ impl BufferPosition {
 
     #[inline]
     fn seq<'a>(&'a self, buffer: &'a [u8]) -> &'a [u8] {
+        if self.sep == self.seq {
+            // no sequence line (only possible with multi-line FASTQ)
+            return b"";
+        }
         trim_cr(&buffer[self.seq..self.sep - 1])
     }
 
@@ -923,6 +1556,52 @@ pub trait Record {
         Ok((h.next().unwrap(), h.next()))
     }
 
+    /// Parses the header line as Illumina read name
+    /// (see the [`illumina`](../illumina/index.html) module)
+    #[inline]
+    fn illumina_header(&self) -> Result<illumina::IlluminaHeader<'_>, illumina::Error> {
+        illumina::IlluminaHeader::parse(self.head())
+    }
+
+    /// Returns an iterator over the `key=value` and SAM-style tags in the
+    /// description (see the [`tags`](../tags/index.html) module)
+    #[inline]
+    fn tags(&self) -> tags::Tags<'_> {
+        tags::Tags::new(self.desc_bytes().unwrap_or_default())
+    }
+
+    /// Returns the first tag with the given name from the description
+    #[inline]
+    fn tag(&self, key: &[u8]) -> Option<tags::Tag<'_>> {
+        self.tags().get(key)
+    }
+
+    /// Returns the mean Phred score, or `None` if the record has no qualities
+    #[inline]
+    fn mean_qual(&self, encoding: quality::Encoding) -> Option<f64> {
+        quality::mean_qual(self.qual(), encoding)
+    }
+
+    /// Returns the minimum Phred score, or `None` if the record has no qualities
+    #[inline]
+    fn min_qual(&self, encoding: quality::Encoding) -> Option<u8> {
+        quality::min_qual(self.qual(), encoding)
+    }
+
+    /// Returns the expected number of sequencing errors, which is the sum
+    /// of the error probabilities (`10^(-Q/10)` for Phred scores)
+    #[inline]
+    fn expected_errors(&self, encoding: quality::Encoding) -> f64 {
+        quality::expected_errors(self.qual(), encoding)
+    }
+
+    /// Returns the fraction of bases with a Phred score of at least `min_qual`
+    /// (e.g. 30), or `None` if the record has no qualities
+    #[inline]
+    fn frac_qual_ge(&self, encoding: quality::Encoding, min_qual: u8) -> Option<f64> {
+        quality::frac_qual_ge(self.qual(), encoding, min_qual)
+    }
+
     /// Writes a record to the given `io::Write` instance
     #[inline]
     fn write<W: io::Write>(&self, writer: W) -> io::Result<()> {
@@ -935,6 +1614,7 @@ pub trait Record {
 pub struct RefRecord<'a> {
     buffer: &'a [u8],
     buf_pos: &'a BufferPosition,
+    header: HeaderConfig,
 }
 
 impl Record for RefRecord<'_> {
@@ -943,24 +1623,91 @@ impl Record for RefRecord<'_> {
         self.buf_pos.head(self.buffer)
     }
 
+    /// Return the FASTQ sequence as byte slice.
+    /// Note that with multi-line FASTQ, this method of `RefRecord` returns
+    /// the **raw** sequence, which may contain line breaks.
+    /// Use `seq_lines()` to iterate over all lines without
+    /// breaks, or use [`full_seq()`](struct.RefRecord.html#method.full_seq)
+    /// to access the whole sequence at once.
     #[inline]
     fn seq(&self) -> &[u8] {
         self.buf_pos.seq(self.buffer)
     }
 
+    /// Return the FASTQ qualities as byte slice.
+    /// With multi-line FASTQ, the raw data may contain line breaks
+    /// (see also `seq()`).
     #[inline]
     fn qual(&self) -> &[u8] {
         self.buf_pos.qual(self.buffer)
     }
+
+    #[inline]
+    fn id_bytes(&self) -> &[u8] {
+        self.header.id(self.head())
+    }
+
+    #[inline]
+    fn desc_bytes(&self) -> Option<&[u8]> {
+        self.header.desc(self.head())
+    }
+
+    #[inline]
+    fn id_desc_bytes(&self) -> (&[u8], Option<&[u8]>) {
+        self.header.split(self.head())
+    }
+
+    #[inline]
+    fn id_desc(&self) -> Result<(&str, Option<&str>), Utf8Error> {
+        let (id, desc) = self.id_desc_bytes();
+        Ok((str::from_utf8(id)?, desc.map(str::from_utf8).transpose()?))
+    }
+
+    #[inline]
+    fn write<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        write_iter(writer, self.head(), self.seq_lines(), self.qual_lines())
+    }
 }
 
 impl RefRecord<'_> {
+    /// Return an iterator over all sequence lines in the data.
+    /// With single-line FASTQ, there is always exactly one line.
+    #[inline]
+    pub fn seq_lines(&self) -> Lines<'_> {
+        Lines::new(self.seq())
+    }
+
+    /// Return an iterator over all quality lines in the data.
+    /// With single-line FASTQ, there is always exactly one line.
+    #[inline]
+    pub fn qual_lines(&self) -> Lines<'_> {
+        Lines::new(self.qual())
+    }
+
+    /// Returns the full sequence. If the sequence consists of a single line,
+    /// then the sequence will be borrowed from the underlying buffer
+    /// (equivalent to calling `RefRecord::seq()`). If there are multiple
+    /// lines, an owned copy will be created.
+    #[inline]
+    pub fn full_seq(&self) -> Cow<'_, [u8]> {
+        join_lines(self.seq())
+    }
+
+    /// Returns the full quality line. If the qualities consist of a single line,
+    /// then they will be borrowed from the underlying buffer
+    /// (equivalent to calling `RefRecord::qual()`). If there are multiple
+    /// lines, an owned copy will be created.
+    #[inline]
+    pub fn full_qual(&self) -> Cow<'_, [u8]> {
+        join_lines(self.qual())
+    }
+
     #[inline]
     pub fn to_owned_record(&self) -> OwnedRecord {
         OwnedRecord {
             head: self.head().to_vec(),
-            seq: self.seq().to_vec(),
-            qual: self.qual().to_vec(),
+            seq: self.full_seq().into_owned(),
+            qual: self.full_qual().into_owned(),
         }
     }
 
@@ -974,6 +1721,46 @@ impl RefRecord<'_> {
     }
 }
 
+#[inline]
+fn join_lines(data: &[u8]) -> Cow<'_, [u8]> {
+    if memchr(b'\n', data).is_none() {
+        return data.into();
+    }
+    let mut out = Vec::with_capacity(data.len());
+    for line in Lines::new(data) {
+        out.extend_from_slice(line);
+    }
+    out.into()
+}
+
+/// Iterator over the sequence or quality lines of a FASTQ record.
+pub struct Lines<'a> {
+    data: Option<&'a [u8]>,
+}
+
+impl<'a> Lines<'a> {
+    #[inline]
+    fn new(data: &'a [u8]) -> Self {
+        Lines { data: Some(data) }
+    }
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a [u8];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let data = self.data?;
+        if let Some(pos) = memchr(b'\n', data) {
+            self.data = Some(&data[pos + 1..]);
+            Some(trim_cr(&data[..pos]))
+        } else {
+            self.data = None;
+            Some(data)
+        }
+    }
+}
+
 /// A FASTQ record that ownes its data (requires allocations)
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct OwnedRecord {
@@ -982,6 +1769,16 @@ pub struct OwnedRecord {
     pub qual: Vec<u8>,
 }
 
+impl OwnedRecord {
+    /// Converts the quality scores from one encoding to another. For converting
+    /// many records, it is more efficient to reuse a
+    /// [`quality::Converter`](quality/struct.Converter.html).
+    #[inline]
+    pub fn convert_qual(&mut self, from: quality::Encoding, to: quality::Encoding) {
+        quality::Converter::new(from, to).convert(&mut self.qual);
+    }
+}
+
 impl Record for OwnedRecord {
     #[inline]
     fn head(&self) -> &[u8] {
@@ -1003,6 +1800,8 @@ impl Record for OwnedRecord {
 pub struct RecordSet {
     buffer: Vec<u8>,
     buf_positions: Vec<BufferPosition>,
+    #[serde(default)]
+    header: HeaderConfig,
 }
 
 impl RecordSet {
@@ -1040,6 +1839,7 @@ impl<'a> iter::IntoIterator for &'a RecordSet {
     fn into_iter(self) -> Self::IntoIter {
         RecordSetIter {
             buffer: &self.buffer,
+            header: self.header,
             pos: self.buf_positions.iter(),
         }
     }
@@ -1048,6 +1848,7 @@ impl<'a> iter::IntoIterator for &'a RecordSet {
 /// Iterator over record sets
 pub struct RecordSetIter<'a> {
     buffer: &'a [u8],
+    header: HeaderConfig,
     pos: slice::Iter<'a, BufferPosition>,
 }
 
@@ -1059,10 +1860,346 @@ impl<'a> Iterator for RecordSetIter<'a> {
         self.pos.next().map(|p| RefRecord {
             buffer: self.buffer,
             buf_pos: p,
+            header: self.header,
+        })
+    }
+}
+
+impl RecordSet {
+    /// Returns an iterator over consecutive pairs of records, as found in record sets
+    /// filled by [`InterleavedReader`](struct.InterleavedReader.html).
+    /// With an odd number of records, the last one is not returned.
+    #[inline]
+    pub fn pairs(&self) -> RecordSetPairs<'_> {
+        RecordSetPairs {
+            buffer: &self.buffer,
+            header: self.header,
+            pos: self.buf_positions.chunks_exact(2),
+        }
+    }
+}
+
+/// Iterator over pairs of records in a record set
+pub struct RecordSetPairs<'a> {
+    buffer: &'a [u8],
+    header: HeaderConfig,
+    pos: slice::ChunksExact<'a, BufferPosition>,
+}
+
+impl<'a> Iterator for RecordSetPairs<'a> {
+    type Item = (RefRecord<'a>, RefRecord<'a>);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pos.next().map(|p| {
+            (
+                RefRecord {
+                    buffer: self.buffer,
+                    buf_pos: &p[0],
+                    header: self.header,
+                },
+                RefRecord {
+                    buffer: self.buffer,
+                    buf_pos: &p[1],
+                    header: self.header,
+                },
+            )
         })
     }
 }
 
+/// Reader of interleaved paired-end FASTQ, in which the records of each pair
+/// directly follow each other. By default, the IDs of the records of each pair are
+/// compared (see [`paired::ids_match`](../paired/fn.ids_match.html)).
+///
+/// Records are read in batches into an internal [`RecordSet`](struct.RecordSet.html),
+/// which never splits a pair. The same is true for record sets obtained with
+/// [`read_record_set`](#method.read_record_set), and `InterleavedReader` can therefore
+/// be used with [`parallel::read_parallel`](../parallel/fn.read_parallel.html).
+///
+/// # Example:
+///
+/// ```
+/// use seq_io::fastq::{InterleavedReader, Reader, Record};
+///
+/// let fastq = b"@id/1\nACGT\n+\nIIII\n@id/2\nTTTT\n+\nIIII\n";
+///
+/// let mut reader = InterleavedReader::new(Reader::new(&fastq[..]));
+///
+/// while let Some(result) = reader.next() {
+///     let (rec1, rec2) = result.unwrap();
+///     assert_eq!(rec1.seq(), b"ACGT");
+///     assert_eq!(rec2.seq(), b"TTTT");
+/// }
+/// ```
+pub struct InterleavedReader<R: io::Read, P = DefaultBufPolicy> {
+    reader: Reader<R, P>,
+    rset: RecordSet,
+    // index of the next pair in `rset`
+    idx: usize,
+    check_ids: bool,
+    pairs: u64,
+}
+
+impl<R, P> InterleavedReader<R, P>
+where
+    R: io::Read,
+    P: BufPolicy,
+{
+    /// Creates a new interleaved reader. The IDs are checked by default.
+    #[inline]
+    pub fn new(reader: Reader<R, P>) -> Self {
+        InterleavedReader {
+            reader,
+            rset: RecordSet::default(),
+            idx: 0,
+            check_ids: true,
+            pairs: 0,
+        }
+    }
+
+    /// Sets whether the IDs of the records of each pair should be compared
+    /// (default: true).
+    #[inline]
+    pub fn set_check_ids(mut self, check: bool) -> Self {
+        self.check_ids = check;
+        self
+    }
+
+    /// Returns the number of pairs read so far
+    #[inline]
+    pub fn num_pairs(&self) -> u64 {
+        self.pairs
+    }
+
+    /// Returns the next pair of records. If the last record has no mate,
+    /// `paired::Error::CountMismatch` is returned.
+    #[allow(clippy::should_implement_trait, clippy::type_complexity)]
+    pub fn next(&mut self) -> Option<Result<(RefRecord<'_>, RefRecord<'_>), paired::Error<Error>>> {
+        if self.idx * 2 >= self.rset.len() {
+            let res = self
+                .reader
+                .read_record_set_multiple(&mut self.rset, None, 2)?;
+            if let Err(e) = res {
+                return Some(Err(paired::Error::Parse { input: 1, error: e }));
+            }
+            self.idx = 0;
+        }
+        let i = self.idx * 2;
+        self.idx += 1;
+        if i + 1 >= self.rset.len() {
+            return Some(Err(paired::Error::CountMismatch {
+                pairs: self.pairs,
+                longer: 1,
+            }));
+        }
+        let rec1 = RefRecord {
+            buffer: &self.rset.buffer,
+            buf_pos: &self.rset.buf_positions[i],
+            header: self.rset.header,
+        };
+        let rec2 = RefRecord {
+            buffer: &self.rset.buffer,
+            buf_pos: &self.rset.buf_positions[i + 1],
+            header: self.rset.header,
+        };
+        self.pairs += 1;
+        if self.check_ids && !paired::ids_match(rec1.head(), rec2.head()) {
+            return Some(Err(paired::Error::id_mismatch(
+                self.pairs,
+                rec1.head(),
+                rec2.head(),
+            )));
+        }
+        Some(Ok((rec1, rec2)))
+    }
+
+    /// Updates a [RecordSet](struct.RecordSet.html) with new data, like
+    /// [`Reader::read_record_set`](struct.Reader.html#method.read_record_set),
+    /// but the record set always contains complete pairs.
+    /// The pairs can be obtained using [`RecordSet::pairs`](struct.RecordSet.html#method.pairs).
+    /// This method should not be mixed with calls to [`next`](#method.next).
+    pub fn read_record_set(
+        &mut self,
+        rset: &mut RecordSet,
+    ) -> Option<Result<(), paired::Error<Error>>> {
+        if let Err(e) = self.reader.read_record_set_multiple(rset, None, 2)? {
+            return Some(Err(paired::Error::Parse { input: 1, error: e }));
+        }
+        if self.check_ids {
+            for (i, (rec1, rec2)) in rset.pairs().enumerate() {
+                if !paired::ids_match(rec1.head(), rec2.head()) {
+                    return Some(Err(paired::Error::id_mismatch(
+                        self.pairs + i as u64 + 1,
+                        rec1.head(),
+                        rec2.head(),
+                    )));
+                }
+            }
+        }
+        self.pairs += (rset.len() / 2) as u64;
+        if rset.len() % 2 != 0 {
+            return Some(Err(paired::Error::CountMismatch {
+                pairs: self.pairs,
+                longer: 1,
+            }));
+        }
+        Some(Ok(()))
+    }
+
+    /// Returns a reference to the underlying reader
+    #[inline]
+    pub fn get_ref(&self) -> &Reader<R, P> {
+        &self.reader
+    }
+
+    /// Returns the underlying reader
+    #[inline]
+    pub fn into_inner(self) -> Reader<R, P> {
+        self.reader
+    }
+}
+
+/// Buffered FASTQ writer with configurable output format.
+///
+/// By default, single-line FASTQ with UNIX line endings is written, and
+/// the `+` separator line contains no ID.
+///
+/// # Example
+///
+/// ```
+/// use seq_io::fastq::{Reader, Writer};
+/// use seq_io::LineEnding;
+///
+/// let input = b"@id desc\nACGT\n+\nIIII\n";
+///
+/// let mut reader = Reader::new(&input[..]);
+/// let mut writer = Writer::new(vec![])
+///     .set_line_ending(LineEnding::CrLf)
+///     .set_repeat_id(true);
+///
+/// while let Some(record) = reader.next() {
+///     writer.write_record(&record.unwrap()).unwrap();
+/// }
+/// assert_eq!(writer.num_records(), 1);
+///
+/// let output = writer.into_inner().unwrap();
+/// assert_eq!(&output[..], b"@id desc\r\nACGT\r\n+id desc\r\nIIII\r\n");
+/// ```
+pub struct Writer<W: io::Write> {
+    base: WriterBase<W>,
+    repeat_id: bool,
+    qual_converter: Option<quality::Converter>,
+    qual_buf: Vec<u8>,
+}
+
+impl<W: io::Write> Writer<W> {
+    /// Creates a new writer with a buffer size of 64 KiB
+    #[inline]
+    pub fn new(writer: W) -> Writer<W> {
+        Writer::with_capacity(BUFSIZE, writer)
+    }
+
+    /// Creates a new writer with a given buffer capacity
+    #[inline]
+    pub fn with_capacity(capacity: usize, writer: W) -> Writer<W> {
+        Writer {
+            base: WriterBase::with_capacity(capacity, writer),
+            repeat_id: false,
+            qual_converter: None,
+            qual_buf: vec![],
+        }
+    }
+
+    /// Sets the maximum width of sequence and quality lines, which results
+    /// in multi-line FASTQ. `None` (the default) writes four lines per record.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the width is `Some(0)`.
+    #[inline]
+    pub fn set_line_width(mut self, width: Option<usize>) -> Self {
+        assert!(width != Some(0), "line width must be > 0");
+        self.base.line_width = width;
+        self
+    }
+
+    writer_base_impl!();
+
+    /// Sets whether the header should be repeated on the `+` line (default: false)
+    #[inline]
+    pub fn set_repeat_id(mut self, repeat: bool) -> Self {
+        self.repeat_id = repeat;
+        self
+    }
+
+    /// Converts the quality scores from one encoding to another while writing
+    #[inline]
+    pub fn set_qual_conversion(mut self, from: quality::Encoding, to: quality::Encoding) -> Self {
+        self.qual_converter = Some(quality::Converter::new(from, to));
+        self
+    }
+
+    /// Writes a record. Sequence and qualities are written according to the
+    /// configured line width, regardless of the line structure of the input.
+    #[inline]
+    pub fn write_record<R: Record + ?Sized>(&mut self, record: &R) -> io::Result<()> {
+        self.write(record.head(), record.seq(), record.qual())
+    }
+
+    /// Writes data (not necessarily stored in a `Record` instance).
+    /// `seq` and `qual` may contain line breaks, which are removed.
+    #[inline]
+    pub fn write(&mut self, head: &[u8], seq: &[u8], qual: &[u8]) -> io::Result<()> {
+        self.write_inner(&Head::Line(head), seq, qual)
+    }
+
+    /// Writes data with the ID and description parts of the header supplied
+    /// separately.
+    #[inline]
+    pub fn write_parts(
+        &mut self,
+        id: &[u8],
+        desc: Option<&[u8]>,
+        seq: &[u8],
+        qual: &[u8],
+    ) -> io::Result<()> {
+        self.write_inner(&Head::Parts(id, desc), seq, qual)
+    }
+
+    fn write_inner(&mut self, head: &Head, seq: &[u8], qual: &[u8]) -> io::Result<()> {
+        self.base.write_head(b'@', head)?;
+        let n = self.base.write_lines(seq)?;
+        if self.repeat_id {
+            self.base.write_head(b'+', head)?;
+        } else {
+            self.base.write_line(b"+")?;
+        }
+        let qual = match self.qual_converter.as_ref() {
+            Some(conv) => {
+                self.qual_buf.clear();
+                self.qual_buf.extend_from_slice(qual);
+                conv.convert(&mut self.qual_buf);
+                &self.qual_buf
+            }
+            None => qual,
+        };
+        self.base.write_lines(qual)?;
+        self.base.record_written(n);
+        Ok(())
+    }
+}
+
+impl Writer<File> {
+    /// Creates a writer to a new file (an existing file will be truncated)
+    #[inline]
+    pub fn from_path<P: AsRef<Path>>(path: P) -> io::Result<Writer<File>> {
+        File::create(path).map(Writer::new)
+    }
+}
+
+compressed_writer_impl!();
+
 /// Helper function for writing data (not necessarily stored in a `Record` instance)
 /// to the FASTQ format
 #[inline]
@@ -1082,6 +2219,30 @@ pub fn write_to<W: io::Write>(
     Ok(())
 }
 
+/// Helper function for writing sequence and quality data supplied as iterators
+/// over line segments (such as `Lines`) to the FASTQ format.
+/// The segments are joined, so the output is always single-line FASTQ.
+#[inline]
+pub fn write_iter<'a, W, S, Q>(mut writer: W, head: &[u8], seq: S, qual: Q) -> io::Result<()>
+where
+    W: io::Write,
+    S: IntoIterator<Item = &'a [u8]>,
+    Q: IntoIterator<Item = &'a [u8]>,
+{
+    writer.write_all(b"@")?;
+    writer.write_all(head)?;
+    writer.write_all(b"\n")?;
+    for s in seq {
+        writer.write_all(s)?;
+    }
+    writer.write_all(b"\n+\n")?;
+    for q in qual {
+        writer.write_all(q)?;
+    }
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
 /// Helper function for writing data (not necessarily stored in a `Record` instance)
 /// to the FASTQ format. The ID and description parts of the header are supplied separately
 /// instead of a whole header line
@@ -1106,3 +2267,55 @@ pub fn write_parts<W: io::Write>(
     writer.write_all(b"\n")?;
     Ok(())
 }
+
+/// Writes the records of two paired-end record streams in interleaved
+/// order (the first record of the first stream, then the first record of
+/// the second stream, etc.). Returns the number of pairs written, or an
+/// error of kind `io::ErrorKind::InvalidData` if the number of records differs.
+///
+/// # Example:
+///
+/// ```
+/// use seq_io::fastq::{write_interleaved, Reader};
+///
+/// let r1 = b"@id/1\nACGT\n+\nIIII\n";
+/// let r2 = b"@id/2\nTTTT\n+\nIIII\n";
+///
+/// let mut out = vec![];
+/// let n = write_interleaved(
+///     &mut out,
+///     Reader::new(&r1[..]).records().map(Result::unwrap),
+///     Reader::new(&r2[..]).records().map(Result::unwrap),
+/// ).unwrap();
+/// assert_eq!(n, 1);
+/// assert_eq!(&out, b"@id/1\nACGT\n+\nIIII\n@id/2\nTTTT\n+\nIIII\n");
+/// ```
+pub fn write_interleaved<W, I1, I2>(mut writer: W, records1: I1, records2: I2) -> io::Result<u64>
+where
+    W: io::Write,
+    I1: IntoIterator,
+    I1::Item: Record,
+    I2: IntoIterator,
+    I2::Item: Record,
+{
+    let mut records2 = records2.into_iter();
+    let mut n = 0;
+    for rec1 in records1 {
+        let rec2 = records2.next().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                "first input has more records than second input",
+            )
+        })?;
+        rec1.write(&mut writer)?;
+        rec2.write(&mut writer)?;
+        n += 1;
+    }
+    if records2.next().is_some() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "second input has more records than first input",
+        ));
+    }
+    Ok(n)
+}
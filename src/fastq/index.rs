@@ -0,0 +1,371 @@
+//! Index of FASTQ files for random access to records by number or ID.
+//!
+//! The index is built in one pass over a [`Reader`](../struct.Reader.html),
+//! recording the [`Position`](../struct.Position.html) and ID of each record.
+//! It can be saved to a compact binary file and loaded again later.
+//! [`IndexedReader`](struct.IndexedReader.html) then uses
+//! [`Reader::seek`](../struct.Reader.html#method.seek) for retrieving
+//! records.
+//!
+//! # Example
+//!
+//! ```
+//! use seq_io::fastq::{Reader, Record};
+//! use seq_io::fastq::index::{Index, IndexedReader};
+//! use std::io::Cursor;
+//!
+//! let fastq = b"@id1
+//! ACGT
+//! +
+//! IIII
+//! @id2
+//! TGCA
+//! +
+//! IIII
+//! @id3
+//! CCCC
+//! +
+//! IIII
+//! ";
+//!
+//! let index = Index::build(&mut Reader::new(&fastq[..])).unwrap();
+//! assert_eq!(index.len(), 3);
+//!
+//! let mut reader = IndexedReader::new(Reader::new(Cursor::new(&fastq[..])), index);
+//!
+//! let rec = reader.get_by_id(b"id3").unwrap().unwrap();
+//! assert_eq!(rec.seq(), b"CCCC");
+//!
+//! let rec = reader.get_nth(1).unwrap().unwrap();
+//! assert_eq!(rec.id(), Ok("id2"));
+//!
+//! let mut range = reader.range(0..2).unwrap();
+//! while let Some(rec) = range.next() {
+//!     println!("{}", rec.unwrap().id().unwrap());
+//! }
+//! ```
+
+use std::error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Seek, Write};
+use std::ops::Range;
+use std::path::Path;
+
+use super::{Position, Reader, RefRecord};
+use crate::policy::BufPolicy;
+
+// Magic number at the start of index files (includes the format version)
+const MAGIC: &[u8; 8] = b"SQIOFQI\x02";
+
+/// Index of a FASTQ file, which stores the position and the ID of every record.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Index {
+    positions: Vec<Position>,
+    // concatenated IDs
+    ids: Vec<u8>,
+    // end of each ID in `ids`
+    id_ends: Vec<usize>,
+    // record numbers sorted by ID
+    by_id: Vec<usize>,
+}
+
+impl Index {
+    /// Builds an index by reading all records of a FASTQ reader.
+    pub fn build<R, P>(reader: &mut Reader<R, P>) -> Result<Index, Error>
+    where
+        R: io::Read,
+        P: BufPolicy,
+    {
+        let mut index = Index::default();
+        while let Some(rec) = reader.next() {
+            let rec = rec?;
+            index.ids.extend_from_slice(super::Record::id_bytes(&rec));
+            index.id_ends.push(index.ids.len());
+            // records are numbered from the start of the index
+            let pos = reader.position();
+            let record = index.positions.len() as u64;
+            index
+                .positions
+                .push(Position::with_record(pos.line(), pos.byte(), record));
+        }
+        index.sort_ids();
+        Ok(index)
+    }
+
+    /// Builds the index of a FASTQ file
+    pub fn build_from_path<P: AsRef<Path>>(path: P) -> Result<Index, Error> {
+        Index::build(&mut Reader::from_path(path)?)
+    }
+
+    /// Reads an index, which was previously saved with
+    /// [`write`](#method.write).
+    pub fn read<R: io::Read>(mut reader: R) -> Result<Index, Error> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(Error::InvalidIndex);
+        }
+        // The numbers are not trusted, no memory is reserved in advance
+        let n = read_usize(&mut reader)?;
+        let mut index = Index::default();
+        for i in 0..n {
+            let line = read_u64(&mut reader)?;
+            let byte = read_u64(&mut reader)?;
+            index
+                .positions
+                .push(Position::with_record(line, byte, i as u64));
+        }
+        for _ in 0..n {
+            let i = read_usize(&mut reader)?;
+            if i >= n {
+                return Err(Error::InvalidIndex);
+            }
+            index.by_id.push(i);
+        }
+        for _ in 0..n {
+            let end = read_usize(&mut reader)?;
+            if end < index.id_ends.last().cloned().unwrap_or(0) {
+                return Err(Error::InvalidIndex);
+            }
+            index.id_ends.push(end);
+        }
+        let len = index.id_ends.last().cloned().unwrap_or(0);
+        reader.take(len as u64).read_to_end(&mut index.ids)?;
+        if index.ids.len() != len {
+            return Err(Error::InvalidIndex);
+        }
+        Ok(index)
+    }
+
+    /// Reads an index file
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Index, Error> {
+        Index::read(io::BufReader::new(File::open(path)?))
+    }
+
+    /// Writes the index in a compact binary format: a magic number, followed
+    /// by the number of records, the positions (line and byte), the record
+    /// numbers sorted by ID (for lookups with [`find_id`](#method.find_id)),
+    /// the end offsets of the IDs and finally the concatenated IDs. All numbers
+    /// are written as 64-bit little endian integers.
+    pub fn write<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&(self.len() as u64).to_le_bytes())?;
+        for pos in &self.positions {
+            writer.write_all(&pos.line().to_le_bytes())?;
+            writer.write_all(&pos.byte().to_le_bytes())?;
+        }
+        for n in &self.by_id {
+            writer.write_all(&(*n as u64).to_le_bytes())?;
+        }
+        for end in &self.id_ends {
+            writer.write_all(&(*end as u64).to_le_bytes())?;
+        }
+        writer.write_all(&self.ids)
+    }
+
+    /// Writes the index to a file
+    pub fn to_path<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = io::BufWriter::new(File::create(path)?);
+        self.write(&mut writer)?;
+        writer.flush()
+    }
+
+    /// Returns the number of indexed records
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Returns true if the index contains no records
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    /// Returns the position of the nth record (0-based). The record index of
+    /// the position is `n`, also if the reader was not at the start of the
+    /// input when building the index.
+    #[inline]
+    pub fn position(&self, n: usize) -> Option<&Position> {
+        self.positions.get(n)
+    }
+
+    /// Returns the ID of the nth record (0-based)
+    #[inline]
+    pub fn id(&self, n: usize) -> Option<&[u8]> {
+        let end = *self.id_ends.get(n)?;
+        let start = if n == 0 { 0 } else { self.id_ends[n - 1] };
+        Some(&self.ids[start..end])
+    }
+
+    /// Returns the number of the record with the given ID. If the ID
+    /// occurs more than once, the first record is returned.
+    pub fn find_id(&self, id: &[u8]) -> Option<usize> {
+        let i = self.by_id.partition_point(|&n| self.id(n).unwrap() < id);
+        self.by_id
+            .get(i)
+            .cloned()
+            .filter(|&n| self.id(n).unwrap() == id)
+    }
+
+    fn sort_ids(&mut self) {
+        let mut by_id: Vec<_> = (0..self.len()).collect();
+        by_id.sort_by_key(|&n| self.id(n).unwrap());
+        self.by_id = by_id;
+    }
+}
+
+fn read_u64<R: io::Read>(mut reader: R) -> io::Result<u64> {
+    let mut b = [0; 8];
+    reader.read_exact(&mut b)?;
+    Ok(u64::from_le_bytes(b))
+}
+
+fn read_usize<R: io::Read>(reader: R) -> Result<usize, Error> {
+    usize::try_from(read_u64(reader)?).map_err(|_| Error::InvalidIndex)
+}
+
+/// Combines a [`Reader`](../struct.Reader.html) with an
+/// [`Index`](struct.Index.html) for accessing records by number or ID.
+pub struct IndexedReader<R: io::Read, P = super::DefaultBufPolicy> {
+    reader: Reader<R, P>,
+    index: Index,
+}
+
+impl<R, P> IndexedReader<R, P>
+where
+    R: io::Read + Seek,
+    P: BufPolicy,
+{
+    #[inline]
+    pub fn new(reader: Reader<R, P>, index: Index) -> Self {
+        IndexedReader { reader, index }
+    }
+
+    /// Returns the index
+    #[inline]
+    pub fn index(&self) -> &Index {
+        &self.index
+    }
+
+    /// Returns a mutable reference to the reader
+    #[inline]
+    pub fn reader_mut(&mut self) -> &mut Reader<R, P> {
+        &mut self.reader
+    }
+
+    /// Returns the reader and the index
+    #[inline]
+    pub fn into_parts(self) -> (Reader<R, P>, Index) {
+        (self.reader, self.index)
+    }
+
+    /// Returns the nth record (0-based), or `None` if `n` is out of range.
+    pub fn get_nth(&mut self, n: usize) -> Option<Result<RefRecord<'_>, super::Error>> {
+        let pos = self.index.position(n)?;
+        try_opt!(self.reader.seek(pos));
+        self.reader.next()
+    }
+
+    /// Returns the first record with the given ID, or `None` if not found.
+    pub fn get_by_id(&mut self, id: &[u8]) -> Option<Result<RefRecord<'_>, super::Error>> {
+        let n = self.index.find_id(id)?;
+        self.get_nth(n)
+    }
+
+    /// Seeks to the start of the given range of record numbers, and returns
+    /// a [`RangeReader`](struct.RangeReader.html), from which the records
+    /// can be obtained. The end of the range is truncated to the number
+    /// of records.
+    pub fn range(&mut self, range: Range<usize>) -> Result<RangeReader<'_, R, P>, super::Error> {
+        let end = range.end.min(self.index.len());
+        let remaining = end.saturating_sub(range.start);
+        if remaining > 0 {
+            let pos = self.index.position(range.start).unwrap();
+            self.reader.seek(pos)?;
+        }
+        Ok(RangeReader {
+            reader: &mut self.reader,
+            remaining,
+        })
+    }
+}
+
+/// Reads a range of records, returned by
+/// [`IndexedReader::range`](struct.IndexedReader.html#method.range).
+pub struct RangeReader<'a, R, P = super::DefaultBufPolicy>
+where
+    P: 'a,
+    R: io::Read + 'a,
+{
+    reader: &'a mut Reader<R, P>,
+    remaining: usize,
+}
+
+impl<R, P> RangeReader<'_, R, P>
+where
+    R: io::Read,
+    P: BufPolicy,
+{
+    /// Returns the next record of the range, or `None` if the end was reached.
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<Result<RefRecord<'_>, super::Error>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+        self.reader.next()
+    }
+
+    /// Returns the number of records that remain to be read
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+/// FASTQ index error
+#[derive(Debug)]
+pub enum Error {
+    /// io::Error
+    Io(io::Error),
+    /// Error parsing the FASTQ file
+    Fastq(super::Error),
+    /// The index file has an invalid format
+    InvalidIndex,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Io(ref e) => e.fmt(f),
+            Error::Fastq(ref e) => e.fmt(f),
+            Error::InvalidIndex => write!(f, "FASTQ index error: invalid index file."),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    #[inline]
+    fn from(e: io::Error) -> Error {
+        Error::Io(e)
+    }
+}
+
+impl From<super::Error> for Error {
+    #[inline]
+    fn from(e: super::Error) -> Error {
+        Error::Fastq(e)
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Io(ref err) => Some(err),
+            Error::Fastq(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
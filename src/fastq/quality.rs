@@ -0,0 +1,567 @@
+//! Quality score encodings.
+//!
+//! FASTQ files use one of three encodings for quality scores:
+//! [`Phred33`](enum.Encoding.html#variant.Phred33) (Sanger, Illumina 1.8+),
+//! [`Phred64`](enum.Encoding.html#variant.Phred64) (Illumina 1.3 - 1.7) and
+//! the historical [`Solexa`](enum.Encoding.html#variant.Solexa) encoding,
+//! which uses a different scale and allows negative scores (down to -5).
+//!
+//! This module allows guessing the encoding of a file, converting between
+//! encodings and obtaining numeric Phred scores or error probabilities.
+//! Summary statistics (mean and minimum quality, expected errors, fraction of
+//! high quality bases) are available as methods of the
+//! [`Record`](../trait.Record.html) trait.
+//!
+//! # Example
+//!
+//! ```
+//! use seq_io::fastq::quality::{guess_encoding, Encoding};
+//! use seq_io::fastq::{OwnedRecord, Reader, Record, Writer};
+//!
+//! let fastq = b"@id1
+//! ACGT
+//! +
+//! hhQ@
+//! @id2
+//! TGCA
+//! +
+//! hhhh
+//! ";
+//!
+//! // guess the encoding from the first 1000 records
+//! let enc = guess_encoding(&mut Reader::new(&fastq[..]), 1000).unwrap();
+//! assert_eq!(enc, Some(Encoding::Phred64));
+//!
+//! let mut reader = Reader::new(&fastq[..]);
+//! let mut record: OwnedRecord = reader.next().unwrap().unwrap().to_owned_record();
+//! let scores: Vec<_> = enc.unwrap().scores(record.qual()).collect();
+//! assert_eq!(scores, [40, 40, 17, 0]);
+//!
+//! // conversion of a record
+//! record.convert_qual(Encoding::Phred64, Encoding::Phred33);
+//! assert_eq!(record.qual(), b"II2!");
+//!
+//! // conversion while writing
+//! let mut writer = Writer::new(vec![])
+//!     .set_qual_conversion(Encoding::Phred64, Encoding::Phred33);
+//! while let Some(record) = reader.next() {
+//!     writer.write_record(&record.unwrap()).unwrap();
+//! }
+//! assert_eq!(writer.into_inner().unwrap(), b"@id2\nTGCA\n+\nIIII\n");
+//! ```
+
+use std::fmt;
+use std::iter;
+use std::slice;
+
+use super::{Error, Reader, Record};
+use crate::policy::BufPolicy;
+use crate::validation::{PHRED33, PHRED64, SOLEXA};
+
+/// Quality score encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Encoding {
+    /// Phred scores + 33 (Sanger, Illumina 1.8+)
+    Phred33,
+    /// Phred scores + 64 (Illumina 1.3 - 1.7)
+    Phred64,
+    /// Solexa scores + 64 (Solexa, Illumina < 1.3)
+    Solexa,
+}
+
+impl Encoding {
+    /// Returns the ASCII offset, which corresponds to a score of zero
+    #[inline]
+    pub fn offset(self) -> u8 {
+        match self {
+            Encoding::Phred33 => 33,
+            Encoding::Phred64 | Encoding::Solexa => 64,
+        }
+    }
+
+    /// Returns the inclusive range of valid quality bytes, which can be used
+    /// with [`Validation::set_qual_range`](../../validation/struct.Validation.html#method.set_qual_range).
+    #[inline]
+    pub fn range(self) -> (u8, u8) {
+        match self {
+            Encoding::Phred33 => PHRED33,
+            Encoding::Phred64 => PHRED64,
+            Encoding::Solexa => SOLEXA,
+        }
+    }
+
+    /// Returns the Phred score of a quality byte. Solexa scores are converted
+    /// to the Phred scale, bytes below the valid range are treated as the
+    /// lowest score.
+    #[inline]
+    pub fn score(self, byte: u8) -> u8 {
+        match self {
+            Encoding::Solexa => SOLEXA_TO_PHRED[solexa_index(byte)],
+            _ => byte.saturating_sub(self.offset()),
+        }
+    }
+
+    /// Returns the error probability of a quality byte (obtained from a
+    /// lookup table). Scores above 93 are treated as 93.
+    #[inline]
+    pub fn error_prob(self, byte: u8) -> f64 {
+        match self {
+            Encoding::Solexa => SOLEXA_ERROR_PROBS[solexa_index(byte)],
+            _ => PHRED_ERROR_PROBS[self.score(byte).min(93) as usize],
+        }
+    }
+
+    /// Returns an iterator over the Phred scores of a quality string.
+    /// Line breaks (as found in multi-line FASTQ) are skipped.
+    #[inline]
+    pub fn scores(self, qual: &[u8]) -> Scores<'_> {
+        Scores {
+            qual: qual.iter(),
+            encoding: self,
+        }
+    }
+
+    /// Returns an iterator over the error probabilities of a quality string.
+    /// Line breaks (as found in multi-line FASTQ) are skipped.
+    #[inline]
+    pub fn error_probs(self, qual: &[u8]) -> ErrorProbs<'_> {
+        ErrorProbs {
+            qual: qual.iter(),
+            encoding: self,
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Encoding::Phred33 => write!(f, "Phred+33"),
+            Encoding::Phred64 => write!(f, "Phred+64"),
+            Encoding::Solexa => write!(f, "Solexa+64"),
+        }
+    }
+}
+
+// Index into the Solexa lookup tables, bytes outside of the valid range are truncated
+#[inline]
+fn solexa_index(byte: u8) -> usize {
+    (byte.clamp(SOLEXA.0, SOLEXA.1) - SOLEXA.0) as usize
+}
+
+fn phred_to_solexa(score: u8) -> i16 {
+    if score == 0 {
+        return -5;
+    }
+    let q = 10. * (10f64.powf(f64::from(score) / 10.) - 1.).log10();
+    (q.round() as i16).max(-5)
+}
+
+#[inline]
+fn is_line_break(byte: u8) -> bool {
+    byte == b'\n' || byte == b'\r'
+}
+
+/// Iterator over Phred scores, created by
+/// [`Encoding::scores`](enum.Encoding.html#method.scores)
+#[derive(Debug, Clone)]
+pub struct Scores<'a> {
+    qual: slice::Iter<'a, u8>,
+    encoding: Encoding,
+}
+
+impl<'a> Iterator for Scores<'a> {
+    type Item = u8;
+
+    #[inline]
+    fn next(&mut self) -> Option<u8> {
+        let enc = self.encoding;
+        self.qual
+            .find(|&&b| !is_line_break(b))
+            .map(|&b| enc.score(b))
+    }
+}
+
+impl<'a> iter::FusedIterator for Scores<'a> {}
+
+/// Iterator over error probabilities, created by
+/// [`Encoding::error_probs`](enum.Encoding.html#method.error_probs)
+#[derive(Debug, Clone)]
+pub struct ErrorProbs<'a> {
+    qual: slice::Iter<'a, u8>,
+    encoding: Encoding,
+}
+
+impl<'a> Iterator for ErrorProbs<'a> {
+    type Item = f64;
+
+    #[inline]
+    fn next(&mut self) -> Option<f64> {
+        let enc = self.encoding;
+        self.qual
+            .find(|&&b| !is_line_break(b))
+            .map(|&b| enc.error_prob(b))
+    }
+}
+
+impl<'a> iter::FusedIterator for ErrorProbs<'a> {}
+
+/// Converts quality bytes from one encoding to another using a lookup table.
+/// Creating a converter for conversion to Solexa scores requires some
+/// calculations, so it should be reused.
+///
+/// Scores outside of the range of the target encoding are truncated, and
+/// bytes below the valid range of the source encoding are treated as the
+/// lowest score. Line breaks are left unchanged.
+#[derive(Clone)]
+pub struct Converter {
+    table: [u8; 256],
+}
+
+impl Converter {
+    /// Creates a new converter
+    pub fn new(from: Encoding, to: Encoding) -> Converter {
+        let mut table = [0; 256];
+        let (to_min, to_max) = to.range();
+        for (b, out) in table.iter_mut().enumerate() {
+            let b = b as u8;
+            *out = if is_line_break(b) || from == to {
+                b
+            } else {
+                let q = match to {
+                    Encoding::Solexa => phred_to_solexa(from.score(b)) + 64,
+                    _ => i16::from(from.score(b)) + i16::from(to.offset()),
+                };
+                q.clamp(i16::from(to_min), i16::from(to_max)) as u8
+            };
+        }
+        Converter { table }
+    }
+
+    /// Converts a single quality byte
+    #[inline]
+    pub fn convert_byte(&self, byte: u8) -> u8 {
+        self.table[byte as usize]
+    }
+
+    /// Converts a quality string in place
+    #[inline]
+    pub fn convert(&self, qual: &mut [u8]) {
+        for q in qual {
+            *q = self.table[*q as usize];
+        }
+    }
+}
+
+impl fmt::Debug for Converter {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Converter").finish_non_exhaustive()
+    }
+}
+
+/// Guesses the quality encoding from the range of quality bytes found.
+/// Quality strings are supplied with [`add`](#method.add).
+///
+/// The rules are the following:
+///
+/// * Bytes below `;` (Solexa score -5) only occur with Phred+33.
+/// * If no byte is above `J` (Phred+33 score 41), the data is assumed to be
+///   Phred+33. High quality Phred+33 data is much more common than Solexa or
+///   Phred+64 data with only low scores.
+/// * Otherwise, bytes from `;` to `?` only occur with Solexa.
+/// * If all bytes are `@` or higher, the encoding is assumed to be Phred+64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Guesser {
+    min: u8,
+    max: u8,
+}
+
+impl Default for Guesser {
+    fn default() -> Self {
+        Guesser {
+            min: u8::MAX,
+            max: 0,
+        }
+    }
+}
+
+impl Guesser {
+    /// Creates a new `Guesser`
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a quality string. Line breaks are ignored.
+    #[inline]
+    pub fn add(&mut self, qual: &[u8]) {
+        for &b in qual {
+            if !is_line_break(b) {
+                self.min = self.min.min(b);
+                self.max = self.max.max(b);
+            }
+        }
+    }
+
+    /// Returns the range of quality bytes seen so far, or `None` if no
+    /// quality data was added
+    #[inline]
+    pub fn range(&self) -> Option<(u8, u8)> {
+        if self.min > self.max {
+            return None;
+        }
+        Some((self.min, self.max))
+    }
+
+    /// Returns the most likely encoding, or `None` if no quality data was
+    /// added or some quality bytes are not valid in any encoding.
+    pub fn guess(&self) -> Option<Encoding> {
+        let (min, max) = self.range()?;
+        if min < PHRED33.0 || max > PHRED33.1 {
+            return None;
+        }
+        let enc = if min < SOLEXA.0 || max <= b'J' {
+            Encoding::Phred33
+        } else if min < PHRED64.0 {
+            Encoding::Solexa
+        } else {
+            Encoding::Phred64
+        };
+        Some(enc)
+    }
+}
+
+/// Guesses the quality encoding from (at most) the first `n` records of a
+/// reader (see [`Guesser`](struct.Guesser.html) for the rules). The records
+/// are consumed; readers of seekable input can be reset with
+/// `seek(&Position::new(1, 0))`.
+pub fn guess_encoding<R, P>(reader: &mut Reader<R, P>, n: usize) -> Result<Option<Encoding>, Error>
+where
+    R: std::io::Read,
+    P: BufPolicy,
+{
+    let mut guesser = Guesser::new();
+    for _ in 0..n {
+        match reader.next() {
+            Some(rec) => guesser.add(rec?.qual()),
+            None => break,
+        }
+    }
+    Ok(guesser.guess())
+}
+
+/// Returns the mean Phred score of a quality string, or `None` if it is empty.
+/// Line breaks are ignored.
+pub fn mean_qual(qual: &[u8], encoding: Encoding) -> Option<f64> {
+    let (sum, n) = encoding
+        .scores(qual)
+        .fold((0u64, 0usize), |(sum, n), q| (sum + u64::from(q), n + 1));
+    if n == 0 {
+        return None;
+    }
+    Some(sum as f64 / n as f64)
+}
+
+/// Returns the minimum Phred score of a quality string, or `None` if it is
+/// empty. Line breaks are ignored.
+#[inline]
+pub fn min_qual(qual: &[u8], encoding: Encoding) -> Option<u8> {
+    encoding.scores(qual).min()
+}
+
+/// Returns the expected number of errors (the sum of the error probabilities)
+/// of a quality string. Line breaks are ignored.
+#[inline]
+pub fn expected_errors(qual: &[u8], encoding: Encoding) -> f64 {
+    encoding.error_probs(qual).sum()
+}
+
+/// Returns the fraction of Phred scores that are at least `min_qual`,
+/// or `None` if the quality string is empty. Line breaks are ignored.
+pub fn frac_qual_ge(qual: &[u8], encoding: Encoding, min_qual: u8) -> Option<f64> {
+    let (n_ge, n) = encoding
+        .scores(qual)
+        .fold((0usize, 0usize), |(n_ge, n), q| {
+            (n_ge + (q >= min_qual) as usize, n + 1)
+        });
+    if n == 0 {
+        return None;
+    }
+    Some(n_ge as f64 / n as f64)
+}
+
+// Error probabilities of Phred scores 0 - 93 (10^(-Q/10))
+static PHRED_ERROR_PROBS: [f64; 94] = [
+    1.0,
+    0.7943282347242815,
+    0.6309573444801932,
+    0.5011872336272722,
+    0.3981071705534972,
+    0.31622776601683794,
+    0.251188643150958,
+    0.19952623149688797,
+    0.15848931924611134,
+    0.12589254117941673,
+    0.1,
+    0.07943282347242814,
+    0.06309573444801933,
+    0.05011872336272722,
+    0.039810717055349734,
+    0.03162277660168379,
+    0.025118864315095794,
+    0.0199526231496888,
+    0.015848931924611134,
+    0.012589254117941675,
+    0.01,
+    0.007943282347242814,
+    0.00630957344480193,
+    0.005011872336272725,
+    0.003981071705534973,
+    0.0031622776601683794,
+    0.0025118864315095794,
+    0.001995262314968879,
+    0.001584893192461114,
+    0.0012589254117941675,
+    0.001,
+    0.0007943282347242813,
+    0.000630957344480193,
+    0.0005011872336272725,
+    0.00039810717055349735,
+    0.00031622776601683794,
+    0.00025118864315095795,
+    0.00019952623149688788,
+    0.00015848931924611142,
+    0.00012589254117941674,
+    0.0001,
+    7.943282347242822e-05,
+    6.309573444801929e-05,
+    5.011872336272725e-05,
+    3.9810717055349695e-05,
+    3.1622776601683795e-05,
+    2.5118864315095822e-05,
+    1.9952623149688786e-05,
+    1.584893192461114e-05,
+    1.2589254117941661e-05,
+    1e-05,
+    7.943282347242822e-06,
+    6.30957344480193e-06,
+    5.011872336272725e-06,
+    3.981071705534969e-06,
+    3.162277660168379e-06,
+    2.5118864315095823e-06,
+    1.9952623149688787e-06,
+    1.584893192461114e-06,
+    1.2589254117941661e-06,
+    1e-06,
+    7.943282347242822e-07,
+    6.30957344480193e-07,
+    5.011872336272725e-07,
+    3.981071705534969e-07,
+    3.162277660168379e-07,
+    2.5118864315095823e-07,
+    1.9952623149688787e-07,
+    1.584893192461114e-07,
+    1.2589254117941662e-07,
+    1e-07,
+    7.943282347242822e-08,
+    6.30957344480193e-08,
+    5.011872336272725e-08,
+    3.981071705534969e-08,
+    3.162277660168379e-08,
+    2.511886431509582e-08,
+    1.9952623149688786e-08,
+    1.5848931924611143e-08,
+    1.2589254117941661e-08,
+    1e-08,
+    7.943282347242822e-09,
+    6.309573444801943e-09,
+    5.011872336272715e-09,
+    3.981071705534969e-09,
+    3.1622776601683795e-09,
+    2.511886431509582e-09,
+    1.9952623149688828e-09,
+    1.584893192461111e-09,
+    1.2589254117941663e-09,
+    1e-09,
+    7.943282347242822e-10,
+    6.309573444801942e-10,
+    5.011872336272714e-10,
+];
+
+// Error probabilities of Solexa scores -5 - 62 (10^(-Q/10) / (1 + 10^(-Q/10)))
+static SOLEXA_ERROR_PROBS: [f64; 68] = [
+    0.7597469266479577,
+    0.7152527510491986,
+    0.666139424583122,
+    0.613136820153143,
+    0.5573116337622928,
+    0.5,
+    0.44268836623770724,
+    0.386863179846857,
+    0.3338605754168779,
+    0.2847472489508014,
+    0.24025307335204213,
+    0.20076000891310175,
+    0.1663375308165619,
+    0.13680688860321,
+    0.11181576977811696,
+    0.09090909090909091,
+    0.07358755611757352,
+    0.059350943102767596,
+    0.0477267210342039,
+    0.03828650388225474,
+    0.030653430031715504,
+    0.024503367550335987,
+    0.019562303872579522,
+    0.015601662241829602,
+    0.012432735254442407,
+    0.009900990099009901,
+    0.007880683850330285,
+    0.006270012341433839,
+    0.004986878736687972,
+    0.0039652856191522015,
+    0.0031523091832602124,
+    0.002505592667285734,
+    0.0019912891707283193,
+    0.0015823852808017208,
+    0.0012573425113552934,
+    0.0009990009990009992,
+    0.0007936977781692436,
+    0.0006305594883398932,
+    0.0005009361708135986,
+    0.00039794874430487687,
+    0.00031612779762961766,
+    0.0002511255632614618,
+    0.0001994864287215303,
+    0.00015846420436223718,
+    0.0001258766942425033,
+    9.999000099990002e-05,
+    7.942651440013086e-05,
+    6.309175362748654e-05,
+    5.011621160218197e-05,
+    3.9809132225250454e-05,
+    3.162177663330557e-05,
+    2.5118233373599877e-05,
+    1.9952225050461358e-05,
+    1.5848680739948997e-05,
+    1.2589095630617653e-05,
+    9.99990000099999e-06,
+    7.943219252009557e-06,
+    6.309533634336061e-06,
+    5.011847217534302e-06,
+    3.98105585666614e-06,
+    3.162267660200002e-06,
+    2.5118801219519865e-06,
+    1.9952583339051167e-06,
+    1.5848906805786636e-06,
+    1.258923826902969e-06,
+    9.99999000001e-07,
+    7.943276037674389e-07,
+    6.309569463732736e-07,
+];
+
+// Phred scores corresponding to Solexa scores -5 - 62
+static SOLEXA_TO_PHRED: [u8; 68] = [
+    1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 7, 8, 9, 10, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21,
+    22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45,
+    46, 47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62,
+];
@@ -0,0 +1,502 @@
+use std::borrow::Cow;
+use std::io;
+use std::str::{self, Utf8Error};
+
+use memchr::{memchr, memchr_iter};
+
+use super::{
+    join_lines, write_iter, BufferPosition, Error, ErrorPosition, Lines, OwnedRecord, Position,
+    Record, RecordPos,
+};
+use crate::header::HeaderConfig;
+use crate::trim_cr;
+
+/// FASTQ parser working directly on a byte slice.
+///
+/// In contrast to [`Reader`](struct.Reader.html), there is no buffering and
+/// no copying involved: the returned [`SliceRecord`](struct.SliceRecord.html)s
+/// borrow from the input slice and not from the reader. Therefore,
+/// `SliceReader` implements `Iterator`, and the records can be kept in a
+/// `Vec` while parsing continues. The input can be a file read into memory
+/// at once or a memory-mapped file (see the [`mmap`](../mmap/index.html)
+/// module, which requires the `memmap` feature).
+///
+/// Parse errors are the same as with `Reader`. Sequence and quality
+/// validation and error recovery are not available.
+///
+/// # Example
+///
+/// ```
+/// use seq_io::fastq::{Record, SliceReader};
+///
+/// let fastq = b"@id1
+/// ACGT
+/// +
+/// IIII
+/// @id2
+/// TGCA
+/// +
+/// IIII
+/// ";
+///
+/// let records: Vec<_> = SliceReader::new(fastq)
+///     .collect::<Result<_, _>>()
+///     .unwrap();
+///
+/// assert_eq!(records.len(), 2);
+/// assert_eq!(records[1].id(), Ok("id2"));
+/// assert_eq!(records[1].seq(), b"TGCA");
+/// ```
+#[derive(Debug, Clone)]
+pub struct SliceReader<'a> {
+    data: &'a [u8],
+    buf_pos: BufferPosition,
+    position: Position,
+    started: bool,
+    finished: bool,
+    multiline: bool,
+    header: HeaderConfig,
+    // header of the last record returned
+    last_head: Option<&'a [u8]>,
+}
+
+impl<'a> SliceReader<'a> {
+    /// Creates a new reader for the given slice
+    #[inline]
+    pub fn new(data: &'a [u8]) -> SliceReader<'a> {
+        SliceReader {
+            data,
+            buf_pos: BufferPosition::default(),
+            position: Position::new(1, 0),
+            started: false,
+            finished: false,
+            multiline: false,
+            header: HeaderConfig::default(),
+            last_head: None,
+        }
+    }
+
+    /// Enables or disables multi-line FASTQ parsing (default: disabled),
+    /// see [`Reader::set_multiline`](struct.Reader.html#method.set_multiline).
+    #[inline]
+    pub fn set_multiline(mut self, multiline: bool) -> Self {
+        self.multiline = multiline;
+        self
+    }
+
+    /// Sets the header parsing configuration of the returned records
+    /// (see [`header`](../header/index.html) module).
+    #[inline]
+    pub fn set_header_config(mut self, config: HeaderConfig) -> Self {
+        self.header = config;
+        self
+    }
+
+    /// Returns the position of the current record (the record returned by
+    /// the last call to `next()`), or `None` if no record was read yet.
+    #[inline]
+    pub fn position(&self) -> Option<&Position> {
+        if !self.started {
+            return None;
+        }
+        Some(&self.position)
+    }
+
+    /// Returns the remaining input after the current record
+    #[inline]
+    pub fn remaining(&self) -> &'a [u8] {
+        if !self.started {
+            return self.data;
+        }
+        &self.data[(self.buf_pos.pos.1 + 1).min(self.data.len())..]
+    }
+
+    fn parse_next(&mut self) -> Result<Option<SliceRecord<'a>>, Error> {
+        // at the end of the input, the position of the last record is kept
+        let last = (self.buf_pos.clone(), self.position.clone());
+        let was_started = self.started;
+        if was_started {
+            self.increment_record();
+        }
+        self.started = true;
+        let found = if self.multiline {
+            self.search_multiline()?
+        } else {
+            self.search()?
+        };
+        if !found {
+            if was_started {
+                (self.buf_pos, self.position) = last;
+            } else {
+                self.started = false;
+            }
+            return Ok(None);
+        }
+        let data = self.data;
+        let p = &self.buf_pos;
+        let head = trim_cr(&data[p.pos.0 + 1..p.seq - 1]);
+        self.last_head = Some(head);
+        Ok(Some(SliceRecord {
+            head,
+            seq: if p.sep == p.seq {
+                b""
+            } else {
+                trim_cr(&data[p.seq..p.sep - 1])
+            },
+            qual: trim_cr(&data[p.qual..p.pos.1]),
+            raw: &data[p.pos.0..p.pos.1],
+            header: self.header,
+        }))
+    }
+
+    fn increment_record(&mut self) {
+        self.position.byte += (self.buf_pos.pos.1 + 1 - self.buf_pos.pos.0) as u64;
+        self.position.line += if self.multiline {
+            self.count_lines(self.buf_pos.pos.1) + 1
+        } else {
+            4
+        };
+        self.position.record += 1;
+        self.buf_pos.pos.0 = self.buf_pos.pos.1 + 1;
+    }
+
+    fn count_lines(&self, end: usize) -> u64 {
+        memchr_iter(b'\n', &self.data[self.buf_pos.pos.0..end]).count() as u64
+    }
+
+    #[inline]
+    fn find_line(&self, search_start: usize) -> Option<usize> {
+        memchr(b'\n', &self.data[search_start..]).map(|pos| search_start + pos + 1)
+    }
+
+    // The end of the input is treated as the end of the last line
+    #[inline]
+    fn find_line_eof(&self, search_start: usize) -> Option<usize> {
+        if search_start > self.data.len() {
+            return None;
+        }
+        self.find_line(search_start).or(Some(self.data.len() + 1))
+    }
+
+    fn search(&mut self) -> Result<bool, Error> {
+        let start = self.buf_pos.pos.0;
+        if start > self.data.len() {
+            return Ok(false);
+        }
+
+        self.buf_pos.seq = unwrap_or!(self.find_line(start), {
+            return self.check_end(RecordPos::Head);
+        });
+        self.buf_pos.sep = unwrap_or!(self.find_line(self.buf_pos.seq), {
+            return self.check_end(RecordPos::Seq);
+        });
+        self.buf_pos.qual = unwrap_or!(self.find_line(self.buf_pos.sep), {
+            return self.check_end(RecordPos::Sep);
+        });
+        self.buf_pos.pos.1 = unwrap_or!(self.find_line(self.buf_pos.qual), {
+            return self.check_end(RecordPos::Qual);
+        }) - 1;
+
+        self.validate()?;
+        Ok(true)
+    }
+
+    fn check_end(&mut self, pos: RecordPos) -> Result<bool, Error> {
+        if pos == RecordPos::Qual {
+            // no line ending at end of last record
+            self.buf_pos.pos.1 = self.data.len();
+            self.validate()?;
+            return Ok(true);
+        }
+
+        if self.rest_is_empty() {
+            return Ok(false);
+        }
+
+        Err(Error::UnexpectedEnd {
+            pos: self.get_error_pos(pos as u64, None, pos > RecordPos::Head),
+        })
+    }
+
+    fn rest_is_empty(&self) -> bool {
+        self.data[self.buf_pos.pos.0..]
+            .split(|c| *c == b'\n')
+            .all(|l| trim_cr(l).is_empty())
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let start_byte = self.data[self.buf_pos.pos.0];
+        if start_byte != b'@' {
+            return Err(Error::InvalidStart {
+                found: start_byte,
+                pos: self.get_error_pos(0, Some(1), false),
+            });
+        }
+
+        let sep_byte = self.data[self.buf_pos.sep];
+        if sep_byte != b'+' {
+            return Err(Error::InvalidSep {
+                found: sep_byte,
+                pos: self.get_error_pos(2, Some(1), true),
+            });
+        }
+
+        let qual_len = self.buf_pos.pos.1 - self.buf_pos.qual + 1;
+        let seq_len = self.buf_pos.sep - self.buf_pos.seq;
+        if seq_len != qual_len {
+            return Err(Error::UnequalLengths {
+                seq: self.buf_pos.seq(self.data).len(),
+                qual: self.buf_pos.qual(self.data).len(),
+                pos: self.get_error_pos(0, None, true),
+            });
+        }
+        Ok(())
+    }
+
+    fn search_multiline(&mut self) -> Result<bool, Error> {
+        let len = self.data.len();
+        let start = self.buf_pos.pos.0;
+        if start >= len {
+            return Ok(false);
+        }
+        if self.data[start] != b'@' {
+            if self.rest_is_empty() {
+                // only empty lines, which are allowed at the end of the input
+                return Ok(false);
+            }
+            return Err(Error::InvalidStart {
+                found: self.data[start],
+                pos: self.get_error_pos(0, Some(1), false),
+            });
+        }
+
+        // header
+        self.buf_pos.seq = unwrap_or!(self.find_line_eof(start), {
+            return self.unexpected_end();
+        });
+        // sequence lines until the separator
+        let mut line_start = self.buf_pos.seq;
+        let mut seq_len = 0;
+        loop {
+            if line_start >= len {
+                return self.unexpected_end();
+            }
+            if self.data[line_start] == b'+' {
+                break;
+            }
+            let next = unwrap_or!(self.find_line_eof(line_start), {
+                return self.unexpected_end();
+            });
+            seq_len += trim_cr(&self.data[line_start..next - 1]).len();
+            line_start = next;
+        }
+        self.buf_pos.sep = line_start;
+        self.buf_pos.qual = unwrap_or!(self.find_line_eof(self.buf_pos.sep), {
+            return self.unexpected_end();
+        });
+        // quality lines until the length matches
+        line_start = self.buf_pos.qual;
+        let mut qual_len = 0;
+        loop {
+            let next = unwrap_or!(self.find_line_eof(line_start), {
+                return self.unexpected_end();
+            });
+            qual_len += trim_cr(&self.data[line_start..next - 1]).len();
+            line_start = next;
+            if qual_len >= seq_len {
+                break;
+            }
+        }
+        self.buf_pos.pos.1 = line_start - 1;
+
+        if qual_len != seq_len {
+            return Err(Error::UnequalLengths {
+                seq: seq_len,
+                qual: qual_len,
+                pos: self.get_error_pos(0, None, true),
+            });
+        }
+        Ok(true)
+    }
+
+    fn unexpected_end(&self) -> Result<bool, Error> {
+        let line_offset = self.count_lines(self.data.len());
+        Err(Error::UnexpectedEnd {
+            pos: self.get_error_pos(line_offset, None, line_offset > 0),
+        })
+    }
+
+    fn get_error_pos(
+        &self,
+        line_offset: u64,
+        column: Option<usize>,
+        parse_id: bool,
+    ) -> ErrorPosition {
+        let id = if parse_id && self.buf_pos.seq - self.buf_pos.pos.0 > 1 {
+            let id = self.header.id(self.buf_pos.head(self.data));
+            Some(String::from_utf8_lossy(id).into())
+        } else {
+            None
+        };
+        ErrorPosition {
+            line: self.position.line + line_offset,
+            column,
+            record_line: self.position.line,
+            byte: self.position.byte,
+            record: self.position.record,
+            id,
+            last_id: crate::last_id(self.last_head, &self.header),
+        }
+    }
+}
+
+impl<'a> Iterator for SliceReader<'a> {
+    type Item = Result<SliceRecord<'a>, Error>;
+
+    /// Searches the next FASTQ record and returns a
+    /// [`SliceRecord`](struct.SliceRecord.html) that borrows its data from
+    /// the input slice. After an error, no more records are returned.
+    #[inline]
+    fn next(&mut self) -> Option<Result<SliceRecord<'a>, Error>> {
+        if self.finished {
+            return None;
+        }
+        match self.parse_next() {
+            Ok(Some(rec)) => Some(Ok(rec)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// A FASTQ record returned by [`SliceReader`](struct.SliceReader.html), which
+/// borrows its data from the input slice (lifetime `'a`), independently of
+/// the reader.
+#[derive(Debug, Clone, Copy)]
+pub struct SliceRecord<'a> {
+    head: &'a [u8],
+    seq: &'a [u8],
+    qual: &'a [u8],
+    raw: &'a [u8],
+    header: HeaderConfig,
+}
+
+impl Record for SliceRecord<'_> {
+    #[inline]
+    fn head(&self) -> &[u8] {
+        self.head
+    }
+
+    /// Return the FASTQ sequence as byte slice.
+    /// With multi-line FASTQ, this is the **raw** sequence, which may
+    /// contain line breaks (see [`RefRecord::seq`](struct.RefRecord.html)).
+    #[inline]
+    fn seq(&self) -> &[u8] {
+        self.seq
+    }
+
+    /// Return the FASTQ qualities as byte slice.
+    /// With multi-line FASTQ, the raw data may contain line breaks.
+    #[inline]
+    fn qual(&self) -> &[u8] {
+        self.qual
+    }
+
+    #[inline]
+    fn id_bytes(&self) -> &[u8] {
+        self.header.id(self.head)
+    }
+
+    #[inline]
+    fn desc_bytes(&self) -> Option<&[u8]> {
+        self.header.desc(self.head)
+    }
+
+    #[inline]
+    fn id_desc_bytes(&self) -> (&[u8], Option<&[u8]>) {
+        self.header.split(self.head)
+    }
+
+    #[inline]
+    fn id_desc(&self) -> Result<(&str, Option<&str>), Utf8Error> {
+        let (id, desc) = self.id_desc_bytes();
+        Ok((str::from_utf8(id)?, desc.map(str::from_utf8).transpose()?))
+    }
+
+    #[inline]
+    fn write<W: io::Write>(&self, writer: W) -> io::Result<()> {
+        write_iter(writer, self.head, self.seq_lines(), self.qual_lines())
+    }
+}
+
+impl<'a> SliceRecord<'a> {
+    /// Return the header line with the lifetime of the input slice
+    #[inline]
+    pub fn head_slice(&self) -> &'a [u8] {
+        self.head
+    }
+
+    /// Return the (raw) sequence with the lifetime of the input slice
+    #[inline]
+    pub fn seq_slice(&self) -> &'a [u8] {
+        self.seq
+    }
+
+    /// Return the (raw) qualities with the lifetime of the input slice
+    #[inline]
+    pub fn qual_slice(&self) -> &'a [u8] {
+        self.qual
+    }
+
+    /// Return an iterator over all sequence lines in the data.
+    /// With single-line FASTQ, there is always exactly one line.
+    #[inline]
+    pub fn seq_lines(&self) -> Lines<'a> {
+        Lines::new(self.seq)
+    }
+
+    /// Return an iterator over all quality lines in the data.
+    /// With single-line FASTQ, there is always exactly one line.
+    #[inline]
+    pub fn qual_lines(&self) -> Lines<'a> {
+        Lines::new(self.qual)
+    }
+
+    /// Returns the full sequence, which is only copied if it consists of
+    /// multiple lines.
+    #[inline]
+    pub fn full_seq(&self) -> Cow<'a, [u8]> {
+        join_lines(self.seq)
+    }
+
+    /// Returns the full quality line, which is only copied if it consists of
+    /// multiple lines.
+    #[inline]
+    pub fn full_qual(&self) -> Cow<'a, [u8]> {
+        join_lines(self.qual)
+    }
+
+    /// Creates an owned copy of the record.
+    #[inline]
+    pub fn to_owned_record(&self) -> OwnedRecord {
+        OwnedRecord {
+            head: self.head.to_vec(),
+            seq: self.full_seq().into_owned(),
+            qual: self.full_qual().into_owned(),
+        }
+    }
+
+    /// Writes a record to the given `io::Write` instance
+    /// by just writing the unmodified input, which is faster than `SliceRecord::write`
+    #[inline]
+    pub fn write_unchanged<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.raw)?;
+        writer.write_all(b"\n")
+    }
+}
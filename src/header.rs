@@ -0,0 +1,137 @@
+//! Configuration of header parsing.
+//!
+//! By default, the ID of a record is everything before the first space of the
+//! header line, and the description is everything after it. A
+//! [`HeaderConfig`](struct.HeaderConfig.html) supplied to
+//! `fasta::Reader::set_header_config()` or `fastq::Reader::set_header_config()`
+//! changes how the `id()`, `desc()` and `id_desc*()` methods of the records
+//! returned by `next()` or stored in a `RecordSet` split the header.
+//!
+//! ```
+//! use seq_io::fastq::{Reader, Record};
+//! use seq_io::header::{Delimiter, HeaderConfig};
+//!
+//! let fastq = b"@gi|123|ref/1\tdesc
+//! ACGT
+//! +
+//! IIII
+//! ";
+//!
+//! let config = HeaderConfig::new()
+//!     .set_delimiter(Delimiter::Whitespace)
+//!     .set_truncate_pipe(true)
+//!     .set_strip_read_number(true);
+//! let mut reader = Reader::new(&fastq[..]).set_header_config(config);
+//!
+//! let record = reader.next().unwrap().unwrap();
+//! assert_eq!(record.id(), Ok("gi"));
+//! assert_eq!(record.desc(), Some(Ok("desc")));
+//! ```
+
+/// Characters separating the ID from the description
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Delimiter {
+    /// Space (the default)
+    Space,
+    /// Tab
+    Tab,
+    /// Space or tab, whichever comes first
+    Whitespace,
+}
+
+impl Default for Delimiter {
+    fn default() -> Self {
+        Delimiter::Space
+    }
+}
+
+impl Delimiter {
+    #[inline]
+    fn find(self, head: &[u8]) -> Option<usize> {
+        match self {
+            Delimiter::Space => memchr::memchr(b' ', head),
+            Delimiter::Tab => memchr::memchr(b'\t', head),
+            Delimiter::Whitespace => memchr::memchr2(b' ', b'\t', head),
+        }
+    }
+}
+
+/// Header parsing settings. The default settings split the header at the
+/// first space and do not modify the ID.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct HeaderConfig {
+    delimiter: Delimiter,
+    truncate_pipe: bool,
+    strip_read_number: bool,
+}
+
+impl HeaderConfig {
+    /// Creates a new `HeaderConfig` with default settings
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the delimiter between ID and description (default: `Delimiter::Space`)
+    #[inline]
+    pub fn set_delimiter(mut self, delimiter: Delimiter) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Sets whether the ID should be truncated at the first `|` (default: false)
+    #[inline]
+    pub fn set_truncate_pipe(mut self, truncate: bool) -> Self {
+        self.truncate_pipe = truncate;
+        self
+    }
+
+    /// Sets whether `/1` and `/2` read number suffixes should be removed from
+    /// the ID (default: false)
+    #[inline]
+    pub fn set_strip_read_number(mut self, strip: bool) -> Self {
+        self.strip_read_number = strip;
+        self
+    }
+
+    /// Splits a header line into ID and description (if present)
+    #[inline]
+    pub fn split<'a>(&self, head: &'a [u8]) -> (&'a [u8], Option<&'a [u8]>) {
+        let (id, desc) = match self.delimiter.find(head) {
+            Some(i) => (&head[..i], Some(&head[i + 1..])),
+            None => (head, None),
+        };
+        (self.trim_id(id), desc)
+    }
+
+    /// Returns the ID of a header line
+    #[inline]
+    pub fn id<'a>(&self, head: &'a [u8]) -> &'a [u8] {
+        self.split(head).0
+    }
+
+    /// Returns the description of a header line, if present
+    #[inline]
+    pub fn desc<'a>(&self, head: &'a [u8]) -> Option<&'a [u8]> {
+        self.delimiter.find(head).map(|i| &head[i + 1..])
+    }
+
+    // ID before removing read numbers and truncating at `|`
+    #[inline]
+    pub(crate) fn untrimmed_id<'a>(&self, head: &'a [u8]) -> &'a [u8] {
+        self.delimiter.find(head).map_or(head, |i| &head[..i])
+    }
+
+    #[inline]
+    fn trim_id<'a>(&self, mut id: &'a [u8]) -> &'a [u8] {
+        if self.strip_read_number && (id.ends_with(b"/1") || id.ends_with(b"/2")) {
+            id = &id[..id.len() - 2];
+        }
+        if self.truncate_pipe {
+            if let Some(i) = memchr::memchr(b'|', id) {
+                id = &id[..i];
+            }
+        }
+        id
+    }
+}
@@ -0,0 +1,254 @@
+//! Parsing of Illumina read names.
+//!
+//! [`IlluminaHeader`](struct.IlluminaHeader.html) is a zero-copy view of the
+//! fields of an Illumina header line, which can be obtained from FASTA and FASTQ
+//! records with `Record::illumina_header()`. The following formats are recognized:
+//!
+//! * Casava 1.8+: `instrument:run:flowcell:lane:tile:x:y[:umi] read:filtered:control:index`.
+//!   The description part is optional.
+//! * Older Illumina pipelines: `instrument:lane:tile:x:y[#index][/read]`.
+//!   The `/read` suffix is also recognized after Casava 1.8+ IDs.
+//! * IDs from the Sequence Read Archive (`SRR001666.1`, optionally followed
+//!   by a read number: `SRR001666.1.2`), followed by the original Illumina
+//!   read name in the description. The SRA ID is available as
+//!   [`SraId`](struct.SraId.html).
+//!
+//! ```
+//! use seq_io::fastq::{Reader, Record};
+//!
+//! let fastq = b"@EAS139:136:FC706VJ:2:2104:15343:197393 1:Y:18:ATCACG
+//! ACGT
+//! +
+//! IIII
+//! ";
+//!
+//! let mut reader = Reader::new(&fastq[..]);
+//! let record = reader.next().unwrap().unwrap();
+//! let h = record.illumina_header().unwrap();
+//! assert_eq!(h.instrument, "EAS139");
+//! assert_eq!(h.flowcell, Some("FC706VJ"));
+//! assert_eq!((h.lane, h.tile, h.x, h.y), (2, 2104, 15343, 197393));
+//! assert_eq!(h.read, Some(1));
+//! assert_eq!(h.filtered, Some(true));
+//! assert_eq!(h.index, Some("ATCACG"));
+//! ```
+
+use std::error;
+use std::fmt;
+use std::str::{self, FromStr, Utf8Error};
+
+/// Fields of an Illumina header line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IlluminaHeader<'a> {
+    /// SRA ID, if the header line is from the Sequence Read Archive
+    pub sra: Option<SraId<'a>>,
+    /// Instrument name
+    pub instrument: &'a str,
+    /// Run number (not present in the legacy format)
+    pub run: Option<u32>,
+    /// Flowcell ID (not present in the legacy format)
+    pub flowcell: Option<&'a str>,
+    /// Flowcell lane
+    pub lane: u32,
+    /// Tile number within the flowcell lane
+    pub tile: u32,
+    /// X coordinate of the cluster within the tile
+    pub x: u32,
+    /// Y coordinate of the cluster within the tile
+    pub y: u32,
+    /// UMI sequence (eighth field of the Casava 1.8+ format)
+    pub umi: Option<&'a str>,
+    /// Read number (1 or 2 for paired-end reads)
+    pub read: Option<u8>,
+    /// Whether the read was filtered (`Y`) or not (`N`)
+    pub filtered: Option<bool>,
+    /// Control number (0 if none of the control bits are on)
+    pub control: Option<u32>,
+    /// Index sequence or sample number
+    pub index: Option<&'a str>,
+}
+
+impl<'a> IlluminaHeader<'a> {
+    /// Parses a header line (without the leading `>` or `@`)
+    pub fn parse(head: &'a [u8]) -> Result<IlluminaHeader<'a>, Error> {
+        let head = str::from_utf8(head).map_err(Error::Utf8)?;
+        let mut parts = head.splitn(2, ' ');
+        let mut id = parts.next().unwrap();
+        let mut desc = parts.next();
+        let sra = SraId::parse(id);
+        if sra.is_some() {
+            // the original read name follows in the description
+            let mut parts = desc.ok_or(Error::NoIlluminaId)?.splitn(2, ' ');
+            id = parts.next().unwrap();
+            desc = parts.next();
+        }
+
+        let mut read = None;
+        if let Some((rest, r)) = id.rsplit_once('/') {
+            if !r.is_empty() && r.bytes().all(|b| b.is_ascii_digit()) {
+                read = Some(parse_field(r, "read")?);
+                id = rest;
+            }
+        }
+        let mut index = None;
+        if let Some((rest, i)) = id.rsplit_once('#') {
+            index = Some(i).filter(|i| !i.is_empty());
+            id = rest;
+        }
+
+        let mut fields = [""; 8];
+        let mut n = 0;
+        for f in id.split(':') {
+            if n < fields.len() {
+                fields[n] = f;
+            }
+            n += 1;
+        }
+        let mut header = match n {
+            5 => IlluminaHeader {
+                sra,
+                instrument: fields[0],
+                run: None,
+                flowcell: None,
+                lane: parse_field(fields[1], "lane")?,
+                tile: parse_field(fields[2], "tile")?,
+                x: parse_field(fields[3], "x")?,
+                y: parse_field(fields[4], "y")?,
+                umi: None,
+                read,
+                filtered: None,
+                control: None,
+                index,
+            },
+            7 | 8 => IlluminaHeader {
+                sra,
+                instrument: fields[0],
+                run: Some(parse_field(fields[1], "run")?),
+                flowcell: Some(fields[2]),
+                lane: parse_field(fields[3], "lane")?,
+                tile: parse_field(fields[4], "tile")?,
+                x: parse_field(fields[5], "x")?,
+                y: parse_field(fields[6], "y")?,
+                umi: Some(fields[7]).filter(|_| n == 8),
+                read,
+                filtered: None,
+                control: None,
+                index,
+            },
+            _ => return Err(Error::FieldCount(n)),
+        };
+
+        // Casava 1.8+ description (read:filtered:control:index)
+        let desc = desc.and_then(|d| d.split(' ').next());
+        if let Some(desc) = desc.filter(|d| d.contains(':')) {
+            let mut fields = desc.splitn(4, ':');
+            header.read = Some(parse_field(fields.next().unwrap(), "read")?);
+            header.filtered = match fields.next() {
+                Some("Y") => Some(true),
+                Some("N") => Some(false),
+                _ => return Err(Error::InvalidField("filtered")),
+            };
+            let control = fields.next().ok_or(Error::InvalidField("control"))?;
+            header.control = Some(parse_field(control, "control")?);
+            if let Some(i) = fields.next().filter(|i| !i.is_empty()) {
+                header.index = Some(i);
+            }
+        }
+        Ok(header)
+    }
+
+    /// Returns true if the header has the legacy format of Illumina
+    /// pipelines before Casava 1.8 (without run number and flowcell ID)
+    #[inline]
+    pub fn is_legacy(&self) -> bool {
+        self.run.is_none()
+    }
+}
+
+#[inline]
+fn parse_field<T: FromStr>(s: &str, name: &'static str) -> Result<T, Error> {
+    s.parse().map_err(|_| Error::InvalidField(name))
+}
+
+/// ID of a read from the Sequence Read Archive (`SRR001666.1` or `SRR001666.1.2`).
+/// Accessions starting with `ERR` and `DRR` are recognized as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SraId<'a> {
+    /// Run accession (`SRR001666`)
+    pub accession: &'a str,
+    /// Spot number
+    pub spot: u64,
+    /// Read number within the spot, if present
+    pub read: Option<u8>,
+}
+
+impl<'a> SraId<'a> {
+    /// Parses a record ID, returns `None` if it is not an SRA ID
+    pub fn parse(id: &'a str) -> Option<SraId<'a>> {
+        let mut parts = id.splitn(3, '.');
+        let accession = parts.next().unwrap();
+        let (prefix, number) = (accession.get(..3)?, &accession[3..]);
+        if !matches!(prefix, "SRR" | "ERR" | "DRR") || !is_number(number) {
+            return None;
+        }
+        let spot = parts.next().filter(|s| is_number(s))?.parse().ok()?;
+        let read = match parts.next() {
+            Some(r) if is_number(r) => Some(r.parse().ok()?),
+            Some(_) => return None,
+            None => None,
+        };
+        Some(SraId {
+            accession,
+            spot,
+            read,
+        })
+    }
+}
+
+#[inline]
+fn is_number(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Illumina header parsing error
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The header line is not valid UTF-8
+    Utf8(Utf8Error),
+    /// The ID does not have 5 (legacy), 7 or 8 (Casava 1.8+) fields delimited
+    /// by `:`. Contains the number of fields found.
+    FieldCount(usize),
+    /// A field could not be parsed. Contains the name of the field.
+    InvalidField(&'static str),
+    /// The header line has an SRA ID, but no Illumina read name follows
+    NoIlluminaId,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Utf8(ref e) => write!(f, "Illumina header error: {}", e),
+            Error::FieldCount(n) => write!(
+                f,
+                "Illumina header error: expected 5, 7 or 8 fields in ID, found {}.",
+                n
+            ),
+            Error::InvalidField(name) => {
+                write!(f, "Illumina header error: invalid '{}' field.", name)
+            }
+            Error::NoIlluminaId => write!(
+                f,
+                "Illumina header error: no Illumina read name found after SRA ID."
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Utf8(ref e) => Some(e),
+            _ => None,
+        }
+    }
+}
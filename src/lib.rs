@@ -1,6 +1,7 @@
 //! This library provides an(other) attempt at high performance FASTA and FASTQ parsing and writing.
-//! The FASTA parser can read and write multi-line files. The FASTQ parser supports only single
-//! lines.
+//! The FASTA parser can read and write multi-line files. The FASTQ parser expects single
+//! lines by default, but multi-line FASTQ can be read after enabling
+//! [`fastq::Reader::set_multiline`](fastq/struct.Reader.html#method.set_multiline).
 //!
 //! By default, the parsers avoid allocations and copying as much as possible.
 //! [`fasta::RefRecord`](fasta/struct.RefRecord.html) and
@@ -93,6 +94,23 @@
 //! For information on how to create a custom policy, refer to the
 //! [`policy`](policy) module docs.
 //!
+//! FASTA records of any size (e.g. whole chromosomes) can be read with
+//! [`fasta::Reader::next_stream`](fasta/struct.Reader.html#method.next_stream),
+//! which returns the sequence in chunks and never grows the buffer beyond
+//! the size of the header line.
+//!
+//! # Compressed input
+//!
+//! Gzip, bzip2, xz and zstd compressed files can be read transparently
+//! using `Reader::from_path_auto()`, after enabling the according cargo features.
+//! See the [`compression`](compression) module for more information.
+//!
+//! # Paired-end reads
+//!
+//! `fastq::PairedReader` and `fasta::PairedReader` read two files in lockstep
+//! and check that the IDs of the records match.
+//! See the [`paired`](paired) module for more information.
+//!
 //! # Owned records
 //! Both readers also provide iterators similar to *Rust-Bio*, which return owned data. This
 //! is slower, but make sense, e.g. if the records are collected in to a vector:
@@ -105,6 +123,22 @@
 //! let records: Result<Vec<_>, _> = reader.records().collect();
 //! ```
 //!
+//! # Input held in memory
+//! If the whole input is available as byte slice (or as memory-mapped file,
+//! see the [`mmap`](mmap/index.html) module, which requires the `memmap` feature),
+//! `fasta::SliceReader` and `fastq::SliceReader` parse it without any
+//! buffering or copying. Their records borrow from the input slice, and can
+//! therefore be collected into a vector without allocating:
+//!
+//! ```
+//! use seq_io::fasta::{Record, SliceReader};
+//!
+//! let fasta = b">id1\nACGT\n>id2\nTGCA\n";
+//!
+//! let records: Vec<_> = SliceReader::new(fasta).collect::<Result<_, _>>().unwrap();
+//! assert_eq!(records[0].seq(), b"ACGT");
+//! ```
+//!
 //! # Parallel processing
 //! Functions for parallel processing can be found in the [`parallel`](parallel/index.html) module
 
@@ -133,10 +167,233 @@ macro_rules! unwrap_or {
     };
 }
 
+#[cfg(feature = "bgzf")]
+pub mod bgzf;
+#[macro_use]
+pub mod compression;
+#[macro_use]
+pub mod paired;
+#[macro_use]
+mod writer;
 pub mod fasta;
 pub mod fastq;
+pub mod header;
+pub mod illumina;
+#[cfg(feature = "memmap")]
+pub mod mmap;
 pub mod parallel;
 pub mod policy;
+pub mod recovery;
+pub mod tags;
+pub mod validation;
+
+/// Line terminator used by [`fasta::Writer`](fasta/struct.Writer.html) and
+/// [`fastq::Writer`](fastq/struct.Writer.html)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    /// UNIX line ending (`\n`), the default
+    Lf,
+    /// Windows line ending (`\r\n`)
+    CrLf,
+}
+
+impl LineEnding {
+    /// Returns the line terminator as byte slice
+    #[inline]
+    pub fn as_bytes(&self) -> &'static [u8] {
+        match *self {
+            LineEnding::Lf => b"\n",
+            LineEnding::CrLf => b"\r\n",
+        }
+    }
+}
+
+impl Default for LineEnding {
+    #[inline]
+    fn default() -> LineEnding {
+        LineEnding::Lf
+    }
+}
+
+/// Position of a parse error, shared by [`fasta::Error`](fasta/enum.Error.html)
+/// and [`fastq::Error`](fastq/enum.Error.html).
+///
+/// More fields may be added in the future, therefore this struct can only be
+/// constructed within this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ErrorPosition {
+    /// Line number where the error occurred (starting with 1)
+    pub line: u64,
+    /// Column of the offending byte (starting with 1), if the error
+    /// was caused by a single byte. This is only the case with `InvalidStart`,
+    /// `InvalidSep` (FASTQ) and the validation errors `InvalidSeq` and
+    /// `InvalidQual` (see [`validation`](validation/index.html)), otherwise
+    /// the column is `None`.
+    pub column: Option<usize>,
+    /// Line number of the start of the record, in which the error occurred
+    pub record_line: u64,
+    /// Byte offset of the start of the record, in which the error occurred
+    pub byte: u64,
+    /// Index of the record (starting with 0). Records are counted from the
+    /// start of the input, or from the record index of the position given to
+    /// the last `seek()` (see `Position::record()`).
+    pub record: u64,
+    /// ID of the record, in which the error occurred (*not* the ID of the
+    /// preceding record). `None` if the header of the record is not known,
+    /// which is the case with `InvalidStart` errors and if the input ends
+    /// within the header line.
+    pub id: Option<String>,
+    /// ID of the last record that was parsed successfully (by `next()` or
+    /// `read_record_set()`) before the error occurred. `None` if the error
+    /// occurred in the first record, or in the first record after a `seek()`.
+    pub last_id: Option<String>,
+}
+
+impl ErrorPosition {
+    /// Returns the position of the start of the FASTQ record, in which the
+    /// error occurred. It can be used with
+    /// [`fastq::Reader::seek`](fastq/struct.Reader.html#method.seek) for
+    /// reading the record again, e.g. after fixing the input.
+    #[inline]
+    pub fn to_position(&self) -> fastq::Position {
+        fastq::Position::with_record(self.record_line, self.byte, self.record)
+    }
+
+    /// Returns the position of the start of the FASTA record, in which the
+    /// error occurred, which can be used with
+    /// [`fasta::Reader::seek`](fasta/struct.Reader.html#method.seek).
+    #[inline]
+    pub fn to_fasta_position(&self) -> fasta::Position {
+        fasta::Position::with_record(self.record_line, self.byte, self.record)
+    }
+}
+
+impl fmt::Display for ErrorPosition {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(id) = self.id.as_ref() {
+            write!(f, "record '{}' at ", id)?;
+        }
+        write!(f, "line {}", self.line)?;
+        if let Some(column) = self.column {
+            write!(f, ", column {}", column)?;
+        }
+        write!(
+            f,
+            " (record no. {} starting at byte {}",
+            self.record + 1,
+            self.byte
+        )?;
+        if let Some(id) = self.last_id.as_ref() {
+            write!(f, ", after record '{}'", id)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Writes sequence or quality data, which may span several lines (as returned by
+/// `RefRecord::seq()` of multi-line records), either as a single line or wrapped
+/// to a maximum width. Returns the number of bytes written without line endings.
+fn write_lines<W>(
+    mut writer: W,
+    data: &[u8],
+    width: Option<usize>,
+    ending: LineEnding,
+) -> io::Result<usize>
+where
+    W: io::Write,
+{
+    let mut start = 0;
+    let segments = memchr::memchr_iter(b'\n', data)
+        .chain(std::iter::once(data.len()))
+        .map(|end| {
+            let segment = trim_cr(&data[start..end]);
+            start = end + 1;
+            segment
+        });
+    let mut n = 0;
+    let mut n_line = 0;
+    for mut segment in segments {
+        n += segment.len();
+        if let Some(width) = width {
+            while segment.len() > width - n_line {
+                let (line, rest) = segment.split_at(width - n_line);
+                writer.write_all(line)?;
+                writer.write_all(ending.as_bytes())?;
+                segment = rest;
+                n_line = 0;
+            }
+        }
+        writer.write_all(segment)?;
+        n_line += segment.len();
+    }
+    writer.write_all(ending.as_bytes())?;
+    Ok(n)
+}
+
+// Header line of the last record that was parsed successfully, which is
+// either still in the buffer of the reader or was copied before the buffer
+// was emptied. Used for `ErrorPosition::last_id`.
+#[derive(Debug, Default)]
+struct LastHead {
+    // buffer range of the header (not yet trimmed)
+    range: Option<(usize, usize)>,
+    copied: Option<Vec<u8>>,
+}
+
+impl LastHead {
+    // Sets the buffer range of the last header
+    #[inline]
+    fn set(&mut self, start: usize, end: usize) {
+        self.range = Some((start, end));
+    }
+
+    #[inline]
+    fn set_copied(&mut self, head: &[u8]) {
+        self.range = None;
+        let copied = self.copied.get_or_insert_with(Vec::new);
+        copied.clear();
+        copied.extend_from_slice(head);
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        self.range = None;
+        self.copied = None;
+    }
+
+    // To be called before the first `consumed` bytes of `buf` are removed from the buffer
+    #[inline]
+    fn consume(&mut self, buf: &[u8], consumed: usize) {
+        if let Some((start, end)) = self.range {
+            if start < consumed {
+                self.set_copied(&buf[start..end]);
+            } else {
+                self.range = Some((start - consumed, end - consumed));
+            }
+        }
+    }
+
+    fn get<'a>(&'a self, buf: &'a [u8]) -> Option<&'a [u8]> {
+        match self.range {
+            Some((start, end)) => Some(trim_cr(&buf[start..end])),
+            None => self.copied.as_deref().map(trim_cr),
+        }
+    }
+
+    // Returns the ID of the last record according to the header settings
+    fn id(&self, buf: &[u8], header: &header::HeaderConfig) -> Option<String> {
+        last_id(self.get(buf), header)
+    }
+}
+
+// Returns the ID of the given header for `ErrorPosition::last_id`
+fn last_id(head: Option<&[u8]>, header: &header::HeaderConfig) -> Option<String> {
+    head.map(|head| header.id(head))
+        .filter(|id| !id.is_empty())
+        .map(|id| String::from_utf8_lossy(id).into())
+}
 
 /// Remove a final '\r' from a byte slice
 #[inline]
@@ -0,0 +1,40 @@
+//! Memory-mapped input for the slice readers (requires the `memmap` feature).
+//!
+//! [`fasta::SliceReader`](../fasta/struct.SliceReader.html) and
+//! [`fastq::SliceReader`](../fastq/struct.SliceReader.html) parse a byte slice
+//! without copying. With a memory-mapped file, the records point directly into
+//! the mapped file. The operating system loads the pages on demand, so files
+//! larger than the available memory can be parsed as well.
+//!
+//! ```no_run
+//! use seq_io::fastq::{Record, SliceReader};
+//! use seq_io::mmap;
+//!
+//! // The file must not be modified while mapped (see `map_file`)
+//! let data = unsafe { mmap::map_file("seqs.fastq").unwrap() };
+//!
+//! // keep all records without copying
+//! let records: Vec<_> = SliceReader::new(&data)
+//!     .collect::<Result<_, _>>()
+//!     .unwrap();
+//!
+//! let total: usize = records.iter().map(|r| r.seq().len()).sum();
+//! ```
+
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+pub use memmap2::Mmap;
+
+/// Maps a file into memory (read-only). The returned `Mmap` dereferences
+/// to `&[u8]`.
+///
+/// # Safety
+///
+/// The behaviour is undefined if the file is modified or truncated by this
+/// or another process while it is mapped (see `memmap2::Mmap::map`).
+pub unsafe fn map_file<P: AsRef<Path>>(path: P) -> io::Result<Mmap> {
+    let file = File::open(path)?;
+    Mmap::map(&file)
+}
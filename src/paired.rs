@@ -0,0 +1,275 @@
+//! Reading of paired-end data.
+//!
+//! [`fastq::PairedReader`](../fastq/struct.PairedReader.html) and
+//! [`fasta::PairedReader`](../fasta/struct.PairedReader.html) read two files
+//! in lockstep and return the records as pairs. By default, the IDs of both
+//! records are compared (see [`ids_match`](fn.ids_match.html)).
+//! If the IDs do not match, or one of the files has more records than the
+//! other, an [`Error`](enum.Error.html) is returned.
+//!
+//! ```
+//! use seq_io::fastq::{PairedReader, Reader, Record};
+//!
+//! let r1 = b"@id/1\nACGT\n+\nIIII\n";
+//! let r2 = b"@id/2\nTTTT\n+\nIIII\n";
+//!
+//! let mut reader = PairedReader::new(Reader::new(&r1[..]), Reader::new(&r2[..]));
+//!
+//! while let Some(result) = reader.next() {
+//!     let (rec1, rec2) = result.unwrap();
+//!     assert_eq!(rec1.seq(), b"ACGT");
+//!     assert_eq!(rec2.seq(), b"TTTT");
+//! }
+//! ```
+
+use std::error;
+use std::fmt;
+
+use memchr::memchr2;
+
+/// Returns the part of a record header that is expected to be equal in both
+/// records of a pair. This is the ID (without the description, which contains the
+/// comment fields in Illumina headers), and with a trailing `/1` or `/2` removed.
+#[inline]
+pub fn pair_id(head: &[u8]) -> &[u8] {
+    let id = memchr2(b' ', b'\t', head)
+        .map(|i| &head[..i])
+        .unwrap_or(head);
+    if id.ends_with(b"/1") || id.ends_with(b"/2") {
+        &id[..id.len() - 2]
+    } else {
+        id
+    }
+}
+
+/// Checks whether two record headers belong to the same pair, comparing their
+/// [`pair_id`](fn.pair_id.html).
+#[inline]
+pub fn ids_match(head1: &[u8], head2: &[u8]) -> bool {
+    pair_id(head1) == pair_id(head2)
+}
+
+/// Paired reading error
+#[derive(Debug)]
+pub enum Error<E> {
+    /// Error parsing one of the inputs
+    Parse {
+        /// Input in which the error occurred (1 or 2)
+        input: u8,
+        /// Parse error
+        error: E,
+    },
+    /// One of the inputs has more records than the other
+    CountMismatch {
+        /// Number of complete pairs read before
+        pairs: u64,
+        /// Input with more records (1 or 2)
+        longer: u8,
+    },
+    /// The IDs of the records of a pair differ
+    IdMismatch {
+        /// Number of the pair (starting with 1)
+        pair: u64,
+        /// ID of the first record
+        id1: String,
+        /// ID of the second record
+        id2: String,
+    },
+}
+
+impl<E> Error<E> {
+    #[inline]
+    pub(crate) fn id_mismatch(pair: u64, head1: &[u8], head2: &[u8]) -> Self {
+        Error::IdMismatch {
+            pair,
+            id1: String::from_utf8_lossy(pair_id(head1)).into_owned(),
+            id2: String::from_utf8_lossy(pair_id(head2)).into_owned(),
+        }
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::Parse { input, ref error } => write!(f, "{} (input {})", error, input),
+            Error::CountMismatch { pairs, longer } => write!(
+                f,
+                "Paired reading error: input {} has more records than input {} ({} pairs read).",
+                longer,
+                3 - longer,
+                pairs
+            ),
+            Error::IdMismatch {
+                pair,
+                ref id1,
+                ref id2,
+            } => write!(
+                f,
+                "Paired reading error: IDs of pair no. {} do not match ('{}' != '{}').",
+                pair, id1, id2
+            ),
+        }
+    }
+}
+
+impl<E: error::Error + 'static> error::Error for Error<E> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match *self {
+            Error::Parse { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Implements `PairedReader` for the FASTA and FASTQ readers.
+macro_rules! paired_reader_impl {
+    ($DefaultPolicy:ty) => {
+        /// Reads two inputs (usually the forward and reverse reads of
+        /// paired-end data) in lockstep. See also the
+        /// [`paired`](../paired/index.html) module.
+        pub struct PairedReader<R1, R2 = R1, P = $DefaultPolicy>
+        where
+            R1: io::Read,
+            R2: io::Read,
+        {
+            reader1: Reader<R1, P>,
+            reader2: Reader<R2, P>,
+            check_ids: bool,
+            pairs: u64,
+        }
+
+        impl<R1, R2, P> PairedReader<R1, R2, P>
+        where
+            R1: io::Read,
+            R2: io::Read,
+            P: BufPolicy,
+        {
+            /// Creates a new paired reader. The IDs are checked by default.
+            #[inline]
+            pub fn new(reader1: Reader<R1, P>, reader2: Reader<R2, P>) -> Self {
+                PairedReader {
+                    reader1,
+                    reader2,
+                    check_ids: true,
+                    pairs: 0,
+                }
+            }
+
+            /// Sets whether the IDs of the records of each pair should be compared
+            /// (default: true). See [`paired::ids_match`](../paired/fn.ids_match.html).
+            #[inline]
+            pub fn set_check_ids(mut self, check: bool) -> Self {
+                self.check_ids = check;
+                self
+            }
+
+            /// Returns the number of pairs read so far
+            #[inline]
+            pub fn num_pairs(&self) -> u64 {
+                self.pairs
+            }
+
+            /// Returns the next pair of records. If one input has more records
+            /// than the other, `Error::CountMismatch` is returned.
+            #[allow(clippy::should_implement_trait, clippy::type_complexity)]
+            pub fn next(
+                &mut self,
+            ) -> Option<Result<(RefRecord<'_>, RefRecord<'_>), $crate::paired::Error<Error>>> {
+                use $crate::paired::Error as PairError;
+                let rec1 = self.reader1.next();
+                let rec2 = self.reader2.next();
+                let (rec1, rec2) = match (rec1, rec2) {
+                    (None, None) => return None,
+                    (Some(Err(e)), _) => return Some(Err(PairError::Parse { input: 1, error: e })),
+                    (_, Some(Err(e))) => return Some(Err(PairError::Parse { input: 2, error: e })),
+                    (Some(Ok(r1)), Some(Ok(r2))) => (r1, r2),
+                    (r1, _) => {
+                        return Some(Err(PairError::CountMismatch {
+                            pairs: self.pairs,
+                            longer: if r1.is_some() { 1 } else { 2 },
+                        }))
+                    }
+                };
+                self.pairs += 1;
+                if self.check_ids && !$crate::paired::ids_match(rec1.head(), rec2.head()) {
+                    return Some(Err(PairError::id_mismatch(
+                        self.pairs,
+                        rec1.head(),
+                        rec2.head(),
+                    )));
+                }
+                Some(Ok((rec1, rec2)))
+            }
+
+            /// Fills two [RecordSet](struct.RecordSet.html)s with the same number
+            /// of records. The number of records is determined by the buffer of the
+            /// first reader, the second reader reads the same number of records
+            /// using `read_record_set_exact()`.
+            /// Returns `None` if both inputs reached their end.
+            pub fn read_record_set_pair(
+                &mut self,
+                rset1: &mut RecordSet,
+                rset2: &mut RecordSet,
+            ) -> Option<Result<(), $crate::paired::Error<Error>>> {
+                use $crate::paired::Error as PairError;
+                match self.reader1.read_record_set(rset1) {
+                    Some(Ok(())) => {}
+                    Some(Err(e)) => return Some(Err(PairError::Parse { input: 1, error: e })),
+                    None => {
+                        return match self.reader2.next() {
+                            None => None,
+                            Some(Err(e)) => Some(Err(PairError::Parse { input: 2, error: e })),
+                            Some(Ok(_)) => Some(Err(PairError::CountMismatch {
+                                pairs: self.pairs,
+                                longer: 2,
+                            })),
+                        };
+                    }
+                }
+                let n = rset1.len();
+                let n2 = match self.reader2.read_record_set_exact(rset2, Some(n)) {
+                    Some(Ok(())) => rset2.len(),
+                    Some(Err(e)) => return Some(Err(PairError::Parse { input: 2, error: e })),
+                    None => 0,
+                };
+                if n2 < n {
+                    return Some(Err(PairError::CountMismatch {
+                        pairs: self.pairs + n2 as u64,
+                        longer: 1,
+                    }));
+                }
+                if self.check_ids {
+                    for (i, (rec1, rec2)) in rset1.into_iter().zip(rset2.into_iter()).enumerate() {
+                        if !$crate::paired::ids_match(rec1.head(), rec2.head()) {
+                            return Some(Err(PairError::id_mismatch(
+                                self.pairs + i as u64 + 1,
+                                rec1.head(),
+                                rec2.head(),
+                            )));
+                        }
+                    }
+                }
+                self.pairs += n as u64;
+                Some(Ok(()))
+            }
+
+            /// Returns references to both readers
+            #[inline]
+            pub fn get_ref(&self) -> (&Reader<R1, P>, &Reader<R2, P>) {
+                (&self.reader1, &self.reader2)
+            }
+
+            /// Returns mutable references to both readers
+            #[inline]
+            pub fn get_mut(&mut self) -> (&mut Reader<R1, P>, &mut Reader<R2, P>) {
+                (&mut self.reader1, &mut self.reader2)
+            }
+
+            /// Returns both readers
+            #[inline]
+            pub fn into_inner(self) -> (Reader<R1, P>, Reader<R2, P>) {
+                (self.reader1, self.reader2)
+            }
+        }
+    };
+}
@@ -68,12 +68,111 @@
 //!         None::<()>
 //! }).unwrap();
 //! ```
+//!
+//! # Preserving the input order
+//! By default, record sets are returned in the order in which their processing
+//! finished, which is not necessarily the order in the input. `read_parallel_ordered`,
+//! `parallel_fasta_ordered` and `parallel_fastq_ordered` (as well as their `_init`
+//! variants) return them in input order, which is useful for writing output in the
+//! same order:
+//!
+//! ```no_run
+//! use seq_io::fastq::{Reader,Record};
+//! use seq_io::parallel::parallel_fastq_ordered;
+//! use std::fs::File;
+//! use std::io::BufWriter;
+//!
+//! let reader = Reader::from_path("seqs.fastq").unwrap();
+//! let mut writer = BufWriter::new(File::create("filtered.fastq").unwrap());
+//!
+//! parallel_fastq_ordered(reader, 4, 2,
+//!     |record, found| {
+//!         *found = record.seq().windows(3).position(|s| s == b"AAA").is_some();
+//!     },
+//!     |record, found| {
+//!         if *found {
+//!             record.write(&mut writer).unwrap();
+//!         }
+//!         None::<()>
+//! }).unwrap();
+//! ```
+//!
+//! # Writing output in a separate thread
+//! With `read_parallel_write`, `parallel_fasta_write` and `parallel_fastq_write`,
+//! the worker threads directly serialise their output into a buffer for
+//! each record set. These buffers are written to an `io::Write` instance in input
+//! order by a dedicated writer thread, which is useful if writing is expensive,
+//! e.g. with compressed output.
+//!
+//! # Paired-end reads
+//! `parallel_fastq_paired` / `parallel_fasta_paired` work the same way with a
+//! [`fastq::PairedReader`](../fastq/struct.PairedReader.html) /
+//! [`fasta::PairedReader`](../fasta/struct.PairedReader.html), passing both records
+//! of each pair to the closures:
+//!
+//! ```no_run
+//! use seq_io::fastq::{PairedReader, Reader, Record};
+//! use seq_io::parallel::parallel_fastq_paired;
+//!
+//! let reader = PairedReader::new(
+//!     Reader::from_path("seqs_1.fastq").unwrap(),
+//!     Reader::from_path("seqs_2.fastq").unwrap(),
+//! );
+//!
+//! parallel_fastq_paired(reader, 4, 2,
+//!     |rec1, rec2, len| { // runs in worker
+//!         *len = rec1.seq().len() + rec2.seq().len();
+//!     },
+//!     |rec1, _, len| { // runs in main thread
+//!         println!("{}: {}", rec1.id().unwrap(), len);
+//!         None::<()>
+//! }).unwrap();
+//! ```
+//!
+//! # Parsing a file in chunks
+//! All functions above read the input in a single thread. With uncompressed files
+//! on fast storage, `read_file_chunked` can be faster, since it splits the file
+//! into several parts, which are parsed in parallel (see the function
+//! documentation for details).
+//!
+//! # Rayon
+//! With the `rayon` feature, `fasta::Reader` and `fastq::Reader` can be turned
+//! into a rayon `ParallelIterator` over record sets (`par_record_sets()`) or
+//! owned records (`par_records()`). The records are processed by the current rayon
+//! thread pool instead of a separate pool with a fixed number of threads.
+//! The order of the items is not preserved.
+//!
+//! ```no_run
+//! # #[cfg(feature = "rayon")] {
+//! use rayon::prelude::*;
+//! use seq_io::fastq::{Reader, Record};
+//!
+//! let reader = Reader::from_path("seqs.fastq").unwrap();
+//!
+//! let n_found: usize = reader
+//!     .par_record_sets()
+//!     .map(|record_set| {
+//!         record_set
+//!             .unwrap()
+//!             .into_iter()
+//!             .filter(|rec| rec.seq().windows(3).any(|s| s == b"AAA"))
+//!             .count()
+//!     })
+//!     .sum();
+//! # }
+//! ```
 
-use std::io;
+use std::io::{self, Read, Seek};
 
+use std::collections::VecDeque;
+use std::fs::File;
+use std::iter;
 use std::marker::PhantomData;
+use std::path::Path;
 use std::sync::mpsc;
 
+use memchr::memchr_iter;
+
 pub trait Reader {
     type DataSet: Send;
     type Err: Send;
@@ -108,6 +207,98 @@ where
 /// This function allows initiating the reader and datasets using a closure.
 /// This is more flexible and allows readers not to be `Send`
 pub fn read_parallel_init<R, E, Ri, Er, O, Di, Ed, W, F, Out>(
+    n_threads: u32,
+    queue_len: usize,
+    reader_init: Ri,
+    dataset_init: Di,
+    work: W,
+    func: F,
+) -> Result<Out, E>
+where
+    R: Reader,
+    Ri: Send + FnOnce() -> Result<R, Er>,
+    Er: Send,
+    E: From<Er> + From<Ed>,
+    O: Send,
+    Di: Send + Sync + FnMut() -> Result<R::DataSet, Ed>,
+    W: Send + Sync + Fn(&mut R::DataSet) -> O,
+    F: FnOnce(&mut ParallelRecordsets<R::DataSet, R::Err, O>) -> Out,
+{
+    read_parallel_impl(
+        false,
+        n_threads,
+        queue_len,
+        reader_init,
+        dataset_init,
+        work,
+        func,
+    )
+}
+
+/// Like [`read_parallel`](fn.read_parallel.html), but the record sets
+/// are returned by `ParallelRecordsets::next()` in the same order as in the input.
+/// Record sets that were processed before the preceding ones are kept back
+/// until these are done. Since no more than `queue_len` record sets are processed at
+/// the same time, these never accumulate.
+pub fn read_parallel_ordered<R, O, W, F, Out>(
+    reader: R,
+    n_threads: u32,
+    queue_len: usize,
+    work: W,
+    func: F,
+) -> Out
+where
+    R: Reader + Send,
+    R::DataSet: Default,
+    O: Default + Send,
+    W: Send + Sync + Fn(&mut R::DataSet) -> O,
+    F: FnMut(&mut ParallelRecordsets<R::DataSet, R::Err, O>) -> Out,
+{
+    read_parallel_ordered_init::<_, (), _, (), _, _, (), _, _, Out>(
+        n_threads,
+        queue_len,
+        || Ok::<_, ()>(reader),
+        || Ok::<_, ()>(R::DataSet::default()),
+        work,
+        func,
+    )
+    .unwrap()
+}
+
+/// Like [`read_parallel_init`](fn.read_parallel_init.html), but the record sets
+/// are returned in the same order as in the input
+/// (see [`read_parallel_ordered`](fn.read_parallel_ordered.html)).
+pub fn read_parallel_ordered_init<R, E, Ri, Er, O, Di, Ed, W, F, Out>(
+    n_threads: u32,
+    queue_len: usize,
+    reader_init: Ri,
+    dataset_init: Di,
+    work: W,
+    func: F,
+) -> Result<Out, E>
+where
+    R: Reader,
+    Ri: Send + FnOnce() -> Result<R, Er>,
+    Er: Send,
+    E: From<Er> + From<Ed>,
+    O: Send,
+    Di: Send + Sync + FnMut() -> Result<R::DataSet, Ed>,
+    W: Send + Sync + Fn(&mut R::DataSet) -> O,
+    F: FnOnce(&mut ParallelRecordsets<R::DataSet, R::Err, O>) -> Out,
+{
+    read_parallel_impl(
+        true,
+        n_threads,
+        queue_len,
+        reader_init,
+        dataset_init,
+        work,
+        func,
+    )
+}
+
+fn read_parallel_impl<R, E, Ri, Er, O, Di, Ed, W, F, Out>(
+    ordered: bool,
     n_threads: u32,
     queue_len: usize,
     reader_init: Ri,
@@ -137,7 +328,8 @@ where
             pool.scoped(|pool_scope| {
                 let work = &work;
 
-                loop {
+                // sequence number of the record sets
+                for i in 0.. {
                     // recycle an old DataSet sent back after use
                     let mut data = if let Ok(r) = empty_recv.recv() {
                         r
@@ -155,11 +347,11 @@ where
                                 pool_scope.execute(move || {
                                     let out = work(&mut data);
 
-                                    done_send.send(Some(Ok((data, out)))).ok();
+                                    done_send.send(Some((i, Ok((data, out))))).ok();
                                 });
                             }
                             Err(e) => {
-                                done_send.send(Some(Err(e))).ok();
+                                done_send.send(Some((i, Err(e)))).ok();
                                 break;
                             }
                         }
@@ -185,6 +377,9 @@ where
             empty_send,
             done_recv,
             current_recordset: dataset_init()?,
+            ordered,
+            next_idx: 0,
+            pending: VecDeque::with_capacity(queue_len),
         };
 
         let out = func(&mut rsets);
@@ -196,6 +391,8 @@ where
     .unwrap()
 }
 
+type DoneMsg<R, E, O> = Option<(u64, Result<(R, O), E>)>;
+
 pub struct ParallelRecordsets<R, E, O>
 where
     R: Send,
@@ -203,8 +400,14 @@ where
     O: Send,
 {
     empty_send: mpsc::SyncSender<R>,
-    done_recv: mpsc::Receiver<Option<Result<(R, O), E>>>,
+    done_recv: mpsc::Receiver<DoneMsg<R, E, O>>,
     current_recordset: R,
+    ordered: bool,
+    // sequence number of the next record set to return (if ordered)
+    next_idx: u64,
+    // reorder buffer: record sets that arrived before their predecessors,
+    // starting at `next_idx` (if ordered)
+    pending: VecDeque<Option<Result<(R, O), E>>>,
 }
 
 impl<R, E, O> ParallelRecordsets<R, E, O>
@@ -216,22 +419,54 @@ where
     #[allow(clippy::should_implement_trait)]
     #[inline]
     pub fn next(&mut self) -> Option<Result<(&mut R, O), E>> {
-        self.done_recv.recv().unwrap().map(move |result| {
-            match result {
-                Ok((r, o)) => {
-                    let prev_rset = ::std::mem::replace(&mut self.current_recordset, r);
-                    self.empty_send.send(prev_rset).ok(); // error: channel closed is not a problem, happens after calling stop()
-                    Ok((&mut self.current_recordset, o))
-                }
-                Err(e) => Err(e),
+        let result = if self.ordered {
+            self.next_ordered()?
+        } else {
+            self.done_recv.recv().unwrap()?.1
+        };
+        Some(match result {
+            Ok((r, o)) => {
+                let prev_rset = ::std::mem::replace(&mut self.current_recordset, r);
+                self.empty_send.send(prev_rset).ok(); // error: channel closed is not a problem, happens after calling stop()
+                Ok((&mut self.current_recordset, o))
             }
+            Err(e) => Err(e),
         })
     }
+
+    fn next_ordered(&mut self) -> Option<Result<(R, O), E>> {
+        loop {
+            if let Some(Some(_)) = self.pending.front() {
+                self.next_idx += 1;
+                return self.pending.pop_front().unwrap();
+            }
+            let (idx, result) = self.done_recv.recv().unwrap()?;
+            let i = (idx - self.next_idx) as usize;
+            if i >= self.pending.len() {
+                self.pending.resize_with(i + 1, || None);
+            }
+            self.pending[i] = Some(result);
+        }
+    }
 }
 
 #[macro_export]
 macro_rules! parallel_record_impl {
     ($name:ident, $name_init:ident, $io_r:tt, $rdr:ty, $dataset:ty, $record:ty, $err:ty) => {
+        $crate::parallel_record_impl!(
+            @impl read_parallel_init, [],
+            $name, $name_init, $io_r, $rdr, $dataset, $record, $err
+        );
+    };
+    (ordered: $name:ident, $name_init:ident, $io_r:tt, $rdr:ty, $dataset:ty, $record:ty, $err:ty) => {
+        $crate::parallel_record_impl!(
+            @impl read_parallel_ordered_init,
+            ["", "The records are passed to `func` in the same order as in the input."],
+            $name, $name_init, $io_r, $rdr, $dataset, $record, $err
+        );
+    };
+    (@impl $read_fn:ident, [$($doc:expr),*],
+     $name:ident, $name_init:ident, $io_r:tt, $rdr:ty, $dataset:ty, $record:ty, $err:ty) => {
         /// Function reading records in a different thread.
         /// processing them in another worker thread
         /// and finally returning the results to the main thread.
@@ -241,6 +476,7 @@ macro_rules! parallel_record_impl {
         /// data must implement `Default`, and data handled to the 'work'
         /// function will receive 'old' data from earlier records which
         /// has to be overwritten.
+        $(#[doc = $doc])*
         pub fn $name<$io_r, D, W, F, Out>(
             reader: $rdr,
             n_threads: u32,
@@ -274,6 +510,7 @@ macro_rules! parallel_record_impl {
         /// `Default` (`record_data_init`). Finally, each record set can have
         /// its own data (kind of thread local data, but actually passed around
         /// with the record set) (`rset_data_init`).
+        $(#[doc = $doc])*
         pub fn $name_init<Ri, E, $io_r, Er, Di, D, Ed, Si, S, Es, W, F, Out>(
             n_threads: u32,
             queue_len: usize,
@@ -296,7 +533,7 @@ macro_rules! parallel_record_impl {
             W: Send + Sync + Fn($record, &mut D, &mut S),
             F: FnMut($record, &mut D, &mut S) -> Option<Out>,
         {
-            $crate::parallel::read_parallel_init::<_, E, _, _, _, _, Es, _, _, _>(
+            $crate::parallel::$read_fn::<_, E, _, _, _, _, Es, _, _, _>(
                 n_threads,
                 queue_len,
                 || reader_init().map($crate::parallel::ReusableReader::<$rdr, (Vec<D>, S)>::new),
@@ -351,6 +588,268 @@ parallel_record_impl!(
     fastq::Error
 );
 
+parallel_record_impl!(
+    ordered: parallel_fasta_ordered,
+    parallel_fasta_ordered_init,
+    R,
+    fasta::Reader<R>,
+    fasta::RecordSet,
+    fasta::RefRecord,
+    fasta::Error
+);
+
+parallel_record_impl!(
+    ordered: parallel_fastq_ordered,
+    parallel_fastq_ordered_init,
+    R,
+    fastq::Reader<R>,
+    fastq::RecordSet,
+    fastq::RefRecord,
+    fastq::Error
+);
+
+/// Three-stage pipeline with a reader thread, worker threads and a writer thread.
+/// The `work` function serialises the output for each record set into a buffer,
+/// which is written to `writer` in a separate thread. The buffers are written
+/// in the same order as the input, and recycled afterwards. This is useful
+/// if writing is expensive (e.g. with compressed output), in which case
+/// doing it in the main thread could slow down the whole process.
+///
+/// Returns the writer after flushing it, or the first error encountered
+/// while reading or writing.
+///
+/// ```no_run
+/// use seq_io::fastq::{Reader, Record};
+/// use seq_io::parallel::read_parallel_write;
+/// use std::fs::File;
+///
+/// let reader = Reader::from_path("seqs.fastq").unwrap();
+/// let writer = File::create("filtered.fastq").unwrap();
+///
+/// read_parallel_write(reader, 4, 2, writer, |record_set, out| {
+///     for record in &*record_set {
+///         if record.seq().windows(3).any(|s| s == b"AAA") {
+///             record.write(&mut *out).unwrap();
+///         }
+///     }
+/// }).unwrap();
+/// ```
+pub fn read_parallel_write<R, Wr, W>(
+    reader: R,
+    n_threads: u32,
+    queue_len: usize,
+    mut writer: Wr,
+    work: W,
+) -> Result<Wr, R::Err>
+where
+    R: Reader + Send,
+    R::DataSet: Default,
+    R::Err: From<io::Error>,
+    Wr: io::Write + Send,
+    W: Send + Sync + Fn(&mut R::DataSet, &mut Vec<u8>),
+{
+    let (buf_send, buf_recv) = mpsc::sync_channel::<Vec<u8>>(queue_len);
+    let (empty_send, empty_recv) = mpsc::channel::<Vec<u8>>();
+
+    crossbeam_utils::thread::scope(|scope| {
+        let handle = scope.spawn(move |_| -> io::Result<Wr> {
+            for mut buf in buf_recv {
+                writer.write_all(&buf)?;
+                buf.clear();
+                empty_send.send(buf).ok();
+            }
+            writer.flush()?;
+            Ok(writer)
+        });
+
+        let res = read_parallel_ordered_init::<_, (), _, (), _, _, (), _, _, _>(
+            n_threads,
+            queue_len,
+            || Ok::<_, ()>(ReusableReader::<R, Vec<u8>>::new(reader)),
+            || Ok::<_, ()>(Default::default()),
+            |&mut (ref mut data, ref mut out)| {
+                out.clear();
+                work(data, out)
+            },
+            |rsets| {
+                while let Some(result) = rsets.next() {
+                    let (data, _) = result?;
+                    let empty = empty_recv.try_recv().unwrap_or_default();
+                    let buf = ::std::mem::replace(&mut data.1, empty);
+                    if buf_send.send(buf).is_err() {
+                        // writer stopped due to an error
+                        break;
+                    }
+                }
+                Ok(())
+            },
+        )
+        .unwrap();
+        ::std::mem::drop(buf_send);
+
+        let writer = handle.join().unwrap()?;
+        res.map(|_| writer)
+    })
+    .unwrap()
+}
+
+/// Implements per-record versions of `read_parallel_write`
+macro_rules! parallel_write_impl {
+    ($name:ident, $rdr:ty, $record:ty, $err:ty) => {
+        /// Per-record version of [`read_parallel_write`](fn.read_parallel_write.html):
+        /// the `work` function receives each record together with the output buffer
+        /// of its record set, which is written to `writer` by a separate thread
+        /// (in input order).
+        pub fn $name<R, Wr, W>(
+            reader: $rdr,
+            n_threads: u32,
+            queue_len: usize,
+            writer: Wr,
+            work: W,
+        ) -> Result<Wr, $err>
+        where
+            R: io::Read + Send,
+            Wr: io::Write + Send,
+            W: Send + Sync + Fn($record, &mut Vec<u8>),
+        {
+            read_parallel_write(reader, n_threads, queue_len, writer, |rset, out| {
+                for record in &*rset {
+                    work(record, out);
+                }
+            })
+        }
+    };
+}
+
+parallel_write_impl!(
+    parallel_fasta_write,
+    fasta::Reader<R>,
+    fasta::RefRecord,
+    fasta::Error
+);
+
+parallel_write_impl!(
+    parallel_fastq_write,
+    fastq::Reader<R>,
+    fastq::RefRecord,
+    fastq::Error
+);
+
+/// Implements parallel per-pair processing for paired readers
+macro_rules! parallel_paired_impl {
+    ($name:ident, $name_init:ident, $rdr:ty, $dataset:ty, $record:ty, $err:ty) => {
+        /// Function reading pairs of records in a different thread,
+        /// processing them in another worker thread
+        /// and finally returning the results to the main thread.
+        ///
+        /// Both inputs are read in chunks with the same number of records
+        /// (using `read_record_set_exact()`), the pairs are then processed like
+        /// the records in the unpaired version of this function.
+        /// Returning `Some(value)` from `func` stops the reader, and the value is
+        /// returned.
+        pub fn $name<R1, R2, D, W, F, Out>(
+            reader: $rdr,
+            n_threads: u32,
+            queue_len: usize,
+            work: W,
+            mut func: F,
+        ) -> Result<Option<Out>, $err>
+        where
+            R1: io::Read + Send,
+            R2: io::Read + Send,
+            D: Default + Send,
+            W: Send + Sync + Fn($record, $record, &mut D),
+            F: FnMut($record, $record, &mut D) -> Option<Out>,
+        {
+            $name_init(
+                n_threads,
+                queue_len,
+                || Ok::<_, $err>(reader),
+                || Ok::<_, $err>(D::default()),
+                || Ok::<_, $err>(()),
+                |rec1, rec2, record_out, _| work(rec1, rec2, record_out),
+                |rec1, rec2, record_out, _| func(rec1, rec2, record_out),
+            )
+        }
+
+        /// More customisable function doing per-pair processing with
+        /// closures for initialization, which work like in the unpaired version.
+        pub fn $name_init<Ri, E, R1, R2, Er, Di, D, Ed, Si, S, Es, W, F, Out>(
+            n_threads: u32,
+            queue_len: usize,
+            reader_init: Ri,
+            record_data_init: Di,
+            rset_data_init: Si,
+            work: W,
+            mut func: F,
+        ) -> Result<Option<Out>, E>
+        where
+            R1: io::Read,
+            R2: io::Read,
+            Ri: Send + FnOnce() -> Result<$rdr, Er>,
+            Er: Send,
+            Ed: Send,
+            E: From<$err> + From<Er> + From<Ed> + From<Es>,
+            Di: Fn() -> Result<D, Ed> + Send + Sync,
+            D: Send,
+            Si: Fn() -> Result<S, Es> + Send + Sync,
+            S: Send,
+            W: Send + Sync + Fn($record, $record, &mut D, &mut S),
+            F: FnMut($record, $record, &mut D, &mut S) -> Option<Out>,
+        {
+            read_parallel_init::<_, E, _, _, _, _, Es, _, _, _>(
+                n_threads,
+                queue_len,
+                || reader_init().map(ReusableReader::<$rdr, (Vec<D>, S)>::new),
+                || rset_data_init().map(|d| (<$dataset>::default(), (vec![], d))),
+                |&mut (ref mut rsets, (ref mut out, ref mut rset_data))| {
+                    let mut pair_iter = rsets.0.into_iter().zip(&rsets.1);
+                    for (d, (rec1, rec2)) in out.iter_mut().zip(&mut pair_iter) {
+                        work(rec1, rec2, d, rset_data);
+                    }
+                    for (rec1, rec2) in pair_iter {
+                        out.push(record_data_init()?);
+                        work(rec1, rec2, out.last_mut().unwrap(), rset_data);
+                    }
+                    Ok::<_, Ed>(())
+                },
+                |records| {
+                    while let Some(result) = records.next() {
+                        let (r, res) = result?;
+                        res?;
+                        let &mut (ref rsets, (ref mut out, ref mut rset_data)) = r;
+                        let pairs = rsets.0.into_iter().zip(&rsets.1);
+                        for ((rec1, rec2), d) in pairs.zip(out.iter_mut()) {
+                            if let Some(out) = func(rec1, rec2, d, rset_data) {
+                                return Ok(Some(out));
+                            }
+                        }
+                    }
+                    Ok(None)
+                },
+            )?
+        }
+    };
+}
+
+parallel_paired_impl!(
+    parallel_fasta_paired,
+    parallel_fasta_paired_init,
+    fasta::PairedReader<R1, R2>,
+    (fasta::RecordSet, fasta::RecordSet),
+    fasta::RefRecord,
+    super::paired::Error<fasta::Error>
+);
+
+parallel_paired_impl!(
+    parallel_fastq_paired,
+    parallel_fastq_paired_init,
+    fastq::PairedReader<R1, R2>,
+    (fastq::RecordSet, fastq::RecordSet),
+    fastq::RefRecord,
+    super::paired::Error<fastq::Error>
+);
+
 /// Wrapper for `parallel::Reader` instances allowing
 /// the output to be reused in order to save allocations.
 /// Used by `parallel_fasta`/`parallel_fastq`
@@ -400,6 +899,148 @@ where
     }
 }
 
+/// Iterator over the data sets of a [`Reader`](trait.Reader.html).
+/// Iteration stops after the first error.
+///
+/// The data sets (e.g. `RecordSet`s) are returned as
+/// [`RecycledSet`](struct.RecycledSet.html), which hands them back to the
+/// iterator when dropped. They are then refilled instead of allocating a new
+/// data set for every item, also if they are dropped in another thread.
+///
+/// With the `rayon` feature, [`par_record_sets`](fn.par_record_sets.html)
+/// turns this into a parallel iterator.
+pub struct RecordSets<R: Reader> {
+    reader: R,
+    finished: bool,
+    recycle_tx: mpsc::Sender<R::DataSet>,
+    recycle_rx: mpsc::Receiver<R::DataSet>,
+}
+
+impl<R: Reader> RecordSets<R> {
+    #[inline]
+    pub fn new(reader: R) -> RecordSets<R> {
+        let (recycle_tx, recycle_rx) = mpsc::channel();
+        RecordSets {
+            reader,
+            finished: false,
+            recycle_tx,
+            recycle_rx,
+        }
+    }
+
+    /// Returns the underlying reader
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R> Iterator for RecordSets<R>
+where
+    R: Reader,
+    R::DataSet: Default,
+{
+    type Item = Result<RecycledSet<R::DataSet>, R::Err>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let mut data = self.recycle_rx.try_recv().unwrap_or_default();
+        match self.reader.fill_data(&mut data) {
+            Some(Ok(())) => Some(Ok(RecycledSet {
+                data: Some(data),
+                recycle: self.recycle_tx.clone(),
+            })),
+            Some(Err(e)) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+            None => {
+                self.finished = true;
+                None
+            }
+        }
+    }
+}
+
+/// Data set returned by [`RecordSets`](struct.RecordSets.html), which
+/// dereferences to the data set (e.g. a `RecordSet`). When dropped, the
+/// data set is handed back to the iterator for reuse, unless it is
+/// taken out with [`into_inner`](#method.into_inner).
+pub struct RecycledSet<D> {
+    // always `Some` until dropped
+    data: Option<D>,
+    recycle: mpsc::Sender<D>,
+}
+
+impl<D> RecycledSet<D> {
+    /// Returns the data set, which is then not reused
+    #[inline]
+    pub fn into_inner(mut self) -> D {
+        self.data.take().unwrap()
+    }
+}
+
+impl<D> std::ops::Deref for RecycledSet<D> {
+    type Target = D;
+
+    #[inline]
+    fn deref(&self) -> &D {
+        self.data.as_ref().unwrap()
+    }
+}
+
+impl<D> std::ops::DerefMut for RecycledSet<D> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut D {
+        self.data.as_mut().unwrap()
+    }
+}
+
+impl<'a, D> IntoIterator for &'a RecycledSet<D>
+where
+    &'a D: IntoIterator,
+{
+    type Item = <&'a D as IntoIterator>::Item;
+    type IntoIter = <&'a D as IntoIterator>::IntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        (**self).into_iter()
+    }
+}
+
+impl<D> Drop for RecycledSet<D> {
+    fn drop(&mut self) {
+        if let Some(data) = self.data.take() {
+            // the iterator may not exist anymore
+            let _ = self.recycle.send(data);
+        }
+    }
+}
+
+/// Returns a rayon `ParallelIterator` over the data sets of a reader, using
+/// `par_bridge()`. The data sets are read one after another (by whichever
+/// thread of the current rayon pool requests the next item, never by several
+/// threads at the same time), and processed by the threads of the pool
+/// (usually the global pool). Data sets are reused after they are dropped
+/// (see [`RecordSets`](struct.RecordSets.html)).
+/// `fasta::Reader::par_record_sets` and `fastq::Reader::par_record_sets` call
+/// this function.
+///
+/// Requires the `rayon` feature.
+#[cfg(feature = "rayon")]
+#[inline]
+pub fn par_record_sets<R>(reader: R) -> rayon::iter::IterBridge<RecordSets<R>>
+where
+    R: Reader + Send,
+    R::DataSet: Default + Send,
+{
+    use rayon::iter::ParallelBridge;
+    RecordSets::new(reader).par_bridge()
+}
+
 /// Using this function currently does not work due to a
 /// [compiler bug](https://github.com/rust-lang/rust/issues/42950).
 ///
@@ -452,6 +1093,202 @@ where
     )
 }
 
+/// Readers that can parse a part of an uncompressed file with
+/// [`read_file_chunked`](fn.read_file_chunked.html). Implemented for
+/// `fasta::Reader<io::Take<File>>` and `fastq::Reader<io::Take<File>>`.
+pub trait ChunkReader: Sized {
+    /// Searches the start of the first record in `buf`. Only records starting
+    /// after a line break are considered, `buf[0]` is therefore the byte
+    /// preceding the searched range. `eof` is true if `buf` reaches
+    /// the end of the file.
+    fn find_record_start(buf: &[u8], eof: bool) -> RecordStart;
+
+    /// Creates a reader for a part of the file, which is preceded by the given
+    /// number of lines and bytes. `headers` is the number of preceding lines
+    /// that start with `>`, which is the number of records in FASTA files.
+    fn chunk_reader(input: io::Take<File>, lines: u64, bytes: u64, headers: u64) -> Self;
+}
+
+/// Return value of
+/// [`ChunkReader::find_record_start`](trait.ChunkReader.html#tymethod.find_record_start)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordStart {
+    /// A record starts at the given offset
+    Found(usize),
+    /// No record start was found and more data is needed. The given number of
+    /// bytes at the start of the buffer can be discarded.
+    NeedMore(usize),
+}
+
+const CHUNK_SEARCH_BUFSIZE: u64 = 1 << 16;
+
+/// Parses a single uncompressed FASTA or FASTQ file in `n_chunks` parts in
+/// parallel, using one thread per part.
+///
+/// The file is split into byte ranges of equal size, and the start of each
+/// range is moved forward to the start of the next record:
+///
+/// * FASTA: the next line starting with `>`
+/// * FASTQ: the next line starting with `@`, which is followed by a line
+///   starting with `+` two lines later, and whose sequence and quality lines
+///   have the same length. Multi-line FASTQ is not supported.
+///
+/// Each range is then read by a `fasta::Reader` or `fastq::Reader` over
+/// `io::Take<File>` (see [`ChunkReader`](trait.ChunkReader.html)), which is
+/// passed to the `work` closure. Together, the readers return exactly the records
+/// of a sequential parse, and their [`position()`](../fastq/struct.Reader.html#method.position)
+/// refers to the whole file. The line numbers are obtained by counting the line
+/// breaks in each range before parsing, which requires reading the file twice.
+/// Parse errors occur in the range with the erroneous record, the other
+/// ranges are still parsed.
+///
+/// The return values of `work` are returned in the order of the ranges.
+///
+/// # Example
+///
+/// ```no_run
+/// use seq_io::fastq::{Error, Reader, Record};
+/// use seq_io::parallel::read_file_chunked;
+///
+/// let counts = read_file_chunked("seqs.fastq", 4, |mut reader: Reader<_>| {
+///     let mut n = 0;
+///     while let Some(record) = reader.next() {
+///         if record?.seq().windows(3).any(|s| s == b"AAA") {
+///             n += 1;
+///         }
+///     }
+///     Ok::<_, Error>(n)
+/// }).unwrap();
+///
+/// let total: usize = counts.into_iter().sum::<Result<_, _>>().unwrap();
+/// println!("AAA found in {} records", total);
+/// ```
+pub fn read_file_chunked<R, P, W, O>(path: P, n_chunks: usize, work: W) -> io::Result<Vec<O>>
+where
+    R: ChunkReader,
+    P: AsRef<Path>,
+    W: Fn(R) -> O + Sync,
+    O: Send,
+{
+    assert!(n_chunks > 0);
+    let path = path.as_ref();
+    let file_len = File::open(path)?.metadata()?.len();
+
+    // start offsets of the chunks, followed by the end of the file
+    let starts = crossbeam_utils::thread::scope(|scope| {
+        let handles: Vec<_> = (1..n_chunks)
+            .map(|i| {
+                let offset = (file_len as u128 * i as u128 / n_chunks as u128) as u64;
+                scope.spawn(move |_| find_chunk_start::<R>(path, offset, file_len))
+            })
+            .collect();
+        iter::once(Ok(0))
+            .chain(handles.into_iter().map(|h| h.join().unwrap()))
+            .chain(iter::once(Ok(file_len)))
+            .collect::<io::Result<Vec<_>>>()
+    })
+    .unwrap()?;
+
+    // number of lines and headers preceding each chunk
+    let line_counts = crossbeam_utils::thread::scope(|scope| {
+        let handles: Vec<_> = starts
+            .windows(2)
+            .take(n_chunks - 1)
+            .map(|w| scope.spawn(move |_| count_lines(path, w[0], w[1])))
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .collect::<io::Result<Vec<_>>>()
+    })
+    .unwrap()?;
+    let lines = iter::once((0, 0)).chain(line_counts.into_iter().scan((0, 0), |n, count| {
+        n.0 += count.0;
+        n.1 += count.1;
+        Some(*n)
+    }));
+
+    let work = &work;
+    crossbeam_utils::thread::scope(|scope| {
+        let handles: Vec<_> = starts
+            .windows(2)
+            .zip(lines)
+            .map(|(w, (lines, headers))| {
+                scope.spawn(move |_| -> io::Result<O> {
+                    let mut file = File::open(path)?;
+                    file.seek(io::SeekFrom::Start(w[0]))?;
+                    let reader = R::chunk_reader(file.take(w[1] - w[0]), lines, w[0], headers);
+                    Ok(work(reader))
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap())
+            .collect::<io::Result<Vec<_>>>()
+    })
+    .unwrap()
+}
+
+// Returns the offset of the first record start at or after `offset`,
+// or the end of the file if there is none.
+fn find_chunk_start<R: ChunkReader>(path: &Path, offset: u64, file_len: u64) -> io::Result<u64> {
+    if offset == 0 {
+        return Ok(0);
+    }
+    let mut file = File::open(path)?;
+    // include the preceding byte
+    let mut buf_start = offset - 1;
+    file.seek(io::SeekFrom::Start(buf_start))?;
+    let mut buf = vec![];
+    loop {
+        let n = (&mut file)
+            .take(CHUNK_SEARCH_BUFSIZE)
+            .read_to_end(&mut buf)?;
+        let eof = n == 0 || buf_start + buf.len() as u64 >= file_len;
+        match R::find_record_start(&buf, eof) {
+            RecordStart::Found(i) => return Ok(buf_start + i as u64),
+            RecordStart::NeedMore(_) if eof => return Ok(file_len),
+            RecordStart::NeedMore(consumed) => {
+                buf.drain(..consumed);
+                buf_start += consumed as u64;
+            }
+        }
+    }
+}
+
+// Counts the line breaks in the given byte range of a file
+fn count_lines(path: &Path, start: u64, end: u64) -> io::Result<(u64, u64)> {
+    let mut file = File::open(path)?;
+    file.seek(io::SeekFrom::Start(start))?;
+    let mut file = file.take(end - start);
+    let mut buf = vec![0; CHUNK_SEARCH_BUFSIZE as usize];
+    let mut lines = 0;
+    let mut headers = 0;
+    // chunks always start at a line start
+    let mut line_start = true;
+    loop {
+        match file.read(&mut buf) {
+            Ok(0) => return Ok((lines, headers)),
+            Ok(len) => {
+                let buf = &buf[..len];
+                if line_start && buf[0] == b'>' {
+                    headers += 1;
+                }
+                for i in memchr_iter(b'\n', buf) {
+                    lines += 1;
+                    if buf.get(i + 1) == Some(&b'>') {
+                        headers += 1;
+                    }
+                }
+                line_start = buf[len - 1] == b'\n';
+            }
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+}
+
 // trait impls
 
 use super::fasta;
@@ -485,3 +1322,90 @@ where
         self.read_record_set(rset)
     }
 }
+
+impl<R, P> Reader for fastq::InterleavedReader<R, P>
+where
+    R: io::Read,
+    P: super::policy::BufPolicy + Send,
+{
+    type DataSet = fastq::RecordSet;
+    type Err = super::paired::Error<fastq::Error>;
+
+    #[inline]
+    fn fill_data(&mut self, rset: &mut fastq::RecordSet) -> Option<Result<(), Self::Err>> {
+        self.read_record_set(rset)
+    }
+}
+
+impl<R1, R2, P> Reader for fasta::PairedReader<R1, R2, P>
+where
+    R1: io::Read,
+    R2: io::Read,
+    P: super::policy::BufPolicy + Send,
+{
+    type DataSet = (fasta::RecordSet, fasta::RecordSet);
+    type Err = super::paired::Error<fasta::Error>;
+
+    #[inline]
+    fn fill_data(&mut self, rsets: &mut Self::DataSet) -> Option<Result<(), Self::Err>> {
+        self.read_record_set_pair(&mut rsets.0, &mut rsets.1)
+    }
+}
+
+impl<R1, R2, P> Reader for fastq::PairedReader<R1, R2, P>
+where
+    R1: io::Read,
+    R2: io::Read,
+    P: super::policy::BufPolicy + Send,
+{
+    type DataSet = (fastq::RecordSet, fastq::RecordSet);
+    type Err = super::paired::Error<fastq::Error>;
+
+    #[inline]
+    fn fill_data(&mut self, rsets: &mut Self::DataSet) -> Option<Result<(), Self::Err>> {
+        self.read_record_set_pair(&mut rsets.0, &mut rsets.1)
+    }
+}
+
+impl ChunkReader for fasta::Reader<io::Take<File>> {
+    fn find_record_start(buf: &[u8], _eof: bool) -> RecordStart {
+        for i in memchr_iter(b'\n', buf) {
+            match buf.get(i + 1) {
+                Some(b'>') => return RecordStart::Found(i + 1),
+                None => return RecordStart::NeedMore(i),
+                _ => {}
+            }
+        }
+        RecordStart::NeedMore(buf.len().saturating_sub(1))
+    }
+
+    fn chunk_reader(input: io::Take<File>, lines: u64, bytes: u64, headers: u64) -> Self {
+        let mut reader = fasta::Reader::new(input);
+        reader.set_input_offset(lines, bytes, headers);
+        reader
+    }
+}
+
+impl ChunkReader for fastq::Reader<io::Take<File>> {
+    fn find_record_start(buf: &[u8], eof: bool) -> RecordStart {
+        for i in memchr_iter(b'\n', buf) {
+            match buf.get(i + 1) {
+                Some(b'@') => match fastq::is_fastq_record(&buf[i + 1..], eof) {
+                    Some(true) => return RecordStart::Found(i + 1),
+                    Some(false) => {}
+                    None => return RecordStart::NeedMore(i),
+                },
+                None => return RecordStart::NeedMore(i),
+                _ => {}
+            }
+        }
+        RecordStart::NeedMore(buf.len().saturating_sub(1))
+    }
+
+    fn chunk_reader(input: io::Take<File>, lines: u64, bytes: u64, _headers: u64) -> Self {
+        let mut reader = fastq::Reader::new(input);
+        // chunks always start at a record start, records have four lines
+        reader.set_input_offset(lines, bytes, lines / 4);
+        reader
+    }
+}
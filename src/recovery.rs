@@ -0,0 +1,94 @@
+//! Recovery from malformed records.
+//!
+//! By default, [`fastq::Reader`](../fastq/struct.Reader.html) and
+//! [`fasta::Reader`](../fasta/struct.Reader.html) stop at the first parse error.
+//! After calling `set_recovery()`, a reader instead skips the malformed part
+//! of the input, resynchronises at the next plausible record start and continues
+//! parsing. Every skipped range is reported to the handler function as
+//! [`Skipped`](struct.Skipped.html), and a summary is available from
+//! `recovery_stats()`.
+//!
+//! I/O errors and `Error::BufferLimit` are never recovered from.
+//!
+//! The handler has to be `Send + Sync`, since it is stored in the reader,
+//! which should remain usable from other threads.
+//!
+//! ```
+//! use seq_io::fastq::{Reader, Record};
+//!
+//! let fastq = b"@id1
+//! ACGT
+//! +
+//! IIII
+//! @id2
+//! ACGT
+//! +
+//! III
+//! @id3
+//! TTTT
+//! +
+//! IIII
+//! ";
+//!
+//! let mut reader = Reader::new(&fastq[..]).set_recovery(|skipped| {
+//!     eprintln!("skipped bytes {}-{}: {}", skipped.start, skipped.end, skipped.error);
+//! });
+//!
+//! let mut ids = vec![];
+//! while let Some(record) = reader.next() {
+//!     ids.push(record.unwrap().id().unwrap().to_string());
+//! }
+//! assert_eq!(ids, ["id1", "id3"]);
+//! assert_eq!(reader.recovery_stats().errors, 1);
+//! assert_eq!(reader.recovery_stats().skipped_bytes, 16);
+//! ```
+
+/// A range of the input that was skipped after a parse error
+#[derive(Debug)]
+pub struct Skipped<E> {
+    /// The error that caused the range to be skipped
+    pub error: E,
+    /// Line number at the start of the skipped range
+    pub line: u64,
+    /// Byte offset of the start of the skipped range
+    pub start: u64,
+    /// Byte offset of the end of the skipped range (exclusive), which is
+    /// the start of the next record or the end of the input
+    pub end: u64,
+}
+
+/// Summary of the errors a reader recovered from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RecoveryStats {
+    /// Number of errors
+    pub errors: u64,
+    /// Total number of bytes skipped
+    pub skipped_bytes: u64,
+}
+
+pub(crate) type Handler<E> = Box<dyn FnMut(Skipped<E>) + Send + Sync>;
+
+// Recovery settings stored in the readers
+pub(crate) struct Recovery<E> {
+    handler: Handler<E>,
+    stats: RecoveryStats,
+}
+
+impl<E> Recovery<E> {
+    pub(crate) fn new(handler: Handler<E>) -> Self {
+        Recovery {
+            handler,
+            stats: RecoveryStats::default(),
+        }
+    }
+
+    pub(crate) fn stats(&self) -> RecoveryStats {
+        self.stats
+    }
+
+    pub(crate) fn report(&mut self, skipped: Skipped<E>) {
+        self.stats.errors += 1;
+        self.stats.skipped_bytes += skipped.end - skipped.start;
+        (self.handler)(skipped);
+    }
+}
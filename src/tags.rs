@@ -0,0 +1,284 @@
+//! Parsing and writing of tags in header descriptions.
+//!
+//! Many tools store metadata in the description part of the header line
+//! (after the first space). Two styles are recognized:
+//!
+//! * `key=value`, e.g. `runid=abc read=12 ch=100` (Oxford Nanopore)
+//! * SAM-style `XX:T:value` tags, e.g. `BC:Z:ACGT RG:Z:sample1` (`samtools fastq -T`)
+//!
+//! Tags are separated by spaces or tabs. Other words in the description are
+//! ignored. [`Tags`](struct.Tags.html) iterates over the tags without copying,
+//! it is returned by the `tags()` method of the FASTA and FASTQ `Record` traits.
+//! [`TagBuilder`](struct.TagBuilder.html) adds or replaces tags in a
+//! description before writing a record.
+//!
+//! # Example
+//!
+//! ```
+//! use seq_io::fastq::{Reader, Record, Writer};
+//! use seq_io::tags::TagBuilder;
+//!
+//! let fastq = b"@id runid=abc ch=100 BC:Z:ACGT
+//! ACGT
+//! +
+//! IIII
+//! ";
+//!
+//! let mut reader = Reader::new(&fastq[..]);
+//! let record = reader.next().unwrap().unwrap();
+//! assert_eq!(record.tag(b"runid").unwrap().value, b"abc");
+//! assert_eq!(record.tag(b"ch").unwrap().parse_value::<u32>(), Some(100));
+//! assert_eq!(record.tag(b"BC").unwrap().sam_type, Some(b'Z'));
+//!
+//! // replace and add tags
+//! let mut builder = TagBuilder::new();
+//! builder.set(b"ch", b"101").set_sam(b"RG", b'Z', b"s1");
+//! let mut writer = Writer::new(vec![]);
+//! writer
+//!     .write_parts(
+//!         record.id_bytes(),
+//!         builder.build(record.desc_bytes()),
+//!         record.seq(),
+//!         record.qual(),
+//!     )
+//!     .unwrap();
+//! assert_eq!(
+//!     writer.into_inner().unwrap(),
+//!     b"@id runid=abc ch=101 BC:Z:ACGT RG:Z:s1\nACGT\n+\nIIII\n"
+//! );
+//! ```
+
+use std::str::{self, FromStr, Utf8Error};
+
+/// A tag found in a header description
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tag<'a> {
+    /// Tag name
+    pub key: &'a [u8],
+    /// Type of SAM-style tags (`Z`, `i`, etc.), `None` for `key=value` tags
+    pub sam_type: Option<u8>,
+    /// Tag value
+    pub value: &'a [u8],
+}
+
+impl<'a> Tag<'a> {
+    /// Parses a single word of the description, returns `None` if it is not a tag
+    pub fn parse(word: &'a [u8]) -> Option<Tag<'a>> {
+        if word.len() >= 5
+            && word[0].is_ascii_alphabetic()
+            && word[1].is_ascii_alphanumeric()
+            && word[2] == b':'
+            && b"AifZHB".contains(&word[3])
+            && word[4] == b':'
+        {
+            return Some(Tag {
+                key: &word[..2],
+                sam_type: Some(word[3]),
+                value: &word[5..],
+            });
+        }
+        let eq = word.iter().position(|&b| b == b'=').filter(|&i| i > 0)?;
+        Some(Tag {
+            key: &word[..eq],
+            sam_type: None,
+            value: &word[eq + 1..],
+        })
+    }
+
+    /// Returns the value as string slice
+    #[inline]
+    pub fn value_str(&self) -> Result<&'a str, Utf8Error> {
+        str::from_utf8(self.value)
+    }
+
+    /// Parses the value (e.g. as number), returns `None` if this fails
+    #[inline]
+    pub fn parse_value<T: FromStr>(&self) -> Option<T> {
+        self.value_str().ok()?.parse().ok()
+    }
+}
+
+#[inline]
+fn is_sep(b: &u8) -> bool {
+    *b == b' ' || *b == b'\t'
+}
+
+/// Iterator over the tags of a header description
+#[derive(Debug, Clone)]
+pub struct Tags<'a> {
+    words: std::slice::Split<'a, u8, fn(&u8) -> bool>,
+}
+
+impl<'a> Tags<'a> {
+    /// Creates an iterator over the tags of a description
+    #[inline]
+    pub fn new(desc: &'a [u8]) -> Tags<'a> {
+        Tags {
+            words: desc.split(is_sep as fn(&u8) -> bool),
+        }
+    }
+
+    /// Returns the first tag with the given name
+    #[inline]
+    pub fn get(mut self, key: &[u8]) -> Option<Tag<'a>> {
+        self.find(|t| t.key == key)
+    }
+}
+
+impl<'a> Iterator for Tags<'a> {
+    type Item = Tag<'a>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Tag<'a>> {
+        self.words.by_ref().find_map(Tag::parse)
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    key: Vec<u8>,
+    sam_type: Option<u8>,
+    // None: remove the tag
+    value: Option<Vec<u8>>,
+    used: bool,
+}
+
+/// Adds, replaces or removes tags in a header description, which is then
+/// supplied to one of the `write_parts()` functions.
+///
+/// Existing tags with the same name are replaced in place, and new tags are
+/// appended to the description, separated by spaces. The builder can be reused
+/// for every record; values can be updated with another call to
+/// [`set`](#method.set) before writing the next record.
+#[derive(Debug, Clone, Default)]
+pub struct TagBuilder {
+    entries: Vec<Entry>,
+    buf: Vec<u8>,
+}
+
+impl TagBuilder {
+    /// Creates a new builder without any tags
+    #[inline]
+    pub fn new() -> TagBuilder {
+        TagBuilder::default()
+    }
+
+    /// Sets a `key=value` tag
+    #[inline]
+    pub fn set(&mut self, key: &[u8], value: &[u8]) -> &mut Self {
+        self.set_entry(key, None, Some(value))
+    }
+
+    /// Sets a SAM-style `XX:T:value` tag.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key does not consist of two characters.
+    #[inline]
+    pub fn set_sam(&mut self, key: &[u8], sam_type: u8, value: &[u8]) -> &mut Self {
+        assert!(key.len() == 2, "SAM tags must have two characters");
+        self.set_entry(key, Some(sam_type), Some(value))
+    }
+
+    /// Removes all tags with the given name from the description
+    #[inline]
+    pub fn remove(&mut self, key: &[u8]) -> &mut Self {
+        self.set_entry(key, None, None)
+    }
+
+    /// Removes all settings
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn set_entry(&mut self, key: &[u8], sam_type: Option<u8>, value: Option<&[u8]>) -> &mut Self {
+        let i = match self.entries.iter().position(|e| e.key == key) {
+            Some(i) => i,
+            None => {
+                self.entries.push(Entry {
+                    key: key.to_vec(),
+                    sam_type,
+                    value: None,
+                    used: false,
+                });
+                self.entries.len() - 1
+            }
+        };
+        let e = &mut self.entries[i];
+        e.sam_type = sam_type;
+        match (value, e.value.as_mut()) {
+            (Some(v), Some(ev)) => {
+                ev.clear();
+                ev.extend_from_slice(v);
+            }
+            (v, _) => e.value = v.map(|v| v.to_vec()),
+        }
+        self
+    }
+
+    /// Returns the modified description, or `None` if it is empty
+    pub fn build(&mut self, desc: Option<&[u8]>) -> Option<&[u8]> {
+        self.buf.clear();
+        for e in &mut self.entries {
+            e.used = false;
+        }
+        let desc = desc.unwrap_or_default();
+        let mut start = 0;
+        while start < desc.len() {
+            let sep_end = desc[start..]
+                .iter()
+                .position(|b| !is_sep(b))
+                .map_or(desc.len(), |i| start + i);
+            let end = desc[sep_end..]
+                .iter()
+                .position(is_sep)
+                .map_or(desc.len(), |i| sep_end + i);
+            let (sep, word) = (&desc[start..sep_end], &desc[sep_end..end]);
+            start = end;
+            let entry =
+                Tag::parse(word).and_then(|tag| self.entries.iter_mut().find(|e| e.key == tag.key));
+            match entry {
+                Some(e) => {
+                    e.used = true;
+                    if let Some(value) = e.value.as_ref() {
+                        push_sep(&mut self.buf, sep);
+                        write_tag(&mut self.buf, &e.key, e.sam_type, value);
+                    }
+                }
+                None => {
+                    push_sep(&mut self.buf, sep);
+                    self.buf.extend_from_slice(word);
+                }
+            }
+        }
+        for e in &self.entries {
+            if let (false, Some(value)) = (e.used, e.value.as_ref()) {
+                push_sep(&mut self.buf, b" ");
+                write_tag(&mut self.buf, &e.key, e.sam_type, value);
+            }
+        }
+        if self.buf.is_empty() {
+            return None;
+        }
+        Some(&self.buf)
+    }
+}
+
+// Separators are only added between words, never at the start
+#[inline]
+fn push_sep(buf: &mut Vec<u8>, sep: &[u8]) {
+    if !buf.is_empty() {
+        buf.extend_from_slice(sep);
+    }
+}
+
+#[inline]
+fn write_tag(buf: &mut Vec<u8>, key: &[u8], sam_type: Option<u8>, value: &[u8]) {
+    buf.extend_from_slice(key);
+    match sam_type {
+        Some(t) => buf.extend_from_slice(&[b':', t, b':']),
+        None => buf.push(b'='),
+    }
+    buf.extend_from_slice(value);
+}
@@ -0,0 +1,158 @@
+//! Optional validation of sequences, qualities and headers.
+//!
+//! By default, the parsers accept any bytes in sequence and quality lines.
+//! A [`Validation`](struct.Validation.html) configured with
+//! `fasta::Reader::set_validation()` or `fastq::Reader::set_validation()`
+//! additionally checks every record, and returns an error if a check fails:
+//!
+//! * Sequences can be checked against an [`Alphabet`](enum.Alphabet.html)
+//!   (`Error::InvalidSeq`). Lower case letters are always accepted.
+//! * FASTQ qualities can be checked against a range of allowed bytes
+//!   (`fastq::Error::InvalidQual`), e.g. [`PHRED33`](constant.PHRED33.html).
+//! * A FASTQ separator line, which is not just `+`, must repeat the header or
+//!   its ID (`fastq::Error::SepIdMismatch`).
+//! * FASTA header lines must not be empty (`fasta::Error::EmptyHeader`).
+//!
+//! ```
+//! use seq_io::fastq::{Error, Reader};
+//! use seq_io::validation::{Alphabet, Validation, PHRED33};
+//!
+//! let fastq = b"@id\nACGU\n+\nIIII\n";
+//!
+//! let validation = Validation::new()
+//!     .set_alphabet(Alphabet::Dna)
+//!     .set_qual_range(PHRED33);
+//! let mut reader = Reader::new(&fastq[..]).set_validation(validation);
+//!
+//! match reader.next().unwrap() {
+//!     Err(Error::InvalidSeq { found, pos }) => {
+//!         assert_eq!(found, b'U');
+//!         assert_eq!((pos.line, pos.column), (2, Some(4)));
+//!     }
+//!     _ => panic!(),
+//! }
+//! ```
+//!
+//! Validation slows down parsing, since every byte has to be checked.
+
+/// Range of quality bytes allowed with the Phred+33 encoding
+pub const PHRED33: (u8, u8) = (33, 126);
+
+/// Range of quality bytes allowed with the Phred+64 encoding
+pub const PHRED64: (u8, u8) = (64, 126);
+
+/// Range of quality bytes allowed with the Solexa encoding (scores from -5)
+pub const SOLEXA: (u8, u8) = (59, 126);
+
+/// Sequence alphabet. Upper and lower case letters are accepted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alphabet {
+    /// `ACGTN`
+    Dna,
+    /// `ACGUN`
+    Rna,
+    /// IUPAC nucleotide codes (`ACGTURYSWKMBDHVN`) and gaps (`-`)
+    Iupac,
+    /// The 20 standard amino acids, `BJOUXZ`, stop codons (`*`) and gaps (`-`)
+    Protein,
+}
+
+impl Alphabet {
+    /// Returns true if the byte is part of the alphabet
+    #[inline]
+    pub fn contains(self, byte: u8) -> bool {
+        self.table()[byte as usize]
+    }
+
+    #[inline]
+    fn table(self) -> &'static [bool; 256] {
+        match self {
+            Alphabet::Dna => &DNA,
+            Alphabet::Rna => &RNA,
+            Alphabet::Iupac => &IUPAC,
+            Alphabet::Protein => &PROTEIN,
+        }
+    }
+}
+
+static DNA: [bool; 256] = make_table(b"ACGTN");
+static RNA: [bool; 256] = make_table(b"ACGUN");
+static IUPAC: [bool; 256] = make_table(b"ACGTURYSWKMBDHVN-");
+static PROTEIN: [bool; 256] = make_table(b"ACDEFGHIKLMNPQRSTVWYBJOUXZ*-");
+
+const fn make_table(symbols: &[u8]) -> [bool; 256] {
+    let mut table = [false; 256];
+    let mut i = 0;
+    while i < symbols.len() {
+        table[symbols[i] as usize] = true;
+        table[symbols[i].to_ascii_lowercase() as usize] = true;
+        i += 1;
+    }
+    table
+}
+
+/// Validation settings for FASTA and FASTQ readers. Nothing is checked by default.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validation {
+    pub(crate) alphabet: Option<Alphabet>,
+    pub(crate) qual_range: Option<(u8, u8)>,
+    pub(crate) check_sep_id: bool,
+    pub(crate) check_header: bool,
+}
+
+impl Validation {
+    /// Creates a new `Validation`, which does not check anything
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks sequences against the given alphabet
+    #[inline]
+    pub fn set_alphabet(mut self, alphabet: Alphabet) -> Self {
+        self.alphabet = Some(alphabet);
+        self
+    }
+
+    /// Checks that all FASTQ quality bytes are within the inclusive range
+    /// `(min, max)`, e.g. [`PHRED33`](constant.PHRED33.html) or
+    /// [`PHRED64`](constant.PHRED64.html).
+    #[inline]
+    pub fn set_qual_range(mut self, range: (u8, u8)) -> Self {
+        self.qual_range = Some(range);
+        self
+    }
+
+    /// Checks whether the FASTQ separator line (if not only `+`) repeats the
+    /// header line or the ID (default: false)
+    #[inline]
+    pub fn set_check_sep_id(mut self, check: bool) -> Self {
+        self.check_sep_id = check;
+        self
+    }
+
+    /// Checks whether FASTA header lines are non-empty (default: false)
+    #[inline]
+    pub fn set_check_header(mut self, check: bool) -> Self {
+        self.check_header = check;
+        self
+    }
+
+    /// Returns the index of the first byte of `seq` that is not part of the
+    /// alphabet. Line breaks are ignored.
+    #[inline]
+    pub(crate) fn find_invalid_seq(&self, seq: &[u8]) -> Option<usize> {
+        let table = self.alphabet?.table();
+        seq.iter()
+            .position(|&b| !table[b as usize] && b != b'\n' && b != b'\r')
+    }
+
+    /// Returns the index of the first byte of `qual` that is not within the
+    /// allowed range. Line breaks are ignored.
+    #[inline]
+    pub(crate) fn find_invalid_qual(&self, qual: &[u8]) -> Option<usize> {
+        let (min, max) = self.qual_range?;
+        qual.iter()
+            .position(|&b| (b < min || b > max) && b != b'\n' && b != b'\r')
+    }
+}
@@ -0,0 +1,187 @@
+//! State and methods shared by `fasta::Writer` and `fastq::Writer`
+
+use std::io::{self, Write};
+
+use crate::header::HeaderConfig;
+use crate::LineEnding;
+
+/// Header line to be written, either unchanged or with ID and description
+/// supplied separately
+pub(crate) enum Head<'a> {
+    Line(&'a [u8]),
+    Parts(&'a [u8], Option<&'a [u8]>),
+}
+
+/// Buffered output and the settings common to the FASTA and FASTQ writers
+pub(crate) struct WriterBase<W: io::Write> {
+    writer: io::BufWriter<W>,
+    pub(crate) line_width: Option<usize>,
+    pub(crate) line_ending: LineEnding,
+    pub(crate) write_desc: bool,
+    pub(crate) header: HeaderConfig,
+    n_records: u64,
+    n_bases: u64,
+}
+
+impl<W: io::Write> WriterBase<W> {
+    #[inline]
+    pub(crate) fn with_capacity(capacity: usize, writer: W) -> Self {
+        WriterBase {
+            writer: io::BufWriter::with_capacity(capacity, writer),
+            line_width: None,
+            line_ending: LineEnding::default(),
+            write_desc: true,
+            header: HeaderConfig::default(),
+            n_records: 0,
+            n_bases: 0,
+        }
+    }
+
+    /// Writes a header line starting with `start`. Whole header lines are
+    /// split in the same way as by the readers (according to `header`)
+    /// if the description is not to be written.
+    #[inline]
+    pub(crate) fn write_head(&mut self, start: u8, head: &Head) -> io::Result<()> {
+        self.writer.write_all(&[start])?;
+        match *head {
+            Head::Line(line) => {
+                if self.write_desc {
+                    self.writer.write_all(line)?;
+                } else {
+                    self.writer.write_all(self.header.untrimmed_id(line))?;
+                }
+            }
+            Head::Parts(id, desc) => {
+                self.writer.write_all(id)?;
+                if let Some(d) = desc.filter(|_| self.write_desc) {
+                    self.writer.write_all(b" ")?;
+                    self.writer.write_all(d)?;
+                }
+            }
+        }
+        self.writer.write_all(self.line_ending.as_bytes())
+    }
+
+    /// Writes a line, to which the line terminator is appended
+    #[inline]
+    pub(crate) fn write_line(&mut self, line: &[u8]) -> io::Result<()> {
+        self.writer.write_all(line)?;
+        self.writer.write_all(self.line_ending.as_bytes())
+    }
+
+    /// Writes sequence or quality data according to the configured line
+    /// width (see `write_lines`) and returns the number of bytes written
+    /// without line endings.
+    #[inline]
+    pub(crate) fn write_lines(&mut self, data: &[u8]) -> io::Result<usize> {
+        crate::write_lines(&mut self.writer, data, self.line_width, self.line_ending)
+    }
+
+    /// Updates the counts after a record was written
+    #[inline]
+    pub(crate) fn record_written(&mut self, n_bases: usize) {
+        self.n_records += 1;
+        self.n_bases += n_bases as u64;
+    }
+
+    #[inline]
+    pub(crate) fn num_records(&self) -> u64 {
+        self.n_records
+    }
+
+    #[inline]
+    pub(crate) fn num_bases(&self) -> u64 {
+        self.n_bases
+    }
+
+    #[inline]
+    pub(crate) fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    #[inline]
+    pub(crate) fn get_ref(&self) -> &W {
+        self.writer.get_ref()
+    }
+
+    #[inline]
+    pub(crate) fn get_mut(&mut self) -> &mut W {
+        self.writer.get_mut()
+    }
+
+    #[inline]
+    pub(crate) fn into_inner(self) -> io::Result<W> {
+        self.writer.into_inner().map_err(|e| e.into_error())
+    }
+}
+
+/// Implements the methods common to the FASTA and FASTQ writers, which
+/// store their `WriterBase` in the `base` field.
+macro_rules! writer_base_impl {
+    () => {
+        /// Sets the line terminator (default: `LineEnding::Lf`)
+        #[inline]
+        pub fn set_line_ending(mut self, ending: LineEnding) -> Self {
+            self.base.line_ending = ending;
+            self
+        }
+
+        /// Sets whether the description should be written (default: true).
+        /// If false, only the ID is written.
+        #[inline]
+        pub fn set_write_desc(mut self, write_desc: bool) -> Self {
+            self.base.write_desc = write_desc;
+            self
+        }
+
+        /// Sets how header lines supplied to `write()` and `write_record()`
+        /// are split into ID and description (default: at the first space),
+        /// which matters if the description is not written
+        /// (see [`set_write_desc`](#method.set_write_desc)).
+        /// The same [`HeaderConfig`](../header/struct.HeaderConfig.html) as
+        /// for the reader should be used. Only the delimiter is relevant,
+        /// IDs are always written unchanged.
+        #[inline]
+        pub fn set_header_config(mut self, config: $crate::header::HeaderConfig) -> Self {
+            self.base.header = config;
+            self
+        }
+
+        /// Returns the number of records written so far
+        #[inline]
+        pub fn num_records(&self) -> u64 {
+            self.base.num_records()
+        }
+
+        /// Returns the total length of the sequences written so far
+        #[inline]
+        pub fn num_bases(&self) -> u64 {
+            self.base.num_bases()
+        }
+
+        /// Flushes the buffer and the underlying writer
+        #[inline]
+        pub fn flush(&mut self) -> io::Result<()> {
+            self.base.flush()
+        }
+
+        /// Returns a reference to the underlying writer
+        #[inline]
+        pub fn get_ref(&self) -> &W {
+            self.base.get_ref()
+        }
+
+        /// Returns a mutable reference to the underlying writer. Writing to it
+        /// directly may mix up the output, since the buffer is not flushed before.
+        #[inline]
+        pub fn get_mut(&mut self) -> &mut W {
+            self.base.get_mut()
+        }
+
+        /// Flushes the buffer and returns the underlying writer
+        #[inline]
+        pub fn into_inner(self) -> io::Result<W> {
+            self.base.into_inner()
+        }
+    };
+}
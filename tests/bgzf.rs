@@ -0,0 +1,436 @@
+#![cfg(feature = "bgzf")]
+extern crate seq_io;
+
+use flate2::write::DeflateEncoder;
+use flate2::{Compression, Crc};
+use seq_io::bgzf;
+use std::io::{Cursor, Read, Write};
+
+// Compresses the data into BGZF blocks of the given (uncompressed) size
+fn bgzf_compress(data: &[u8], block_size: usize) -> Vec<u8> {
+    let mut out = vec![];
+    for chunk in data.chunks(block_size).chain(Some(&b""[..])) {
+        let mut enc = DeflateEncoder::new(vec![], Compression::default());
+        enc.write_all(chunk).unwrap();
+        let cdata = enc.finish().unwrap();
+        let bsize = (cdata.len() + 25) as u16;
+        out.extend_from_slice(&[
+            0x1f, 0x8b, 8, 4, 0, 0, 0, 0, 0, 0xff, 6, 0, b'B', b'C', 2, 0,
+        ]);
+        out.extend_from_slice(&bsize.to_le_bytes());
+        out.extend_from_slice(&cdata);
+        let mut crc = Crc::new();
+        crc.update(chunk);
+        out.extend_from_slice(&crc.sum().to_le_bytes());
+        out.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+    }
+    out
+}
+
+fn fastq_data() -> Vec<u8> {
+    let mut data = vec![];
+    for i in 0..50 {
+        write!(data, "@id{}\nACGTACGT\n+\nIIIIIIII\n", i).unwrap();
+    }
+    data
+}
+
+#[test]
+fn test_bgzf_read() {
+    let data = fastq_data();
+    for block_size in &[1, 7, 100, 1000, 65536] {
+        let compressed = bgzf_compress(&data, *block_size);
+        let mut out = vec![];
+        bgzf::Reader::new(&compressed[..])
+            .read_to_end(&mut out)
+            .unwrap();
+        assert_eq!(out, data);
+    }
+}
+
+#[test]
+fn test_bgzf_window() {
+    let data = fastq_data();
+    let compressed = bgzf_compress(&data, 7);
+    let mut reader = bgzf::Reader::new(&compressed[..]).set_window_size(20);
+    let mut out = vec![];
+    reader.read_to_end(&mut out).unwrap();
+    assert_eq!(out, data);
+    let end = data.len() as u64;
+    // blocks before the window are discarded while reading
+    assert_eq!(reader.virtual_offset_at(0), None);
+    assert_eq!(reader.virtual_offset_at(end - 27), None);
+    assert!(reader.virtual_offset_at(end - 20).is_some());
+    assert!(reader.virtual_offset_at(end - 1).is_some());
+}
+
+#[test]
+fn test_bgzf_invalid() {
+    let mut compressed = bgzf_compress(b"@id\nA\n+\nI\n", 100);
+    compressed[20] ^= 0xff;
+    let mut out = vec![];
+    assert!(bgzf::Reader::new(&compressed[..])
+        .read_to_end(&mut out)
+        .is_err());
+}
+
+#[test]
+fn test_bgzf_fastq_seek() {
+    use seq_io::fastq::{Reader, Record};
+    let data = fastq_data();
+    for block_size in &[5, 33, 200] {
+        for cap in &[3, 20, 100, 1000] {
+            let compressed = bgzf_compress(&data, *block_size);
+            let mut reader =
+                Reader::with_capacity(bgzf::Reader::new(Cursor::new(&compressed)), *cap);
+            let mut positions = vec![];
+            while let Some(r) = reader.next() {
+                let id = r.unwrap().id().unwrap().to_string();
+                positions.push((reader.virtual_position().unwrap(), id));
+            }
+            assert_eq!(positions.len(), 50);
+            for (pos, id) in positions.iter().rev() {
+                reader.seek_virtual(pos).unwrap();
+                let rec = reader.next().unwrap().unwrap();
+                assert_eq!(rec.id().unwrap(), id);
+                assert_eq!(reader.virtual_position().as_ref(), Some(pos));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_bgzf_fasta_seek() {
+    use seq_io::fasta::{Reader, Record};
+    let mut data = vec![];
+    for i in 0..30 {
+        write!(data, ">id{}\nACGT\nAC\n", i).unwrap();
+    }
+    for block_size in &[5, 33, 200] {
+        let compressed = bgzf_compress(&data, *block_size);
+        let mut reader = Reader::with_capacity(bgzf::Reader::new(Cursor::new(&compressed)), 10);
+        let mut positions = vec![];
+        while let Some(r) = reader.next() {
+            let id = r.unwrap().id().unwrap().to_string();
+            positions.push((reader.virtual_position().unwrap(), id));
+        }
+        assert_eq!(positions.len(), 30);
+        for (pos, id) in positions.iter().rev() {
+            reader.seek_virtual(pos).unwrap();
+            let rec = reader.next().unwrap().unwrap();
+            assert_eq!(rec.id().unwrap(), id);
+            assert_eq!(rec.seq(), b"ACGT\nAC");
+        }
+    }
+}
+
+#[test]
+fn test_bgzf_seek() {
+    use std::io::{Seek, SeekFrom};
+    let data = fastq_data();
+    for block_size in &[1, 7, 100, 65536] {
+        let compressed = bgzf_compress(&data, *block_size);
+        for &(start, len) in &[(500, 10), (1, 30), (1000, 3), (0, 5), (999, 1)] {
+            // fresh reader (block headers are skipped), and reader after reading
+            // everything with a small window (known blocks are used)
+            let mut fresh = bgzf::Reader::new(Cursor::new(&compressed));
+            let mut used = bgzf::Reader::new(Cursor::new(&compressed)).set_window_size(10);
+            used.read_to_end(&mut vec![]).unwrap();
+            for reader in [&mut fresh, &mut used] {
+                assert_eq!(reader.seek(SeekFrom::Start(start)).unwrap(), start);
+                assert_eq!(reader.offset(), start);
+                let mut buf = vec![0; len];
+                reader.read_exact(&mut buf).unwrap();
+                assert_eq!(&buf[..], &data[start as usize..start as usize + len]);
+                assert_eq!(reader.stream_position().unwrap(), start + len as u64);
+                reader.seek(SeekFrom::Current(-(len as i64))).unwrap();
+                reader.read_exact(&mut buf).unwrap();
+                assert_eq!(&buf[..], &data[start as usize..start as usize + len]);
+                // virtual offsets agree with the decompressed offsets
+                let voffset = reader.virtual_offset();
+                reader.seek(SeekFrom::Start(0)).unwrap();
+                reader.seek_virtual(voffset).unwrap();
+                assert_eq!(reader.offset(), start + len as u64);
+            }
+        }
+        // beyond the end
+        let mut reader = bgzf::Reader::new(Cursor::new(&compressed));
+        let end = data.len() as u64;
+        assert_eq!(reader.seek(SeekFrom::Start(end + 10)).unwrap(), end + 10);
+        assert_eq!(reader.read(&mut [0; 10]).unwrap(), 0);
+        assert!(reader.seek(SeekFrom::End(0)).is_err());
+    }
+}
+
+#[test]
+fn test_bgzf_fastq_seek_error() {
+    use seq_io::fastq::{Error, Reader};
+    let mut data = fastq_data();
+    let bad_start = data.len() as u64;
+    data.extend_from_slice(b"@bad\nACGT\n+\nII\n");
+    for block_size in &[5, 33, 200] {
+        let compressed = bgzf_compress(&data, *block_size);
+        let mut reader = Reader::with_capacity(bgzf::Reader::new(Cursor::new(&compressed)), 40);
+        for _ in 0..40 {
+            reader.next().unwrap().unwrap();
+        }
+        let vpos = reader.virtual_position().unwrap();
+        let pos = reader.position().clone();
+        while reader.next().unwrap().is_ok() {}
+        reader.seek_virtual(&vpos).unwrap();
+        // the position is the same as before, not relative to the block
+        assert_eq!(reader.position(), &pos);
+        let err_pos = loop {
+            match reader.next().unwrap() {
+                Ok(_) => {}
+                Err(Error::UnequalLengths { pos, .. }) => break pos,
+                Err(e) => panic!("unexpected error: {}", e),
+            }
+        };
+        assert_eq!(err_pos.byte, bad_start);
+        assert_eq!(err_pos.record_line, 201);
+        assert_eq!(err_pos.id.as_deref(), Some("bad"));
+        // seeking with the decompressed position works as well
+        reader.seek(&err_pos.to_position()).unwrap();
+        assert!(matches!(
+            reader.next().unwrap(),
+            Err(Error::UnequalLengths { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_bgzf_fastq_index() {
+    use seq_io::fastq::index::{Index, IndexedReader};
+    use seq_io::fastq::{Reader, Record};
+    let data = fastq_data();
+    // index of the uncompressed data
+    let index = Index::build(&mut Reader::new(&data[..])).unwrap();
+    for block_size in &[5, 33, 200] {
+        let compressed = bgzf_compress(&data, *block_size);
+        let reader = Reader::with_capacity(bgzf::Reader::new(Cursor::new(&compressed)), 20);
+        let mut reader = IndexedReader::new(reader, index.clone());
+        for i in &[42, 3, 49, 0, 17] {
+            let id = format!("id{}", i);
+            let rec = reader.get_by_id(id.as_bytes()).unwrap().unwrap();
+            assert_eq!(rec.id(), Ok(&id[..]));
+        }
+    }
+}
+
+#[test]
+fn test_bgzf_fasta_index() {
+    use seq_io::fasta::index::{Index, IndexedReader};
+    use seq_io::fasta::Reader;
+    let mut data = vec![];
+    for i in 0..30 {
+        write!(data, ">id{}\nACGT\nAC\n", i).unwrap();
+    }
+    let index = Index::build(&mut Reader::new(&data[..])).unwrap();
+    let compressed = bgzf_compress(&data, 7);
+    let reader = std::io::BufReader::new(bgzf::Reader::new(Cursor::new(&compressed)));
+    let mut reader = IndexedReader::new(reader, index);
+    let mut seq = vec![];
+    reader.fetch_region("id21:3-5", &mut seq).unwrap();
+    assert_eq!(&seq, b"GTA");
+    reader.fetch_all("id2", &mut seq).unwrap();
+    assert_eq!(&seq, b"ACGTAC");
+}
+
+// Pseudo-random, incompressible data
+fn random_data(len: usize) -> Vec<u8> {
+    let mut x: u64 = 0x2545_f491_4f6c_dd1d;
+    (0..len)
+        .map(|_| {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            x as u8
+        })
+        .collect()
+}
+
+fn decompress(compressed: &[u8]) -> Vec<u8> {
+    let mut out = vec![];
+    bgzf::Reader::new(compressed).read_to_end(&mut out).unwrap();
+    // also readable as multi-member gzip
+    let mut out2 = vec![];
+    flate2::read::MultiGzDecoder::new(compressed)
+        .read_to_end(&mut out2)
+        .unwrap();
+    assert_eq!(out, out2);
+    out
+}
+
+#[test]
+fn test_bgzf_write() {
+    let mut data = vec![];
+    for _ in 0..100 {
+        data.extend_from_slice(&fastq_data());
+    }
+    data.extend_from_slice(&random_data(200_000));
+    for chunk_size in &[1000, 65280, 100_000] {
+        let mut writer = bgzf::Writer::new(vec![]);
+        for (i, chunk) in data.chunks(*chunk_size).enumerate() {
+            writer.write_all(chunk).unwrap();
+            if i % 3 == 0 {
+                writer.flush().unwrap();
+            }
+        }
+        let compressed = writer.finish().unwrap();
+        assert!(compressed.ends_with(&bgzf::EOF_BLOCK));
+        assert_eq!(decompress(&compressed), data);
+    }
+    // empty output
+    let compressed = bgzf::Writer::new(vec![]).finish().unwrap();
+    assert_eq!(&compressed[..], &bgzf::EOF_BLOCK[..]);
+}
+
+#[test]
+fn test_bgzf_write_drop() {
+    let mut compressed = vec![];
+    {
+        let mut writer = bgzf::Writer::new(&mut compressed);
+        writer.write_all(b"ACGT").unwrap();
+    }
+    assert!(compressed.ends_with(&bgzf::EOF_BLOCK));
+    assert_eq!(decompress(&compressed), b"ACGT");
+}
+
+#[test]
+fn test_bgzf_write_virtual_offset() {
+    use seq_io::fastq::{Reader, Record};
+    let data = fastq_data();
+    let mut writer = bgzf::Writer::new(vec![]);
+    let mut positions = vec![];
+    for (i, chunk) in data.chunks(23).enumerate() {
+        if i % 4 == 0 {
+            // start a new block now and then
+            writer.flush().unwrap();
+        }
+        positions.push((writer.virtual_offset(), chunk));
+        writer.write_all(chunk).unwrap();
+    }
+    let compressed = writer.finish().unwrap();
+    let mut reader = bgzf::Reader::new(Cursor::new(&compressed));
+    for (voffset, chunk) in positions.iter().rev() {
+        reader.seek_virtual(*voffset).unwrap();
+        let mut buf = vec![0; chunk.len()];
+        reader.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, chunk);
+    }
+
+    // records written with the FASTQ writer (flushed after every record)
+    let mut writer = seq_io::fastq::Writer::new(bgzf::Writer::new(vec![]));
+    let mut positions = vec![];
+    let mut reader = Reader::new(&data[..]);
+    while let Some(rec) = reader.next() {
+        let rec = rec.unwrap();
+        positions.push((
+            writer.get_ref().virtual_offset(),
+            rec.id().unwrap().to_string(),
+        ));
+        writer.write_record(&rec).unwrap();
+        writer.flush().unwrap();
+    }
+    let compressed = writer.into_inner().unwrap().finish().unwrap();
+    let mut reader = Reader::new(bgzf::Reader::new(Cursor::new(&compressed)));
+    for (voffset, id) in positions.iter().rev() {
+        reader
+            .seek_virtual(&seq_io::fastq::Position::new(1, *voffset))
+            .unwrap();
+        assert_eq!(reader.next().unwrap().unwrap().id().unwrap(), id);
+    }
+}
+
+#[test]
+fn test_bgzf_parallel_write() {
+    let mut data = vec![];
+    for _ in 0..200 {
+        data.extend_from_slice(&fastq_data());
+    }
+    data.extend_from_slice(&random_data(200_000));
+    let mut expected = bgzf::Writer::new(vec![]);
+    for (i, chunk) in data.chunks(10_000).enumerate() {
+        expected.write_all(chunk).unwrap();
+        if i % 7 == 0 {
+            expected.flush().unwrap();
+        }
+    }
+    let expected = expected.finish().unwrap();
+    for n_threads in 1..5 {
+        let mut writer = bgzf::ParallelWriter::new(vec![], n_threads);
+        for (i, chunk) in data.chunks(10_000).enumerate() {
+            writer.write_all(chunk).unwrap();
+            if i % 7 == 0 {
+                writer.flush().unwrap();
+            }
+        }
+        let compressed = writer.finish().unwrap();
+        assert!(compressed == expected);
+    }
+}
+
+#[derive(Debug)]
+struct FailingWriter;
+
+impl Write for FailingWriter {
+    fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            "write failed",
+        ))
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_bgzf_parallel_write_err() {
+    let data = random_data(1_000_000);
+    let mut writer = bgzf::ParallelWriter::new(FailingWriter, 2);
+    // the error is returned by a later write() call or by finish()
+    let res = writer
+        .write_all(&data)
+        .and_then(|_| writer.finish().map(|_| ()));
+    assert_eq!(res.unwrap_err().to_string(), "write failed");
+}
+
+// Writer whose contents can be inspected while it is owned by `ParallelWriter`
+#[derive(Clone, Default)]
+struct SharedWriter(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+impl Write for SharedWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.0.lock().unwrap().write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_bgzf_parallel_flush() {
+    let data = random_data(200_000);
+    let out = SharedWriter::default();
+    let mut writer = bgzf::ParallelWriter::new(out.clone(), 3);
+    writer.write_all(&data).unwrap();
+    // all data is written after flush() returns
+    writer.flush().unwrap();
+    let compressed = out.0.lock().unwrap().clone();
+    let mut decompressed = vec![];
+    bgzf::Reader::new(&compressed[..])
+        .read_to_end(&mut decompressed)
+        .unwrap();
+    assert!(decompressed == data);
+    writer.finish().unwrap();
+
+    // flush() returns the error
+    let mut writer = bgzf::ParallelWriter::new(FailingWriter, 2);
+    writer.write_all(b"ACGT").unwrap();
+    assert_eq!(writer.flush().unwrap_err().to_string(), "write failed");
+    assert!(writer.flush().is_err());
+}
@@ -0,0 +1,260 @@
+extern crate seq_io;
+
+use seq_io::compression::{auto_reader, Format};
+use seq_io::{fasta, fastq};
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
+
+const FASTA: &[u8] = b">id1 desc\nACGT\nAC\n>id2\nTGCA\n";
+const FASTQ: &[u8] = b"@id1 desc\nACGT\n+\nIIII\n@id2\nTGCA\n+\nIIII\n";
+
+fn write_tmp(name: &str, data: &[u8]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("seq_io_test_{}_{}", std::process::id(), name));
+    File::create(&path).unwrap().write_all(data).unwrap();
+    path
+}
+
+fn fasta_ids<R: std::io::Read>(mut reader: fasta::Reader<R>) -> Vec<String> {
+    use seq_io::fasta::Record;
+    let mut ids = vec![];
+    while let Some(r) = reader.next() {
+        ids.push(r.unwrap().id().unwrap().to_string());
+    }
+    ids
+}
+
+fn fastq_ids<R: std::io::Read>(mut reader: fastq::Reader<R>) -> Vec<String> {
+    use seq_io::fastq::Record;
+    let mut ids = vec![];
+    while let Some(r) = reader.next() {
+        ids.push(r.unwrap().id().unwrap().to_string());
+    }
+    ids
+}
+
+#[test]
+fn test_detect_format() {
+    assert_eq!(Format::detect(&[0x1f, 0x8b, 8, 4]), Format::Gzip);
+    assert_eq!(Format::detect(b"BZh91AY"), Format::Bzip2);
+    assert_eq!(Format::detect(b"\xfd7zXZ\x00"), Format::Xz);
+    assert_eq!(Format::detect(&[0x28, 0xb5, 0x2f, 0xfd]), Format::Zstd);
+    assert_eq!(Format::detect(b">id"), Format::Plain);
+    assert_eq!(Format::detect(b""), Format::Plain);
+}
+
+#[test]
+fn test_auto_plain() {
+    let path = write_tmp("plain.fa", FASTA);
+    let reader = fasta::Reader::from_path_auto(&path).unwrap();
+    assert_eq!(fasta_ids(reader), vec!["id1", "id2"]);
+    std::fs::remove_file(path).unwrap();
+
+    let path = write_tmp("plain.fq", FASTQ);
+    let reader = fastq::Reader::from_path_auto(&path).unwrap();
+    assert_eq!(fastq_ids(reader), vec!["id1", "id2"]);
+    std::fs::remove_file(path).unwrap();
+
+    // very short input
+    let reader = fasta::Reader::new(auto_reader(&b">"[..]).unwrap());
+    assert_eq!(fasta_ids(reader), vec![""]);
+}
+
+#[cfg(not(feature = "gzip"))]
+#[test]
+fn test_auto_unsupported() {
+    let err = auto_reader(&[0x1f, 0x8b, 8, 0][..]).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::Unsupported);
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn test_gzip() {
+    use flate2::write::GzEncoder;
+    // two gzip members
+    let mut data = vec![];
+    for part in FASTQ.chunks(20) {
+        let mut enc = GzEncoder::new(&mut data, flate2::Compression::default());
+        enc.write_all(part).unwrap();
+        enc.finish().unwrap();
+    }
+    let path = write_tmp("gz.fq.gz", &data);
+    let reader = fastq::Reader::from_path_auto(&path).unwrap();
+    assert_eq!(fastq_ids(reader), vec!["id1", "id2"]);
+    std::fs::remove_file(path).unwrap();
+    let reader = fastq::Reader::from_gzip(&data[..]);
+    assert_eq!(fastq_ids(reader), vec!["id1", "id2"]);
+}
+
+#[cfg(feature = "bzip2")]
+#[test]
+fn test_bzip2() {
+    let mut enc = bzip2::write::BzEncoder::new(vec![], bzip2::Compression::default());
+    enc.write_all(FASTA).unwrap();
+    let data = enc.finish().unwrap();
+    let path = write_tmp("bz2.fa.bz2", &data);
+    let reader = fasta::Reader::from_path_auto(&path).unwrap();
+    assert_eq!(fasta_ids(reader), vec!["id1", "id2"]);
+    std::fs::remove_file(path).unwrap();
+    let reader = fasta::Reader::from_bzip2(&data[..]);
+    assert_eq!(fasta_ids(reader), vec!["id1", "id2"]);
+}
+
+#[cfg(feature = "xz")]
+#[test]
+fn test_xz() {
+    let mut enc = xz2::write::XzEncoder::new(vec![], 6);
+    enc.write_all(FASTQ).unwrap();
+    let data = enc.finish().unwrap();
+    let path = write_tmp("xz.fq.xz", &data);
+    let reader = fastq::Reader::from_path_auto(&path).unwrap();
+    assert_eq!(fastq_ids(reader), vec!["id1", "id2"]);
+    std::fs::remove_file(path).unwrap();
+    let reader = fastq::Reader::from_xz(&data[..]);
+    assert_eq!(fastq_ids(reader), vec!["id1", "id2"]);
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn test_zstd() {
+    let data = zstd::encode_all(FASTA, 3).unwrap();
+    let path = write_tmp("zst.fa.zst", &data);
+    let reader = fasta::Reader::from_path_auto(&path).unwrap();
+    assert_eq!(fasta_ids(reader), vec!["id1", "id2"]);
+    std::fs::remove_file(path).unwrap();
+    let reader = fasta::Reader::from_zstd(&data[..]).unwrap();
+    assert_eq!(fasta_ids(reader), vec!["id1", "id2"]);
+}
+
+fn format_enabled(format: Format) -> bool {
+    match format {
+        Format::Plain => true,
+        Format::Gzip => cfg!(feature = "gzip"),
+        Format::Bgzf => cfg!(feature = "bgzf"),
+        Format::Bzip2 => cfg!(feature = "bzip2"),
+        Format::Xz => cfg!(feature = "xz"),
+        Format::Zstd => cfg!(feature = "zstd"),
+    }
+}
+
+#[test]
+fn test_encoder() {
+    use seq_io::compression::{detect, Encoder};
+    let formats = [
+        Format::Plain,
+        Format::Gzip,
+        Format::Bgzf,
+        Format::Bzip2,
+        Format::Xz,
+        Format::Zstd,
+    ];
+    for format in formats {
+        let encoder = match Encoder::new(vec![], format, Some(1)) {
+            Ok(e) => e,
+            Err(e) => {
+                assert!(!format_enabled(format));
+                assert_eq!(e.kind(), std::io::ErrorKind::Unsupported);
+                continue;
+            }
+        };
+        assert!(format_enabled(format));
+        let mut writer = fastq::Writer::new(encoder);
+        let mut reader = fastq::Reader::new(FASTQ);
+        while let Some(rec) = reader.next() {
+            writer.write_record(&rec.unwrap()).unwrap();
+        }
+        let data = writer.finish().unwrap();
+        // BGZF is detected as gzip
+        let detected = if format == Format::Bgzf {
+            Format::Gzip
+        } else {
+            format
+        };
+        assert_eq!(detect(&data[..]).unwrap().0, detected);
+        let reader = fastq::Reader::new(auto_reader(std::io::Cursor::new(data)).unwrap());
+        assert_eq!(fastq_ids(reader), vec!["id1", "id2"]);
+    }
+}
+
+#[test]
+fn test_writer_auto() {
+    let extensions = [
+        ("fa", Format::Plain),
+        ("fa.gz", Format::Gzip),
+        (
+            "fa.bgz",
+            if cfg!(feature = "bgzf") {
+                Format::Bgzf
+            } else {
+                Format::Gzip
+            },
+        ),
+        ("fa.bz2", Format::Bzip2),
+        ("fa.xz", Format::Xz),
+        ("fa.zst", Format::Zstd),
+    ];
+    for (ext, format) in extensions {
+        let path = std::env::temp_dir().join(format!(
+            "seq_io_test_{}_writer_auto.{}",
+            std::process::id(),
+            ext
+        ));
+        assert_eq!(Format::from_path(&path), format);
+        let mut writer = match fasta::Writer::from_path_auto(&path) {
+            Ok(w) => w,
+            Err(e) => {
+                assert!(!format_enabled(format));
+                assert_eq!(e.kind(), std::io::ErrorKind::Unsupported);
+                assert!(!path.exists());
+                continue;
+            }
+        };
+        writer.write(b"id1 desc", b"ACGTAC").unwrap();
+        writer.write(b"id2", b"TGCA").unwrap();
+        writer.finish().unwrap();
+        let reader = fasta::Reader::from_path_auto(&path).unwrap();
+        assert_eq!(fasta_ids(reader), vec!["id1", "id2"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
+
+#[cfg(feature = "bgzf")]
+#[test]
+fn test_encoder_bgzf() {
+    use seq_io::compression::Encoder;
+    let mut writer = fastq::Writer::new(Encoder::bgzf(vec![], None));
+    let mut reader = fastq::Reader::new(FASTQ);
+    while let Some(rec) = reader.next() {
+        writer.write_record(&rec.unwrap()).unwrap();
+    }
+    let data = writer.finish().unwrap();
+    assert!(data.ends_with(&seq_io::bgzf::EOF_BLOCK));
+    let reader = fastq::Reader::new(seq_io::bgzf::Reader::new(&data[..]));
+    assert_eq!(fastq_ids(reader), vec!["id1", "id2"]);
+}
+
+#[cfg(feature = "bgzf")]
+#[test]
+fn test_writer_auto_bgzf() {
+    for (ext, bgzf) in [("bgz", true), ("gz", false)] {
+        let path = write_tmp(&format!("writer_auto_bgzf.fa.{}", ext), b"");
+        let mut writer = fasta::Writer::from_path_auto(&path).unwrap();
+        writer.write(b"id1 desc", b"ACGTAC").unwrap();
+        writer.write(b"id2", b"TGCA").unwrap();
+        writer.finish().unwrap();
+        let data = std::fs::read(&path).unwrap();
+        assert_eq!(data.ends_with(&seq_io::bgzf::EOF_BLOCK), bgzf);
+        let reader = fasta::Reader::from_path_auto(&path).unwrap();
+        assert_eq!(fasta_ids(reader), vec!["id1", "id2"]);
+
+        // opt-in BGZF for .gz
+        let mut writer = fasta::Writer::from_path_auto_bgzf(&path).unwrap();
+        writer.write(b"id1", b"ACGTAC").unwrap();
+        writer.finish().unwrap();
+        let data = std::fs::read(&path).unwrap();
+        assert!(data.ends_with(&seq_io::bgzf::EOF_BLOCK));
+        let reader = fasta::Reader::new(seq_io::bgzf::Reader::new(&data[..]));
+        assert_eq!(fasta_ids(reader), vec!["id1"]);
+        std::fs::remove_file(path).unwrap();
+    }
+}
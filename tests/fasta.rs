@@ -165,12 +165,275 @@ fn test_fasta_invalid_start() {
     assert_matches!(
         rec,
         Err(Error::InvalidStart {
-            line: 2,
-            found: b'i'
+            found: b'i',
+            pos: ErrorPosition {
+                line: 2,
+                column: Some(1),
+                record_line: 2,
+                byte: 2,
+                record: 0,
+                id: None,
+                ..
+            }
         })
     );
 }
 
+fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+#[test]
+fn test_fasta_recovery() {
+    use std::sync::{Arc, Mutex};
+
+    let fasta = b"\n; comment\n;more >x\n>id1\nACGT\n>id2\nTT\n";
+    for cap in 3..40 {
+        let skipped = Arc::new(Mutex::new(vec![]));
+        let s = skipped.clone();
+        let mut reader = Reader::with_capacity(&fasta[..], cap).set_recovery(move |sk| {
+            s.lock().unwrap().push(sk);
+        });
+        assert_send_sync(&reader);
+        let mut ids = vec![];
+        while let Some(rec) = reader.next() {
+            ids.push(rec.unwrap().id().unwrap().to_string());
+        }
+        assert_eq!(ids, ["id1", "id2"]);
+        let skipped = skipped.lock().unwrap();
+        assert_eq!(skipped.len(), 1);
+        assert_matches!(
+            skipped[0].error,
+            Error::InvalidStart {
+                found: b';',
+                pos: ErrorPosition {
+                    line: 2,
+                    column: Some(1),
+                    record_line: 2,
+                    byte: 1,
+                    ..
+                }
+            }
+        );
+        assert_eq!(
+            (skipped[0].line, skipped[0].start, skipped[0].end),
+            (2, 1, 20)
+        );
+        assert_eq!(reader.recovery_stats().skipped_bytes, 19);
+    }
+
+    // no header at all
+    let mut reader = Reader::new(&b"junk\nmore\n"[..]).set_recovery(|_| ());
+    assert!(reader.next().is_none());
+    assert_eq!(reader.recovery_stats().errors, 1);
+    assert_eq!(reader.recovery_stats().skipped_bytes, 10);
+}
+
+#[test]
+fn test_fasta_empty_record() {
+    let fasta = b">id1\nACGT\n>id2\n\n>id3\nTT\n>id4\n";
+
+    // allowed by default
+    let mut reader = Reader::new(&fasta[..]);
+    let mut n = 0;
+    while let Some(rec) = reader.next() {
+        rec.unwrap();
+        n += 1;
+    }
+    assert_eq!(n, 4);
+
+    let mut reader = Reader::new(&fasta[..]).set_allow_empty(false);
+    assert_eq!(reader.next().unwrap().unwrap().id(), Ok("id1"));
+    let err = reader.next().unwrap().unwrap_err();
+    assert_matches!(
+        err,
+        Error::EmptyRecord {
+            pos: ErrorPosition {
+                line: 3,
+                column: None,
+                record_line: 3,
+                byte: 10,
+                record: 1,
+                ..
+            }
+        }
+    );
+    if let Error::EmptyRecord { pos } = err {
+        assert_eq!(pos.id.as_deref(), Some("id2"));
+        // seek to the empty record
+        let mut reader = Reader::new(io::Cursor::new(&fasta[..]));
+        reader.seek(&pos.to_fasta_position()).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap().id(), Ok("id2"));
+        assert_eq!(reader.position(), Some(&Position::with_record(3, 10, 1)));
+        // the record index is restored by seek()
+        let mut reader = Reader::new(io::Cursor::new(&fasta[..])).set_allow_empty(false);
+        reader.seek(&pos.to_fasta_position()).unwrap();
+        assert_matches!(
+            reader.next().unwrap(),
+            Err(Error::EmptyRecord {
+                pos: ErrorPosition {
+                    line: 3,
+                    byte: 10,
+                    record: 1,
+                    ..
+                }
+            })
+        );
+    }
+    assert!(reader.next().is_none());
+
+    let mut reader = Reader::new(&b">id1\nACGT\n>id2"[..]).set_allow_empty(false);
+    assert!(reader.next().unwrap().is_ok());
+    assert_matches!(
+        reader.next().unwrap(),
+        Err(Error::UnexpectedEnd {
+            pos: ErrorPosition {
+                line: 3,
+                column: None,
+                record_line: 3,
+                byte: 10,
+                record: 1,
+                ..
+            }
+        })
+    );
+}
+
+#[test]
+fn test_fasta_error_last_id() {
+    let fasta = b">id0\nACGT\n>id1 desc\nAC\nGT\n>id2\n\n>id3\nTT\n";
+    for cap in 3..40 {
+        let mut reader = Reader::with_capacity(&fasta[..], cap).set_allow_empty(false);
+        reader.next().unwrap().unwrap();
+        reader.next().unwrap().unwrap();
+        match reader.next().unwrap() {
+            Err(Error::EmptyRecord { pos }) => {
+                assert_eq!(pos.id.as_deref(), Some("id2"));
+                assert_eq!(pos.last_id.as_deref(), Some("id1"), "cap: {}", cap);
+            }
+            res => panic!(
+                "unexpected result: {:?}",
+                res.map(|r| r.id_bytes().to_vec())
+            ),
+        }
+
+        let mut reader = Reader::with_capacity(&fasta[..], cap).set_allow_empty(false);
+        let mut rset = RecordSet::default();
+        loop {
+            match reader.read_record_set(&mut rset) {
+                Some(Ok(())) => {}
+                Some(Err(Error::EmptyRecord { pos })) => {
+                    assert_eq!(pos.last_id.as_deref(), Some("id1"), "cap: {}", cap);
+                    break;
+                }
+                res => panic!("unexpected result: {:?}", res.map(|r| r.is_ok())),
+            }
+        }
+    }
+
+    // error in first record
+    let mut reader = Reader::new(&b"id0\nACGT\n"[..]);
+    assert_matches!(
+        reader.next().unwrap(),
+        Err(Error::InvalidStart {
+            pos: ErrorPosition { last_id: None, .. },
+            ..
+        })
+    );
+
+    // slice reader
+    let mut reader = SliceReader::new(&fasta[..]).set_allow_empty(false);
+    reader.next().unwrap().unwrap();
+    reader.next().unwrap().unwrap();
+    let err = reader.next().unwrap().unwrap_err();
+    if let Error::EmptyRecord { ref pos } = err {
+        assert_eq!(pos.last_id.as_deref(), Some("id1"));
+    } else {
+        panic!("unexpected error: {}", err);
+    }
+    assert!(err.to_string().contains(", after record 'id1')"));
+}
+
+#[test]
+fn test_fasta_empty_record_recovery() {
+    let fasta = b">id1\nACGT\n>id2\n\n>id3\nTT\n>id4\n";
+    for cap in 3..30 {
+        let mut reader = Reader::with_capacity(&fasta[..], cap)
+            .set_allow_empty(false)
+            .set_recovery(|_| ());
+        let mut ids = vec![];
+        while let Some(rec) = reader.next() {
+            ids.push(rec.unwrap().id().unwrap().to_string());
+        }
+        assert_eq!(ids, ["id1", "id3"]);
+        assert_eq!(reader.recovery_stats().errors, 2);
+        assert_eq!(reader.recovery_stats().skipped_bytes, 6 + 5);
+
+        let mut reader = Reader::with_capacity(&fasta[..], cap)
+            .set_allow_empty(false)
+            .set_recovery(|_| ());
+        let mut rset = RecordSet::default();
+        let mut ids = vec![];
+        while let Some(res) = reader.read_record_set(&mut rset) {
+            res.unwrap();
+            ids.extend(rset.into_iter().map(|r| r.id().unwrap().to_string()));
+        }
+        assert_eq!(ids, ["id1", "id3"]);
+        assert_eq!(reader.recovery_stats().errors, 2);
+    }
+}
+
+#[test]
+fn test_fasta_validation() {
+    use seq_io::validation::{Alphabet, Validation};
+
+    let fasta = b">id1\nACGT\nAC\n>id2\nACGT\nAXGT\n>\nACGT\n";
+
+    let mut reader =
+        Reader::new(&fasta[..]).set_validation(Validation::new().set_alphabet(Alphabet::Dna));
+    assert!(reader.next().unwrap().is_ok());
+    assert_matches!(
+        reader.next().unwrap(),
+        Err(Error::InvalidSeq {
+            found: b'X',
+            pos: ErrorPosition {
+                line: 6,
+                column: Some(2),
+                record_line: 4,
+                byte: 13,
+                record: 1,
+                ..
+            }
+        })
+    );
+    assert!(reader.next().is_none());
+
+    let mut reader =
+        Reader::new(&fasta[..]).set_validation(Validation::new().set_check_header(true));
+    let mut rset = RecordSet::default();
+    let err = loop {
+        if let Err(e) = reader.read_record_set(&mut rset).unwrap() {
+            break e;
+        }
+    };
+    assert_matches!(
+        err,
+        Error::EmptyHeader {
+            pos: ErrorPosition {
+                line: 7,
+                record: 2,
+                id: None,
+                ..
+            }
+        }
+    );
+
+    // protein sequences
+    let validation = Validation::new().set_alphabet(Alphabet::Protein);
+    let mut reader = Reader::new(&fasta[..]).set_validation(validation);
+    while let Some(rec) = reader.next() {
+        rec.unwrap();
+    }
+}
+
 #[test]
 fn test_fasta_none_after_err() {
     let mut reader = Reader::new(&b"id\nATGC\n"[..]);
@@ -305,6 +568,121 @@ fn test_fasta_parallel() {
     }
 }
 
+#[cfg(feature = "rayon")]
+#[test]
+fn test_fasta_par_records() {
+    use rayon::iter::ParallelIterator;
+    use std::io::Write;
+    let mut fa = vec![];
+    for i in 0..200 {
+        write!(&mut fa, ">id{}\nAT\nGC\n", i).unwrap();
+    }
+    for cap in (3..400).step_by(31) {
+        let mut records: Vec<_> = Reader::with_capacity(&fa[..], cap)
+            .par_records()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        records.sort_by_key(|r| r.id().unwrap()[2..].parse::<usize>().unwrap());
+        let expected: Vec<_> = Reader::new(&fa[..])
+            .into_records()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(records, expected);
+    }
+}
+
+#[test]
+fn test_fasta_read_file_chunked() {
+    use std::io::Write;
+    let mut fa = vec![];
+    for i in 0..300 {
+        writeln!(&mut fa, ">id{} desc", i).unwrap();
+        for j in 0..i % 5 {
+            writeln!(&mut fa, "{}", "ACGT".repeat(j + 1)).unwrap();
+        }
+        if i % 7 == 0 {
+            fa.push(b'\n');
+        }
+    }
+    let path =
+        std::env::temp_dir().join(format!("seq_io_test_{}_chunked.fasta", std::process::id()));
+    std::fs::File::create(&path)
+        .unwrap()
+        .write_all(&fa)
+        .unwrap();
+
+    let mut reader = Reader::new(&fa[..]);
+    let mut expected = vec![];
+    while let Some(rec) = reader.next() {
+        let rec = rec.unwrap().to_owned_record();
+        expected.push((reader.position().unwrap().clone(), rec));
+    }
+    for n in 1..40 {
+        let records: Vec<_> =
+            seq_io::parallel::read_file_chunked(&path, n, |mut reader: Reader<_>| {
+                let mut out = vec![];
+                while let Some(rec) = reader.next() {
+                    let rec = rec.unwrap().to_owned_record();
+                    out.push((reader.position().unwrap().clone(), rec));
+                }
+                out
+            })
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect();
+        assert_eq!(records, expected);
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_fasta_parallel_ordered() {
+    use std::io::Write;
+    let mut fa = vec![];
+    for i in 0..200 {
+        write!(&mut fa, ">id{}\nAT\nGC\n", i).unwrap();
+    }
+    for cap in (3..400).step_by(31) {
+        let par_reader = Reader::with_capacity(&fa[..], cap);
+        let mut reader = Reader::new(&fa[..]);
+        seq_io::parallel::parallel_fasta_ordered(
+            par_reader,
+            4,
+            3,
+            |rec, out: &mut String| *out = rec.id().unwrap().to_string(),
+            |rec, id| {
+                let r0 = reader.next().unwrap().unwrap();
+                assert_eq!(rec.id(), r0.id());
+                assert_eq!(id, r0.id().unwrap());
+                None::<()>
+            },
+        )
+        .unwrap();
+        assert!(reader.next().is_none());
+    }
+}
+
+#[test]
+fn test_fasta_parallel_write() {
+    use std::io::Write;
+    let mut fa = vec![];
+    let mut expected = vec![];
+    for i in 0..200 {
+        write!(&mut fa, ">id{}\nAT\nGC\n", i).unwrap();
+        write!(&mut expected, ">id{}\nATGC\n", i).unwrap();
+    }
+    for cap in (3..400).step_by(31) {
+        let reader = Reader::with_capacity(&fa[..], cap);
+        let out = seq_io::parallel::parallel_fasta_write(reader, 4, 2, vec![], |rec, out| {
+            write_head(&mut *out, rec.head()).unwrap();
+            write_seq(&mut *out, &rec.owned_seq()).unwrap();
+        })
+        .unwrap();
+        assert_eq!(out, expected);
+    }
+}
+
 #[test]
 fn test_fasta_seek() {
     let fa = b"\n\n\n\n>s1\nAT\nGC\n\n>s2\n\nATGC\n>s3\nATGC\n";
@@ -350,6 +728,86 @@ fn test_write_fasta() {
     assert_eq!(&out, &fasta);
 }
 
+#[test]
+fn test_fasta_writer() {
+    use seq_io::LineEnding;
+    // input with LF and CRLF
+    for terminator in [&b"\n"[..], b"\r\n"] {
+        let fasta = concat_lines(FASTA, terminator, true);
+        for (ending, width) in [
+            (LineEnding::Lf, 10),
+            (LineEnding::CrLf, 10),
+            (LineEnding::Lf, 3),
+        ] {
+            let mut expected = vec![];
+            let mut reader = Reader::new(&fasta[..]);
+            while let Some(r) = reader.next() {
+                r.unwrap().write_wrap(&mut expected, width).unwrap();
+            }
+            if ending == LineEnding::CrLf {
+                expected = concat_lines(
+                    &expected.split(|&b| b == b'\n').collect::<Vec<_>>(),
+                    b"\r\n",
+                    false,
+                );
+            }
+
+            let mut reader = Reader::new(&fasta[..]);
+            let mut writer = Writer::new(vec![])
+                .set_line_width(Some(width))
+                .set_line_ending(ending);
+            while let Some(r) = reader.next() {
+                writer.write_record(&r.unwrap()).unwrap();
+            }
+            assert_eq!(writer.num_records(), 2);
+            assert_eq!(writer.num_bases(), 78);
+            let out = writer.into_inner().unwrap();
+            assert_eq!(out, expected);
+        }
+    }
+
+    // owned records, single-line output, no description
+    let records: Vec<_> = Reader::new(&concat_lines(FASTA, b"\n", true)[..])
+        .records()
+        .map(|r| r.unwrap())
+        .collect();
+    let mut writer = Writer::new(vec![]).set_write_desc(false);
+    for rec in &records {
+        writer.write_record(rec).unwrap();
+    }
+    writer.write_parts(b"id3", Some(b"desc"), b"").unwrap();
+    writer.write(b"id4 desc", b"A").unwrap();
+    assert_eq!(writer.num_records(), 4);
+    assert_eq!(writer.num_bases(), 79);
+    assert_eq!(
+        &writer.into_inner().unwrap()[..],
+        &b">id\nACCGTAGGCTCCGTAGGCTGCGTAGGCTGAGTAGGCTGAACCCC\n\
+           >id2\nATTGTTGTTTATTGTTGTTTATTGTTGTTTGGGG\n>id3\n\n>id4\nA\n"[..]
+    );
+}
+
+#[test]
+fn test_fasta_writer_header_config() {
+    use seq_io::header::{Delimiter, HeaderConfig};
+    let config = HeaderConfig::new()
+        .set_delimiter(Delimiter::Tab)
+        .set_strip_read_number(true);
+    let input = b">id/1 x\tdesc\nACGT\n";
+    // the header is written unchanged
+    let mut reader = Reader::new(&input[..]).set_header_config(config);
+    let mut writer = Writer::new(vec![]).set_header_config(config);
+    writer
+        .write_record(&reader.next().unwrap().unwrap())
+        .unwrap();
+    assert_eq!(&writer.into_inner().unwrap()[..], &input[..]);
+    // split at the tab, the ID is not modified
+    let mut writer = Writer::new(vec![])
+        .set_header_config(config)
+        .set_write_desc(false);
+    writer.write(b"id/1 x\tdesc", b"ACGT").unwrap();
+    assert_eq!(&writer.into_inner().unwrap()[..], b">id/1 x\nACGT\n");
+}
+
 #[test]
 fn test_fasta_write_head() {
     let mut out = vec![];
@@ -527,3 +985,349 @@ fn test_fasta_mixed_read_seek() {
         assert_eq!(&out, &fasta);
     }
 }
+
+// FASTA index
+
+#[test]
+fn test_fasta_index() {
+    use seq_io::fasta::index::{Index, IndexRecord, IndexedReader};
+
+    for t in &[&b"\n"[..], b"\r\n"] {
+        let fasta = concat_lines(FASTA, t, false);
+        let lt = t.len() as u64;
+        for cap in 3..100 {
+            let mut reader = Reader::with_capacity(fasta.as_slice(), cap);
+            let index = Index::build(&mut reader).unwrap();
+            assert_eq!(
+                index.records(),
+                &[
+                    IndexRecord {
+                        name: "id".to_string(),
+                        len: 44,
+                        offset: 8 + lt,
+                        line_bases: 10,
+                        line_width: 10 + lt,
+                    },
+                    IndexRecord {
+                        name: "id2".to_string(),
+                        len: 34,
+                        offset: 8 + lt + 4 * (10 + lt) + 4 + lt + 4 + lt,
+                        line_bases: 10,
+                        line_width: 10 + lt,
+                    },
+                ]
+            );
+        }
+
+        let index = Index::build(&mut Reader::new(fasta.as_slice())).unwrap();
+        let mut fai = vec![];
+        index.write(&mut fai).unwrap();
+        assert_eq!(Index::read(fai.as_slice()).unwrap(), index);
+
+        let full: Vec<_> = FASTA[1..6].concat();
+        let mut reader = IndexedReader::new(io::Cursor::new(&fasta), index);
+        let mut seq = vec![];
+        for start in 0..full.len() {
+            for end in start..full.len() + 2 {
+                reader
+                    .fetch("id", start as u64, end as u64, &mut seq)
+                    .unwrap();
+                assert_eq!(&seq, &full[start..end.min(full.len())]);
+            }
+        }
+        reader.fetch_region("id2:11-22", &mut seq).unwrap();
+        assert_eq!(&seq, b"ATTGTTGTTTAT");
+        reader.fetch_region("id2:31", &mut seq).unwrap();
+        assert_eq!(&seq, b"GGGG");
+        reader.fetch_region("id2", &mut seq).unwrap();
+        assert_eq!(&seq, &FASTA[7..11].concat());
+    }
+}
+
+#[test]
+fn test_fasta_index_ragged() {
+    use seq_io::fasta::index::{Error, Index};
+    let fa = &b">s1\nATG\nCA\nG\n>s2\nA\n"[..];
+    let res = Index::build(&mut Reader::new(fa));
+    assert_matches!(res, Err(Error::RaggedLines { ref name, line: 3 }) if name == "s1");
+    let fa = &b">s1\nATG\nC\n>s2\nAT\n\nGC\n"[..];
+    let res = Index::build(&mut Reader::new(fa));
+    assert_matches!(res, Err(Error::RaggedLines { ref name, line: 6 }) if name == "s2");
+    let fa = &b">s1\nAT\nGCA\n"[..];
+    let res = Index::build(&mut Reader::new(fa));
+    assert_matches!(res, Err(Error::RaggedLines { line: 3, .. }));
+    // empty lines at the end are ignored
+    let fa = &b">s1\nAT\nG\n\n\n>s2\n"[..];
+    let index = Index::build(&mut Reader::new(fa)).unwrap();
+    assert_eq!(index.get("s1").unwrap().len, 3);
+    assert_eq!(index.get("s2").unwrap().len, 0);
+}
+
+#[test]
+fn test_fasta_index_errors() {
+    use seq_io::fasta::index::{Error, Index, IndexedReader, Region};
+    let fa = &b">s1\nAT\n>s1\nGC\n"[..];
+    let res = Index::build(&mut Reader::new(fa));
+    assert_matches!(res, Err(Error::DuplicateName(ref n)) if n == "s1");
+
+    let res = Index::read(&b"s1\t2\t4\t2\t3\ns2\t2\tx\t2\t3\n"[..]);
+    assert_matches!(res, Err(Error::InvalidIndex { line: 2 }));
+
+    assert_eq!(
+        Region::parse("chr1:1,001-2,000").unwrap(),
+        Region::new("chr1", 1000, Some(2000))
+    );
+    assert_eq!(Region::parse("chr1").unwrap(), Region::new("chr1", 0, None));
+    assert_matches!(Region::parse("chr1:0-10"), Err(Error::InvalidRegion(_)));
+    assert_matches!(Region::parse("chr1:10-5"), Err(Error::InvalidRegion(_)));
+
+    let fa = &b">s1\nAT\n>s2:1\nGC\n"[..];
+    let index = Index::build(&mut Reader::new(fa)).unwrap();
+    let mut reader = IndexedReader::new(io::Cursor::new(fa), index);
+    let mut seq = vec![];
+    reader.fetch_region("s2:1", &mut seq).unwrap();
+    assert_eq!(&seq, b"GC");
+    reader.fetch_region("s2:1:2-2", &mut seq).unwrap();
+    assert_eq!(&seq, b"C");
+    assert_matches!(
+        reader.fetch_region("s3:1-2", &mut seq),
+        Err(Error::UnknownSequence(ref n)) if n == "s3"
+    );
+}
+
+#[test]
+fn test_fasta_header_config() {
+    use seq_io::header::{Delimiter, HeaderConfig};
+
+    let fasta = b">sp|P1|X_HUMAN\tdesc 1\nA\n>id2/2 desc\nA\n";
+    let config = HeaderConfig::new()
+        .set_delimiter(Delimiter::Whitespace)
+        .set_truncate_pipe(true)
+        .set_strip_read_number(true);
+
+    let mut reader = Reader::new(&fasta[..]).set_header_config(config);
+    let rec = reader.next().unwrap().unwrap();
+    assert_eq!(rec.id(), Ok("sp"));
+    assert_eq!(rec.desc(), Some(Ok("desc 1")));
+    assert_eq!(rec.id_desc(), Ok(("sp", Some("desc 1"))));
+    let rec = reader.next().unwrap().unwrap();
+    assert_eq!(rec.id_desc_bytes(), (&b"id2"[..], Some(&b"desc"[..])));
+
+    let mut reader = Reader::new(&fasta[..]).set_header_config(config);
+    let mut rset = RecordSet::default();
+    reader.read_record_set(&mut rset).unwrap().unwrap();
+    let ids: Vec<_> = rset
+        .into_iter()
+        .map(|r| r.id().unwrap().to_string())
+        .collect();
+    assert_eq!(ids, ["sp", "id2"]);
+
+    // the owned record keeps the header, but not the settings
+    let mut reader = Reader::new(&fasta[..]).set_header_config(config);
+    let rec = reader.next().unwrap().unwrap().to_owned_record();
+    assert_eq!(rec.id(), Ok("sp|P1|X_HUMAN\tdesc"));
+}
+
+#[test]
+fn test_fasta_stream() {
+    let inputs: &[&[u8]] = &[
+        b">id1 desc\nACGTACGT\nAC\n>id2\n>id3\r\nAC\r\nGT\r\n\r\nTT\r\n>id4\n\nAC\n\n>id5\nACGT",
+        b"\n\n>a\nACGT\n>b\n",
+        b">a\nACGT\n>b",
+        b">a\nAC\r",
+        b">a",
+        b"",
+    ];
+    for input in inputs {
+        let expected: Vec<_> = Reader::new(*input)
+            .records()
+            .map(|r| {
+                let r = r.unwrap();
+                (r.head, r.seq)
+            })
+            .collect();
+        for cap in 3..40 {
+            let mut reader = Reader::with_capacity(*input, cap);
+            let mut records = vec![];
+            while let Some(stream) = reader.next_stream() {
+                let mut stream = stream.unwrap();
+                let head = stream.head().to_vec();
+                let mut seq = vec![];
+                while let Some(chunk) = stream.next_chunk() {
+                    let chunk = chunk.unwrap();
+                    assert!(!chunk.is_empty());
+                    assert!(!chunk.contains(&b'\n') && !chunk.contains(&b'\r'));
+                    seq.extend_from_slice(chunk);
+                }
+                // EOF or next record was found
+                assert!(stream.next_chunk().is_none());
+                records.push((head, seq));
+            }
+            assert_eq!(records, expected, "capacity {}", cap);
+            assert!(reader.next().is_none());
+        }
+    }
+}
+
+#[test]
+fn test_fasta_stream_mixed() {
+    let fasta = b">id1\nACGT\nACGT\n>id2 desc\nTTTT\nTT\n>id3\nGG\n>id4\nCC\n";
+
+    for cap in 5..30 {
+        let mut reader = Reader::with_capacity(io::Cursor::new(&fasta[..]), cap);
+
+        // partially read stream
+        let mut stream = reader.next_stream().unwrap().unwrap();
+        assert_eq!(stream.id(), Ok("id1"));
+        assert!(stream.next_chunk().unwrap().unwrap().starts_with(b"A"));
+
+        // the rest is skipped by next()
+        let rec = reader.next().unwrap().unwrap();
+        assert_eq!(rec.id(), Ok("id2"));
+        assert_eq!(reader.position().unwrap(), &Position::with_record(4, 15, 1));
+
+        let stream = reader.next_stream().unwrap().unwrap();
+        assert_eq!(stream.id(), Ok("id3"));
+        assert_eq!(stream.desc(), None);
+        assert_eq!(reader.position().unwrap(), &Position::with_record(7, 33, 2));
+
+        // read_record_set() after an unfinished stream
+        let mut rset = RecordSet::default();
+        reader.read_record_set(&mut rset).unwrap().unwrap();
+        let ids: Vec<_> = rset
+            .into_iter()
+            .map(|r| r.id().unwrap().to_string())
+            .collect();
+        assert_eq!(ids, ["id4"]);
+        assert!(reader.next_stream().is_none());
+
+        // seek back to id2 and stream it, then continue with next()
+        reader.seek(&Position::with_record(4, 15, 1)).unwrap();
+        let mut stream = reader.next_stream().unwrap().unwrap();
+        assert_eq!(stream.desc(), Some(Ok("desc")));
+        let mut seq = vec![];
+        while let Some(chunk) = stream.next_chunk() {
+            seq.extend_from_slice(chunk.unwrap());
+        }
+        assert_eq!(&seq, b"TTTTTT");
+        assert_eq!(reader.next().unwrap().unwrap().id(), Ok("id3"));
+        assert_eq!(reader.position().unwrap(), &Position::with_record(7, 33, 2));
+
+        // seek from within a streamed record
+        let mut reader = Reader::with_capacity(io::Cursor::new(&fasta[..]), cap);
+        let mut stream = reader.next_stream().unwrap().unwrap();
+        while let Some(chunk) = stream.next_chunk() {
+            chunk.unwrap();
+        }
+        let mut stream = reader.next_stream().unwrap().unwrap();
+        stream.next_chunk().unwrap().unwrap();
+        reader.seek(&Position::new(7, 33)).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap().id(), Ok("id3"));
+        reader.seek(&Position::new(1, 0)).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap().id(), Ok("id1"));
+    }
+}
+
+#[test]
+fn test_fasta_slice_reader() {
+    let fasta = concat_lines(FASTA, b"\n", true);
+    let fasta_crlf = concat_lines(FASTA, b"\r\n", false);
+    let inputs: &[&[u8]] = &[
+        &fasta,
+        &fasta_crlf,
+        b">id1 desc\nACGTACGT\nAC\n>id2\n>id3\r\nAC\r\nGT\r\n\r\nTT\r\n>id4\n\nAC\n\n>id5\nACGT",
+        b"\n\r\n>a\nACGT\n>b\n",
+        b">a\nACGT\n>b",
+        b">a\nAC\r",
+        b">a\n",
+        b">a",
+        b"",
+        b"\n\n",
+        b"\nid\nACGT\n",
+        b">a\nACGT\n>b\n\n>c\nACGT\n",
+    ];
+    for &input in inputs {
+        for &allow_empty in &[true, false] {
+            let mut reader = Reader::new(input).set_allow_empty(allow_empty);
+            let mut expected = vec![];
+            while let Some(res) = reader.next() {
+                expected.push(match res {
+                    Ok(r) => {
+                        let mut out = vec![];
+                        r.write_unchanged(&mut out).unwrap();
+                        let lines: Vec<_> = r.seq_lines().rev().map(|l| l.to_vec()).collect();
+                        let rec = format!(
+                            "{:?} {:?} {:?} {:?}",
+                            r.to_owned_record(),
+                            r.seq(),
+                            lines,
+                            out
+                        );
+                        format!("{} {:?}", rec, reader.position())
+                    }
+                    Err(e) => format!("{:?}", e),
+                });
+            }
+            let mut reader = SliceReader::new(input).set_allow_empty(allow_empty);
+            let mut out = vec![];
+            while let Some(res) = reader.next() {
+                out.push(match res {
+                    Ok(r) => {
+                        let mut out = vec![];
+                        r.write_unchanged(&mut out).unwrap();
+                        let lines: Vec<_> = r.seq_lines().rev().map(|l| l.to_vec()).collect();
+                        assert_eq!(lines.len(), r.num_seq_lines());
+                        let rec = format!(
+                            "{:?} {:?} {:?} {:?}",
+                            r.to_owned_record(),
+                            r.seq(),
+                            lines,
+                            out
+                        );
+                        format!("{} {:?}", rec, reader.position())
+                    }
+                    Err(e) => format!("{:?}", e),
+                });
+            }
+            assert_eq!(
+                out,
+                expected,
+                "{:?} (allow empty: {})",
+                String::from_utf8_lossy(input),
+                allow_empty
+            );
+        }
+    }
+}
+
+#[test]
+fn test_fasta_slice_records() {
+    let fasta = concat_lines(FASTA, b"\n", true);
+    // the records outlive the reader
+    let records: Vec<SliceRecord> = {
+        let mut reader = SliceReader::new(&fasta);
+        assert!(reader.position().is_none());
+        let records = reader.by_ref().collect::<Result<_, _>>().unwrap();
+        assert_eq!(reader.position(), Some(&Position::with_record(7, 58, 1)));
+        assert_eq!(reader.remaining(), b"");
+        records
+    };
+
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].id(), Ok("id"));
+    assert_eq!(records[0].desc(), Some(Ok("desc")));
+    assert_eq!(records[0].head_slice(), b"id desc");
+    assert_eq!(records[0].num_seq_lines(), 5);
+    assert_eq!(records[0].seq_slice(), &fasta[9..57]);
+    assert_eq!(
+        records[1].full_seq().as_ref(),
+        &b"ATTGTTGTTTATTGTTGTTTATTGTTGTTTGGGG"[..]
+    );
+
+    let mut out = vec![];
+    records[1].write_wrap(&mut out, 20).unwrap();
+    assert_eq!(
+        &out[..],
+        &b">id2\nATTGTTGTTTATTGTTGTTT\nATTGTTGTTTGGGG\n"[..]
+    );
+}
@@ -39,6 +39,20 @@ lazy_static! {
     ];
 }
 
+// Fields of an error position for comparison with `assert_eq!`
+type PosFields<'a> = (u64, Option<usize>, u64, u64, u64, Option<&'a str>);
+
+fn pos_fields(pos: &ErrorPosition) -> PosFields<'_> {
+    (
+        pos.line,
+        pos.column,
+        pos.record_line,
+        pos.byte,
+        pos.record,
+        pos.id.as_deref(),
+    )
+}
+
 #[test]
 fn test_fastq_reader() {
     // try different initial capacities to test
@@ -85,58 +99,108 @@ fn test_fastq_invalid_start() {
         rec,
         Err(Error::InvalidStart {
             found: b'i',
-            pos: ErrorPosition { line: 5, id: None }
+            pos: ErrorPosition {
+                line: 5,
+                column: Some(1),
+                record_line: 5,
+                byte: 11,
+                record: 1,
+                id: None,
+                ..
+            }
         })
     );
 }
 
 #[test]
-#[allow(unused_variables)]
+fn test_fastq_error_last_id() {
+    let fastq = b"@id0\nACGT\n+\nIIII\n@id1 desc\nACGT\n+\nIIII\nid2\nA\n+\nI\n";
+    for cap in 3..60 {
+        let mut reader = Reader::with_capacity(&fastq[..], cap);
+        reader.next().unwrap().unwrap();
+        reader.next().unwrap().unwrap();
+        match reader.next().unwrap() {
+            Err(Error::InvalidStart { found: b'i', pos }) => {
+                assert_eq!(pos.record, 2);
+                assert_eq!(pos.last_id.as_deref(), Some("id1"), "cap: {}", cap);
+            }
+            res => panic!(
+                "unexpected result: {:?}",
+                res.map(|r| r.id_bytes().to_vec())
+            ),
+        }
+
+        let mut reader = Reader::with_capacity(&fastq[..], cap);
+        let mut rset = RecordSet::default();
+        loop {
+            match reader.read_record_set(&mut rset) {
+                Some(Ok(())) => {}
+                Some(Err(Error::InvalidStart { pos, .. })) => {
+                    assert_eq!(pos.last_id.as_deref(), Some("id1"), "cap: {}", cap);
+                    break;
+                }
+                res => panic!("unexpected result: {:?}", res.map(|r| r.is_ok())),
+            }
+        }
+    }
+
+    // error in first record
+    let mut reader = Reader::new(&b"id0\nA\n+\nI\n"[..]);
+    let err = reader.next().unwrap().unwrap_err();
+    assert_matches!(
+        err,
+        Error::InvalidStart {
+            pos: ErrorPosition { last_id: None, .. },
+            ..
+        }
+    );
+
+    // slice reader
+    let mut reader = SliceReader::new(&fastq[..]);
+    reader.next().unwrap().unwrap();
+    reader.next().unwrap().unwrap();
+    let err = reader.next().unwrap().unwrap_err();
+    if let Error::InvalidStart { ref pos, .. } = err {
+        assert_eq!(pos.last_id.as_deref(), Some("id1"));
+    } else {
+        panic!("unexpected error: {}", err);
+    }
+    assert!(err.to_string().contains(", after record 'id1')"));
+}
+
+#[test]
 fn test_fastq_truncated() {
     let mut reader = Reader::new(&b"@id\nATGC\n+"[..]);
-    let rec = reader.next().unwrap();
-    let p = ErrorPosition {
-        line: 3,
-        id: Some("id".to_string()),
-    };
-    assert_matches!(rec, Err(Error::UnexpectedEnd { pos: p }));
+    match reader.next().unwrap() {
+        Err(Error::UnexpectedEnd { pos }) => {
+            assert_eq!(pos_fields(&pos), (3, None, 1, 0, 0, Some("id")));
+        }
+        r => panic!("unexpected result: {:?}", r.map(|r| r.to_owned_record())),
+    }
 }
 
 #[test]
-#[allow(unused_variables)]
 fn test_fastq_unequal() {
     let mut reader = Reader::new(&b"@id\nATGC\n+\n~~"[..]);
-    let rec = reader.next().unwrap();
-    let p = ErrorPosition {
-        line: 1,
-        id: Some("id".to_string()),
-    };
-    assert_matches!(
-        rec,
-        Err(Error::UnequalLengths {
-            seq: 4,
-            qual: 2,
-            pos: p
-        })
-    );
+    match reader.next().unwrap() {
+        Err(Error::UnequalLengths { seq, qual, pos }) => {
+            assert_eq!((seq, qual), (4, 2));
+            assert_eq!(pos_fields(&pos), (1, None, 1, 0, 0, Some("id")));
+        }
+        r => panic!("unexpected result: {:?}", r.map(|r| r.to_owned_record())),
+    }
 }
 
 #[test]
-#[allow(unused_variables)]
 fn test_fastq_no_sep() {
     let mut reader = Reader::new(&b"@id\nATGC\n~~~~\n"[..]);
-    let rec = reader.next().unwrap();
-    let p = ErrorPosition {
-        line: 3,
-        id: Some("id".to_string()),
-    };
-    assert_matches!(
-        rec,
-        Err(Error::InvalidSep {
-            found: b'~',
-            pos: p
-        })
-    );
+    match reader.next().unwrap() {
+        Err(Error::InvalidSep { found, pos }) => {
+            assert_eq!(found, b'~');
+            assert_eq!(pos_fields(&pos), (3, Some(1), 1, 0, 0, Some("id")));
+        }
+        r => panic!("unexpected result: {:?}", r.map(|r| r.to_owned_record())),
+    }
 }
 
 #[test]
@@ -246,6 +310,29 @@ fn test_fastq_read_record_set_limited() {
     }
 }
 
+#[test]
+fn test_fastq_read_record_set_exact_end() {
+    use std::io::Write;
+    let mut fastq = vec![];
+    for i in 0..49 {
+        write!(&mut fastq, "@id{}\nATGC\n+\nJJJJ\n", i).unwrap();
+    }
+    for cap in (3..200).step_by(11) {
+        for n in 1..12 {
+            let mut reader = Reader::with_capacity(&fastq[..], cap);
+            let mut rset = RecordSet::default();
+            let mut ids = vec![];
+            while let Some(res) = reader.read_record_set_exact(&mut rset, Some(n)) {
+                res.unwrap();
+                assert!(rset.len() == n || ids.len() + rset.len() == 49);
+                ids.extend(rset.into_iter().map(|r| r.id().unwrap().to_string()));
+            }
+            let expected: Vec<_> = (0..49).map(|i| format!("id{}", i)).collect();
+            assert_eq!(ids, expected);
+        }
+    }
+}
+
 #[test]
 fn test_fastq_parallel() {
     for cap in 3..400 {
@@ -274,6 +361,257 @@ fn test_fastq_parallel() {
     }
 }
 
+#[cfg(feature = "rayon")]
+#[test]
+fn test_fastq_par_records() {
+    use rayon::iter::ParallelIterator;
+    use std::io::Write;
+    let mut fastq = vec![];
+    for i in 0..200 {
+        write!(&mut fastq, "@id{}\nATGC\n+\nJJJJ\n", i).unwrap();
+    }
+    for cap in (20..400).step_by(37) {
+        let n: usize = Reader::with_capacity(&fastq[..], cap)
+            .par_record_sets()
+            .map(|rset| rset.unwrap().len())
+            .sum();
+        assert_eq!(n, 200);
+
+        let mut records: Vec<_> = Reader::with_capacity(&fastq[..], cap)
+            .par_records()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        records.sort_by_key(|r| r.id().unwrap()[2..].parse::<usize>().unwrap());
+        let expected: Vec<_> = Reader::new(&fastq[..])
+            .into_records()
+            .map(|r| r.unwrap())
+            .collect();
+        assert_eq!(records, expected);
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn test_fastq_par_records_err() {
+    use rayon::iter::ParallelIterator;
+    let fastq = &b"@id1\nATGC\n+\nJJJJ\n@id2\nATGC\n+\nJJJ\n@id3\nATGC\n+\nJJJJ\n"[..];
+    let results: Vec<_> = Reader::with_capacity(fastq, 20).par_records().collect();
+    let errors: Vec<_> = results.iter().filter_map(|r| r.as_ref().err()).collect();
+    assert_eq!(errors.len(), 1);
+    assert_matches!(errors[0], Error::UnequalLengths { .. });
+}
+
+#[test]
+fn test_fastq_read_file_chunked() {
+    use std::io::Write;
+    // quality lines starting with '@' and '+', as well as empty records
+    let qual_chars = b"@+I#";
+    let mut fastq = vec![];
+    for i in 0..300 {
+        let len = i % 13;
+        let qual: Vec<_> = (0..len).map(|j| qual_chars[(i + j) % 4]).collect();
+        write!(
+            &mut fastq,
+            "@id{} desc\n{}\n+\n",
+            i,
+            &"ACGT".repeat(4)[..len]
+        )
+        .unwrap();
+        fastq.extend_from_slice(&qual);
+        fastq.push(b'\n');
+    }
+    let path =
+        std::env::temp_dir().join(format!("seq_io_test_{}_chunked.fastq", std::process::id()));
+    std::fs::File::create(&path)
+        .unwrap()
+        .write_all(&fastq)
+        .unwrap();
+
+    let mut reader = Reader::new(&fastq[..]);
+    let mut expected = vec![];
+    while let Some(rec) = reader.next() {
+        let rec = rec.unwrap().to_owned_record();
+        expected.push((reader.position().clone(), rec));
+    }
+    for n in 1..40 {
+        let records: Vec<_> =
+            seq_io::parallel::read_file_chunked(&path, n, |mut reader: Reader<_>| {
+                let mut out = vec![];
+                while let Some(rec) = reader.next() {
+                    let rec = rec.unwrap().to_owned_record();
+                    out.push((reader.position().clone(), rec));
+                }
+                out
+            })
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect();
+        assert_eq!(records, expected);
+    }
+
+    // error: quality line of record 'id100' too short
+    let i = fastq.windows(6).position(|w| w == b"@id100").unwrap();
+    let i = i + fastq[i..].iter().position(|&b| b == b'+').unwrap() + 2;
+    fastq.remove(i);
+    std::fs::File::create(&path)
+        .unwrap()
+        .write_all(&fastq)
+        .unwrap();
+    let err = Reader::new(&fastq[..])
+        .records()
+        .find_map(|r| r.err())
+        .unwrap();
+    for n in 1..40 {
+        let errors: Vec<_> =
+            seq_io::parallel::read_file_chunked(&path, n, |mut reader: Reader<_>| {
+                while let Some(rec) = reader.next() {
+                    if let Err(e) = rec {
+                        return Some(e);
+                    }
+                }
+                None
+            })
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(format!("{}", errors[0]), format!("{}", err));
+    }
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_fastq_parallel_ordered() {
+    use std::io::Write;
+    use std::{thread, time};
+    let mut fastq = vec![];
+    for i in 0..200 {
+        write!(&mut fastq, "@id{}\nATGC\n+\nJJJJ\n", i).unwrap();
+    }
+    for cap in (20..400).step_by(37) {
+        let par_reader = Reader::with_capacity(&fastq[..], cap);
+        let mut reader = Reader::new(&fastq[..]);
+        seq_io::parallel::parallel_fastq_ordered(
+            par_reader,
+            4,
+            3,
+            |rec, out: &mut usize| {
+                // vary the processing time to mix up the order
+                let i: usize = rec.id().unwrap()[2..].parse().unwrap();
+                thread::sleep(time::Duration::from_micros((i % 7) as u64 * 50));
+                *out = i;
+            },
+            |rec, i| {
+                let r0 = reader.next().unwrap().unwrap();
+                assert_eq!(rec.id(), r0.id());
+                assert_eq!(format!("id{}", i), r0.id().unwrap());
+                None::<()>
+            },
+        )
+        .unwrap();
+        assert!(reader.next().is_none());
+    }
+}
+
+#[test]
+fn test_fastq_read_parallel_ordered_err() {
+    let fastq = b"@id1\nATGC\n+\nJJJJ\n@id2\nATGC\n+\nJJJJ\n@id3\nATGC\n+\nJJ\n";
+    let reader = Reader::with_capacity(&fastq[..], 20);
+    let (n, err) = seq_io::parallel::read_parallel_ordered(
+        reader,
+        2,
+        2,
+        |rset| rset.len(),
+        |rsets| {
+            let mut n_records = 0;
+            while let Some(res) = rsets.next() {
+                match res {
+                    Ok((_, n)) => n_records += n,
+                    Err(e) => return (n_records, Some(e)),
+                }
+            }
+            (n_records, None)
+        },
+    );
+    assert_eq!(n, 2);
+    assert_matches!(err, Some(Error::UnequalLengths { .. }));
+}
+
+#[test]
+fn test_fastq_parallel_write() {
+    use std::io::Write;
+    let mut fastq = vec![];
+    let mut expected = vec![];
+    for i in 0..300 {
+        let rec = format!("@id{}\nATGC\n+\nJJJJ\n", i);
+        fastq.extend_from_slice(rec.as_bytes());
+        if i % 3 != 0 {
+            expected.extend_from_slice(rec.as_bytes());
+        }
+    }
+    for cap in (20..1000).step_by(49) {
+        let reader = Reader::with_capacity(&fastq[..], cap);
+        let out = seq_io::parallel::parallel_fastq_write(reader, 4, 2, vec![], |rec, out| {
+            let i: usize = rec.id().unwrap()[2..].parse().unwrap();
+            if i % 3 != 0 {
+                rec.write(out).unwrap();
+            }
+        })
+        .unwrap();
+        assert_eq!(out, expected);
+
+        // record set version
+        let reader = Reader::with_capacity(&fastq[..], cap);
+        let out = seq_io::parallel::read_parallel_write(reader, 3, 3, vec![], |rset, out| {
+            writeln!(out, "{}", rset.len()).unwrap();
+        })
+        .unwrap();
+        let n: usize = out
+            .split(|&b| b == b'\n')
+            .filter(|l| !l.is_empty())
+            .map(|l| std::str::from_utf8(l).unwrap().parse::<usize>().unwrap())
+            .sum();
+        assert_eq!(n, 300);
+    }
+}
+
+#[test]
+fn test_fastq_parallel_write_err() {
+    #[derive(Debug)]
+    struct FailingWriter(usize);
+    impl io::Write for FailingWriter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.0 == 0 {
+                return Err(io::Error::new(io::ErrorKind::Other, "write failed"));
+            }
+            self.0 -= 1;
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+    let mut fastq = vec![];
+    for i in 0..300 {
+        fastq.extend_from_slice(format!("@id{}\nATGC\n+\nJJJJ\n", i).as_bytes());
+    }
+    let reader = Reader::with_capacity(&fastq[..], 100);
+    let res = seq_io::parallel::parallel_fastq_write(reader, 2, 2, FailingWriter(3), |rec, out| {
+        rec.write(out).unwrap();
+    });
+    assert_matches!(res, Err(Error::Io(_)));
+
+    // parse error
+    fastq.extend_from_slice(b"@id\nATGC\n+\nJJ\n");
+    let reader = Reader::with_capacity(&fastq[..], 100);
+    let res = seq_io::parallel::parallel_fastq_write(reader, 2, 2, vec![], |rec, out| {
+        rec.write(out).unwrap();
+    });
+    assert_matches!(res, Err(Error::UnequalLengths { .. }));
+}
+
 #[test]
 fn test_fastq_seek() {
     for cap in 3..31 {
@@ -330,6 +668,7 @@ fn test_fastq_seek_err() {
                 pos: ErrorPosition {
                     line: 7,
                     id: Some(_),
+                    ..
                 },
             })
         );
@@ -348,6 +687,7 @@ fn test_fastq_seek_err() {
                 pos: ErrorPosition {
                     line: 7,
                     id: Some(_),
+                    ..
                 },
             })
         );
@@ -527,3 +867,1031 @@ fn test_fastq_mixed_read_seek() {
         assert_eq!(&out, &fastq);
     }
 }
+
+// Multi-line FASTQ
+
+const FASTQ_MULTI: &[u8] = b"@id desc
+AT
+GC
++
+~~
+~~
+@id2
+ATGC
++
+@@@@
+@id3
+A
+TG
+C
++
++~~
+~
+";
+
+#[test]
+fn test_fastq_multiline() {
+    let expected = [
+        (
+            &b"id desc"[..],
+            vec![&b"AT"[..], b"GC"],
+            vec![&b"~~"[..], b"~~"],
+        ),
+        (&b"id2"[..], vec![&b"ATGC"[..]], vec![&b"@@@@"[..]]),
+        (
+            &b"id3"[..],
+            vec![&b"A"[..], b"TG", b"C"],
+            vec![&b"+~~"[..], b"~"],
+        ),
+    ];
+    let crlf: Vec<u8> = FASTQ_MULTI
+        .split(|b| *b == b'\n')
+        .collect::<Vec<_>>()
+        .join(&b"\r\n"[..]);
+
+    for input in &[FASTQ_MULTI, &crlf[..]] {
+        for cap in 3..100 {
+            let mut reader = Reader::with_capacity(*input, cap).set_multiline(true);
+            for (head, seq_lines, qual_lines) in &expected {
+                let record = reader
+                    .next()
+                    .unwrap()
+                    .unwrap_or_else(|e| panic!("Error reading record at cap. {}: {}", cap, e));
+                assert_eq!(record.head(), *head, "head mismatch at cap. {}", cap);
+                assert_eq!(&record.seq_lines().collect::<Vec<_>>(), seq_lines);
+                assert_eq!(&record.qual_lines().collect::<Vec<_>>(), qual_lines);
+                let owned = record.to_owned_record();
+                assert_eq!(owned.seq(), &seq_lines.concat()[..]);
+                assert_eq!(owned.qual(), &qual_lines.concat()[..]);
+                assert_eq!(&record.full_seq()[..], owned.seq());
+                assert_eq!(&record.full_qual()[..], owned.qual());
+            }
+            assert!(reader.next().is_none());
+        }
+    }
+}
+
+#[test]
+fn test_fastq_multiline_single() {
+    // single-line FASTQ is parsed the same way in multi-line mode
+    for cap in 3..100 {
+        let mut reader = Reader::with_capacity(FASTQ, cap).set_multiline(true);
+        let mut out = vec![];
+        while let Some(r) = reader.next() {
+            r.unwrap().write(&mut out).unwrap();
+        }
+        assert_eq!(&out, &FASTQ);
+    }
+}
+
+#[test]
+fn test_fastq_multiline_write() {
+    let mut out = vec![];
+    let mut reader = Reader::new(FASTQ_MULTI).set_multiline(true);
+    while let Some(r) = reader.next() {
+        r.unwrap().write(&mut out).unwrap();
+    }
+    assert_eq!(
+        &out[..],
+        &b"@id desc\nATGC\n+\n~~~~\n@id2\nATGC\n+\n@@@@\n@id3\nATGC\n+\n+~~~\n"[..]
+    );
+}
+
+#[test]
+fn test_fastq_writer() {
+    use seq_io::LineEnding;
+    let mut reader = Reader::new(FASTQ_MULTI).set_multiline(true);
+    let mut writer = Writer::new(vec![])
+        .set_line_width(Some(3))
+        .set_line_ending(LineEnding::CrLf)
+        .set_repeat_id(true);
+    while let Some(r) = reader.next() {
+        writer.write_record(&r.unwrap()).unwrap();
+    }
+    assert_eq!(writer.num_records(), 3);
+    assert_eq!(writer.num_bases(), 12);
+    let out = writer.into_inner().unwrap();
+    assert_eq!(
+        &out[..],
+        &b"@id desc\r\nATG\r\nC\r\n+id desc\r\n~~~\r\n~\r\n\
+           @id2\r\nATG\r\nC\r\n+id2\r\n@@@\r\n@\r\n\
+           @id3\r\nATG\r\nC\r\n+id3\r\n+~~\r\n~\r\n"[..]
+    );
+
+    // read back
+    let records: Vec<_> = Reader::new(&out[..])
+        .set_multiline(true)
+        .into_records()
+        .map(|r| r.unwrap())
+        .collect();
+    let mut writer = Writer::new(vec![]).set_write_desc(false);
+    for rec in &records {
+        writer.write_record(rec).unwrap();
+    }
+    writer
+        .write_parts(b"id4", Some(b"desc"), b"A", b"I")
+        .unwrap();
+    assert_eq!(
+        &writer.into_inner().unwrap()[..],
+        &b"@id\nATGC\n+\n~~~~\n@id2\nATGC\n+\n@@@@\n@id3\nATGC\n+\n+~~~\n@id4\nA\n+\nI\n"[..]
+    );
+}
+
+#[test]
+fn test_fastq_writer_header_config() {
+    use seq_io::header::{Delimiter, HeaderConfig};
+    let config = HeaderConfig::new().set_delimiter(Delimiter::Tab);
+    let input = b"@id x\tdesc\nACGT\n+\nIIII\n";
+    let mut reader = Reader::new(&input[..]).set_header_config(config);
+    let mut writer = Writer::new(vec![])
+        .set_header_config(config)
+        .set_repeat_id(true);
+    writer
+        .write_record(&reader.next().unwrap().unwrap())
+        .unwrap();
+    assert_eq!(
+        &writer.into_inner().unwrap()[..],
+        &b"@id x\tdesc\nACGT\n+id x\tdesc\nIIII\n"[..]
+    );
+    let mut writer = Writer::new(vec![])
+        .set_header_config(config)
+        .set_write_desc(false)
+        .set_repeat_id(true);
+    writer.write(b"id x\tdesc", b"ACGT", b"IIII").unwrap();
+    assert_eq!(
+        &writer.into_inner().unwrap()[..],
+        &b"@id x\nACGT\n+id x\nIIII\n"[..]
+    );
+}
+
+#[test]
+fn test_fastq_multiline_empty() {
+    let mut reader = Reader::new(&b"@id\n+\n\n@id2\n\n+\n"[..]).set_multiline(true);
+    let rec = reader.next().unwrap().unwrap();
+    assert_eq!(rec.id(), Ok("id"));
+    assert_eq!(rec.seq(), b"");
+    assert_eq!(rec.qual(), b"");
+    let rec = reader.next().unwrap().unwrap();
+    assert_eq!(rec.id(), Ok("id2"));
+    assert_eq!(rec.seq(), b"");
+    assert_eq!(rec.qual(), b"");
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn test_fastq_multiline_unequal() {
+    let mut reader = Reader::new(&b"@id\nAT\nGC\n+\n~~~\n~~\n"[..]).set_multiline(true);
+    match reader.next().unwrap() {
+        Err(Error::UnequalLengths { seq, qual, pos }) => {
+            assert_eq!((seq, qual), (4, 5));
+            assert_eq!(pos_fields(&pos), (1, None, 1, 0, 0, Some("id")));
+        }
+        r => panic!("unexpected result: {:?}", r.map(|r| r.to_owned_record())),
+    }
+    assert!(reader.next().is_none());
+}
+
+#[test]
+fn test_fastq_multiline_truncated() {
+    for cap in 3..30 {
+        let mut reader =
+            Reader::with_capacity(&b"@id\nAT\nGC\n+\n~~\n"[..], cap).set_multiline(true);
+        match reader.next().unwrap() {
+            Err(Error::UnexpectedEnd { pos }) => {
+                assert_eq!(pos_fields(&pos), (6, None, 1, 0, 0, Some("id")));
+            }
+            r => panic!("unexpected result: {:?}", r.map(|r| r.to_owned_record())),
+        }
+    }
+}
+
+#[test]
+fn test_fastq_multiline_invalid_start() {
+    for cap in 3..30 {
+        let mut reader =
+            Reader::with_capacity(&b"@id\nA\n+\n~\n\nid\nA\n+\n~\n"[..], cap).set_multiline(true);
+        reader.next().unwrap().unwrap();
+        assert_matches!(
+            reader.next().unwrap(),
+            Err(Error::InvalidStart {
+                found: b'\n',
+                pos: ErrorPosition {
+                    line: 5,
+                    id: None,
+                    ..
+                }
+            })
+        );
+    }
+}
+
+#[test]
+fn test_fastq_multiline_recset() {
+    for cap in 3..200 {
+        let mut reader = Reader::with_capacity(FASTQ_MULTI, cap).set_multiline(true);
+        let mut rsets = vec![];
+        loop {
+            rsets.push(RecordSet::default());
+            let rset = rsets.last_mut().unwrap();
+            if let Some(r) = reader.read_record_set(rset) {
+                r.unwrap();
+            } else {
+                break;
+            }
+        }
+        let mut rset_iter = rsets.iter().flat_map(|r| r.into_iter());
+
+        let mut reader = Reader::new(FASTQ_MULTI).set_multiline(true);
+        while let Some(r0) = reader.next() {
+            let rec = rset_iter.next().unwrap();
+            let r0 = r0.unwrap();
+            assert_eq!(rec.head(), r0.head());
+            assert_eq!(rec.seq(), r0.seq());
+            assert_eq!(rec.qual(), r0.qual());
+        }
+        assert!(rset_iter.next().is_none());
+    }
+}
+
+#[test]
+fn test_fastq_multiline_seek() {
+    for cap in 3..60 {
+        let mut reader =
+            Reader::with_capacity(io::Cursor::new(FASTQ_MULTI), cap).set_multiline(true);
+        reader.next().unwrap().unwrap();
+        let pos1 = reader.position().clone();
+        reader.next().unwrap().unwrap();
+        let rec3 = reader.next().unwrap().unwrap().to_owned_record();
+        let pos3 = reader.position().clone();
+        assert!(reader.next().is_none());
+
+        assert_eq!(pos1.line(), 1);
+        assert_eq!(pos1.byte(), 0);
+        assert_eq!(pos3.line(), 11);
+        assert_eq!(pos3.byte(), 40);
+
+        reader.seek(&pos3).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap().to_owned_record(), rec3);
+        assert!(reader.next().is_none());
+    }
+}
+
+// FASTQ index
+
+#[test]
+fn test_fastq_index() {
+    use seq_io::fastq::index::{Index, IndexedReader};
+    use std::io::Write;
+
+    let mut fastq = vec![];
+    for i in 0..100 {
+        write!(&mut fastq, "@id{} desc\nATGC\n+\nIIII\n", 99 - i).unwrap();
+    }
+
+    for cap in (5..400).step_by(7) {
+        let index = Index::build(&mut Reader::with_capacity(&fastq[..], cap)).unwrap();
+        assert_eq!(index.len(), 100);
+        assert_eq!(index.id(3), Some(&b"id96"[..]));
+        assert_eq!(index.position(3).unwrap().line(), 13);
+        assert_eq!(index.position(3).unwrap().record(), 3);
+        assert_eq!(index.find_id(b"id96"), Some(3));
+        assert_eq!(index.find_id(b"id100"), None);
+
+        let mut out = vec![];
+        index.write(&mut out).unwrap();
+        let read = Index::read(&out[..]).unwrap();
+        assert_eq!(read, index);
+        let index = read;
+        // the ID table is stored in the file
+        assert_eq!(index.find_id(b"id96"), Some(3));
+        assert_eq!(index.find_id(b"id0"), Some(99));
+        assert_eq!(index.find_id(b"id100"), None);
+
+        let reader = Reader::with_capacity(io::Cursor::new(&fastq[..]), cap);
+        let mut reader = IndexedReader::new(reader, index);
+        for n in (0..100).rev().step_by(3) {
+            let id = format!("id{}", 99 - n);
+            assert_eq!(reader.get_nth(n).unwrap().unwrap().id(), Ok(id.as_str()));
+            assert_eq!(
+                reader.get_by_id(id.as_bytes()).unwrap().unwrap().id(),
+                Ok(id.as_str())
+            );
+        }
+        assert!(reader.get_nth(100).is_none());
+        assert!(reader.get_by_id(b"id").is_none());
+
+        let mut range = reader.range(95..105).unwrap();
+        assert_eq!(range.remaining(), 5);
+        let mut ids = vec![];
+        while let Some(rec) = range.next() {
+            ids.push(rec.unwrap().id().unwrap().to_string());
+        }
+        assert_eq!(ids, vec!["id4", "id3", "id2", "id1", "id0"]);
+        assert!(reader.range(100..200).unwrap().next().is_none());
+    }
+}
+
+#[test]
+fn test_fastq_index_multiline() {
+    use seq_io::fastq::index::{Index, IndexedReader};
+    let index = Index::build(&mut Reader::new(FASTQ_MULTI).set_multiline(true)).unwrap();
+    let reader = Reader::new(io::Cursor::new(FASTQ_MULTI)).set_multiline(true);
+    let mut reader = IndexedReader::new(reader, index);
+    let n = reader.index().len();
+    let mut expected = Reader::new(FASTQ_MULTI).set_multiline(true);
+    for i in 0..n {
+        let exp = expected.next().unwrap().unwrap().to_owned_record();
+        let rec = reader.get_nth(i).unwrap().unwrap().to_owned_record();
+        assert_eq!(rec, exp);
+    }
+}
+
+#[test]
+fn test_fastq_index_invalid() {
+    use seq_io::fastq::index::{Error, Index};
+    // old format version
+    assert_matches!(Index::read(&b"SQIOFQI\x01"[..]), Err(Error::InvalidIndex));
+    assert_matches!(Index::read(&b"SQIOFQI\x02\x01"[..]), Err(Error::Io(_)));
+
+    // corrupt headers
+    let mut index = vec![];
+    Index::build(&mut Reader::new(FASTQ))
+        .unwrap()
+        .write(&mut index)
+        .unwrap();
+    assert!(Index::read(&index[..]).is_ok());
+    // huge number of records
+    let mut corrupt = index.clone();
+    corrupt[8..16].copy_from_slice(&u64::MAX.to_le_bytes());
+    assert_matches!(Index::read(&corrupt[..]), Err(Error::Io(_)));
+    // record number out of range in the ID table
+    let mut corrupt = index.clone();
+    corrupt[48..56].copy_from_slice(&2u64.to_le_bytes());
+    assert_matches!(Index::read(&corrupt[..]), Err(Error::InvalidIndex));
+    // huge ID length
+    let mut corrupt = index.clone();
+    let l = corrupt.len();
+    corrupt[l - 13..l - 5].copy_from_slice(&(u64::MAX / 2).to_le_bytes());
+    assert_matches!(Index::read(&corrupt[..]), Err(Error::InvalidIndex));
+    // truncated IDs
+    assert_matches!(Index::read(&index[..l - 1]), Err(Error::InvalidIndex));
+    assert_matches!(
+        Index::build(&mut Reader::new(&b"@id\nATGC\n+\nII\n"[..])),
+        Err(Error::Fastq(seq_io::fastq::Error::UnequalLengths { .. }))
+    );
+}
+
+const FASTQ_MALFORMED: &[u8] = b"@id1
+ACGT
++
+IIII
+@id2
+ACGT
++
+III
+junk
+@id3
+ACGT
++
+IIII
+@id4
+ACGT
+-
+IIII
+@id5
+ACGT
++
+IIII
+@id6
+AC
+";
+
+fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+#[test]
+fn test_fastq_recovery() {
+    use std::sync::{Arc, Mutex};
+
+    for cap in 3..100 {
+        let skipped = Arc::new(Mutex::new(vec![]));
+        let s = skipped.clone();
+        let mut reader = Reader::with_capacity(FASTQ_MALFORMED, cap).set_recovery(move |sk| {
+            s.lock().unwrap().push(sk);
+        });
+        assert_send_sync(&reader);
+        let mut ids = vec![];
+        while let Some(rec) = reader.next() {
+            ids.push(rec.unwrap().id().unwrap().to_string());
+        }
+        assert_eq!(ids, ["id1", "id3", "id5"]);
+
+        let skipped = skipped.lock().unwrap();
+        assert_eq!(skipped.len(), 3);
+        assert_matches!(
+            skipped[0].error,
+            Error::UnequalLengths {
+                seq: 4,
+                qual: 3,
+                ..
+            }
+        );
+        assert_eq!(
+            (skipped[0].line, skipped[0].start, skipped[0].end),
+            (5, 17, 38)
+        );
+        assert_matches!(skipped[1].error, Error::InvalidSep { found: b'-', .. });
+        assert_eq!(
+            (skipped[1].line, skipped[1].start, skipped[1].end),
+            (14, 55, 72)
+        );
+        assert_matches!(skipped[2].error, Error::UnexpectedEnd { .. });
+        assert_eq!(
+            (skipped[2].line, skipped[2].start, skipped[2].end),
+            (22, 89, 97)
+        );
+
+        let stats = reader.recovery_stats();
+        assert_eq!(stats.errors, 3);
+        assert_eq!(stats.skipped_bytes, 21 + 17 + 8);
+    }
+}
+
+#[test]
+fn test_fastq_recovery_recset() {
+    for cap in 3..100 {
+        let mut reader = Reader::with_capacity(FASTQ_MALFORMED, cap).set_recovery(|_| ());
+        let mut rset = RecordSet::default();
+        let mut ids = vec![];
+        while let Some(res) = reader.read_record_set(&mut rset) {
+            res.unwrap();
+            ids.extend(rset.into_iter().map(|r| r.id().unwrap().to_string()));
+        }
+        assert_eq!(ids, ["id1", "id3", "id5"]);
+        assert_eq!(reader.recovery_stats().errors, 3);
+    }
+}
+
+#[test]
+fn test_fastq_recovery_multiline() {
+    let fastq = b"@id1\nAC\nGT\n+\nIIII\n@id2\nACGT\n+\nIII\n@id3\nAC\n+\nII\n";
+    let mut reader = Reader::new(&fastq[..])
+        .set_multiline(true)
+        .set_recovery(|_| ());
+    let mut ids = vec![];
+    while let Some(rec) = reader.next() {
+        ids.push(rec.unwrap().id().unwrap().to_string());
+    }
+    assert_eq!(ids, ["id1", "id3"]);
+    assert_eq!(reader.recovery_stats().errors, 1);
+}
+
+#[test]
+fn test_fastq_recovery_disabled() {
+    let mut reader = Reader::new(FASTQ_MALFORMED);
+    assert!(reader.next().unwrap().is_ok());
+    assert_matches!(reader.next().unwrap(), Err(Error::UnequalLengths { .. }));
+    assert!(reader.next().is_none());
+    assert_eq!(reader.recovery_stats(), Default::default());
+}
+
+#[test]
+fn test_fastq_error_position() {
+    let fastq = b"@id1\nACGT\n+\nIIII\n@id2\nAC\n+\nII\n@id3\nACGT\nIIII\n";
+    let expected: PosFields = (11, Some(1), 9, 30, 2, Some("id3"));
+    for cap in 3..60 {
+        let mut reader = Reader::with_capacity(io::Cursor::new(&fastq[..]), cap);
+        let err = reader.records().find_map(|r| r.err()).unwrap();
+        assert_matches!(err, Error::InvalidSep { found: b'I', .. });
+        if let Error::InvalidSep { ref pos, .. } = err {
+            assert_eq!(pos_fields(pos), expected);
+        }
+
+        let mut reader = Reader::with_capacity(io::Cursor::new(&fastq[..]), cap);
+        let mut rset = RecordSet::default();
+        let err = loop {
+            if let Err(e) = reader.read_record_set(&mut rset).unwrap() {
+                break e;
+            }
+        };
+        let pos = match err {
+            Error::InvalidSep { pos, .. } => pos,
+            _ => panic!("unexpected error: {}", err),
+        };
+        assert_eq!(pos_fields(&pos), expected);
+
+        // seek back to the invalid record
+        reader.seek(&pos.to_position()).unwrap();
+        assert_eq!(reader.position(), &Position::with_record(9, 30, 2));
+        assert_matches!(
+            reader.next().unwrap(),
+            Err(Error::InvalidSep {
+                pos: ErrorPosition {
+                    line: 11,
+                    record: 2,
+                    ..
+                },
+                ..
+            })
+        );
+
+        // resume from the second record: the record index is restored by seek()
+        let mut reader = Reader::with_capacity(io::Cursor::new(&fastq[..]), cap);
+        reader.next().unwrap().unwrap();
+        reader.next().unwrap().unwrap();
+        let pos = reader.position().clone();
+        assert_eq!(pos.record(), 1);
+        let mut reader = Reader::with_capacity(io::Cursor::new(&fastq[..]), cap);
+        reader.seek(&pos).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap().id(), Ok("id2"));
+        let err = reader.next().unwrap().unwrap_err();
+        if let Error::InvalidSep { ref pos, .. } = err {
+            assert_eq!(pos_fields(pos), expected);
+        } else {
+            panic!("unexpected error: {}", err);
+        }
+    }
+}
+
+#[test]
+fn test_fastq_validation() {
+    use seq_io::validation::{Alphabet, Validation, PHRED33, PHRED64};
+
+    let fastq =
+        b"@id1\nACGT\n+\nIIII\n@id2\nACGU\n+id2\nIIII\n@id3\nACGT\n+\nII I\n@id4\nACGT\n+x\nIIII\n";
+
+    let errors = |validation: Validation, multiline: bool| -> Vec<Error> {
+        let mut out = vec![];
+        for cap in 3..80 {
+            let mut reader = Reader::with_capacity(&fastq[..], cap)
+                .set_multiline(multiline)
+                .set_validation(validation.clone());
+            let err = reader.records().find_map(|r| r.err());
+            let mut reader = Reader::with_capacity(&fastq[..], cap)
+                .set_multiline(multiline)
+                .set_validation(validation.clone());
+            let mut rset = RecordSet::default();
+            let rset_err = loop {
+                match reader.read_record_set(&mut rset) {
+                    Some(Ok(())) => {}
+                    Some(Err(e)) => break Some(e),
+                    None => break None,
+                }
+            };
+            assert_eq!(format!("{:?}", err), format!("{:?}", rset_err));
+            out.extend(err);
+        }
+        out
+    };
+
+    // nothing checked
+    assert!(errors(Validation::new(), false).is_empty());
+
+    for multiline in [false, true] {
+        for err in errors(Validation::new().set_alphabet(Alphabet::Dna), multiline) {
+            assert_matches!(
+                err,
+                Error::InvalidSeq {
+                    found: b'U',
+                    pos: ErrorPosition {
+                        line: 6,
+                        column: Some(4),
+                        record: 1,
+                        ..
+                    }
+                }
+            );
+        }
+        for err in errors(Validation::new().set_qual_range(PHRED33), multiline) {
+            assert_matches!(
+                err,
+                Error::InvalidQual {
+                    found: b' ',
+                    pos: ErrorPosition {
+                        line: 12,
+                        column: Some(3),
+                        record: 2,
+                        ..
+                    }
+                }
+            );
+        }
+        for err in errors(Validation::new().set_check_sep_id(true), multiline) {
+            assert_matches!(
+                err,
+                Error::SepIdMismatch {
+                    pos: ErrorPosition {
+                        line: 15,
+                        column: None,
+                        record: 3,
+                        ..
+                    }
+                }
+            );
+        }
+    }
+
+    assert!(errors(Validation::new().set_alphabet(Alphabet::Iupac), false).is_empty());
+
+    // Phred+33 qualities are invalid with Phred+64
+    let mut reader = Reader::new(&b"@id\nAC\n+\nh5\n"[..])
+        .set_validation(Validation::new().set_qual_range(PHRED64));
+    assert_matches!(
+        reader.next().unwrap(),
+        Err(Error::InvalidQual { found: b'5', .. })
+    );
+
+    // validation errors can be recovered from
+    let validation = Validation::new()
+        .set_alphabet(Alphabet::Dna)
+        .set_qual_range(PHRED33)
+        .set_check_sep_id(true);
+    let mut reader = Reader::new(&fastq[..])
+        .set_validation(validation)
+        .set_recovery(|_| ());
+    let ids: Vec<_> = reader
+        .records()
+        .map(|r| r.unwrap().id().unwrap().to_string())
+        .collect();
+    assert_eq!(ids, ["id1"]);
+    assert_eq!(reader.recovery_stats().errors, 3);
+}
+
+#[test]
+fn test_fastq_quality() {
+    use seq_io::fastq::quality::*;
+
+    // scores and error probabilities
+    let scores: Vec<_> = Encoding::Phred33.scores(b"!+\n5I").collect();
+    assert_eq!(scores, [0, 10, 20, 40]);
+    let scores: Vec<_> = Encoding::Phred64.scores(b"@J\r\nTh").collect();
+    assert_eq!(scores, [0, 10, 20, 40]);
+    let scores: Vec<_> = Encoding::Solexa.scores(b";@J").collect();
+    assert_eq!(scores, [1, 3, 10]);
+    let probs: Vec<_> = Encoding::Phred33.error_probs(b"+5").collect();
+    assert!((probs[0] - 0.1).abs() < 1e-12);
+    assert!((probs[1] - 0.01).abs() < 1e-12);
+    let p = Encoding::Solexa.error_probs(b"J").next().unwrap();
+    assert!((p - 0.1 / 1.1).abs() < 1e-12);
+
+    // conversion
+    let conv = Converter::new(Encoding::Phred33, Encoding::Phred64);
+    let mut qual = b"!5\nI~".to_vec();
+    conv.convert(&mut qual);
+    assert_eq!(&qual, b"@T\nh~");
+    let conv = Converter::new(Encoding::Phred33, Encoding::Solexa);
+    assert_eq!(conv.convert_byte(b'!'), b';');
+    assert_eq!(conv.convert_byte(b'"'), b';');
+    assert_eq!(conv.convert_byte(b'+'), b'J');
+    assert_eq!(conv.convert_byte(b'I'), b'h');
+    let conv = Converter::new(Encoding::Solexa, Encoding::Phred33);
+    assert_eq!(conv.convert_byte(b';'), b'"');
+    assert_eq!(conv.convert_byte(b'@'), b'$');
+    assert_eq!(conv.convert_byte(b'J'), b'+');
+    let conv = Converter::new(Encoding::Phred64, Encoding::Phred64);
+    assert_eq!(conv.convert_byte(b'!'), b'!');
+
+    let mut rec = OwnedRecord {
+        head: b"id".to_vec(),
+        seq: b"ACGT".to_vec(),
+        qual: b"@JTh".to_vec(),
+    };
+    rec.convert_qual(Encoding::Phred64, Encoding::Phred33);
+    assert_eq!(&rec.qual, b"!+5I");
+
+    // conversion while writing multi-line FASTQ
+    let mut writer = Writer::new(vec![])
+        .set_line_width(Some(2))
+        .set_qual_conversion(Encoding::Phred33, Encoding::Phred64);
+    writer.write(b"id", b"AC\nG", b"!+\n5").unwrap();
+    assert_eq!(
+        writer.into_inner().unwrap(),
+        b"@id\nAC\nG\n+\n@J\nT\n".to_vec()
+    );
+
+    // guessing
+    let guess = |qual: &[u8]| {
+        let mut g = Guesser::new();
+        g.add(qual);
+        g.guess()
+    };
+    assert_eq!(guess(b"5II"), Some(Encoding::Phred33));
+    assert_eq!(guess(b";h"), Some(Encoding::Solexa));
+    assert_eq!(guess(b"@h"), Some(Encoding::Phred64));
+    assert_eq!(guess(b"@J"), Some(Encoding::Phred33));
+    assert_eq!(guess(b"?I"), Some(Encoding::Phred33));
+    assert_eq!(guess(b";5I"), Some(Encoding::Phred33));
+    assert_eq!(guess(b"IIIIII?<>IIII"), Some(Encoding::Phred33));
+    assert_eq!(guess(b";K"), Some(Encoding::Solexa));
+    assert_eq!(guess(b"\n"), None);
+    assert_eq!(guess(b" I"), None);
+
+    let fastq = b"@id1\nAC\n+\nhh\n@id2\nAC\n+\n@h\n@id3\nAC\n+\n5I\n";
+    let mut reader = Reader::new(&fastq[..]);
+    assert_eq!(
+        guess_encoding(&mut reader, 2).unwrap(),
+        Some(Encoding::Phred64)
+    );
+    assert_eq!(reader.next().unwrap().unwrap().id(), Ok("id3"));
+    let mut reader = Reader::new(&fastq[..]);
+    assert_eq!(
+        guess_encoding(&mut reader, 10).unwrap(),
+        Some(Encoding::Phred33)
+    );
+}
+
+#[test]
+fn test_fastq_quality_stats() {
+    use seq_io::fastq::quality::Encoding;
+
+    let fastq = b"@id1\nACGT\n+\n!+5I\n@id2\nACGT\n+\n5555\n@id3\n\n+\n\n";
+    let mut reader = Reader::new(&fastq[..]);
+
+    let rec = reader.next().unwrap().unwrap();
+    assert_eq!(rec.mean_qual(Encoding::Phred33), Some(17.5));
+    assert_eq!(rec.min_qual(Encoding::Phred33), Some(0));
+    assert_eq!(rec.frac_qual_ge(Encoding::Phred33, 20), Some(0.5));
+    assert_eq!(rec.frac_qual_ge(Encoding::Phred33, 30), Some(0.25));
+    let ee = rec.expected_errors(Encoding::Phred33);
+    assert!((ee - (1. + 0.1 + 0.01 + 0.0001)).abs() < 1e-12);
+    let owned = rec.to_owned_record();
+    assert_eq!(owned.mean_qual(Encoding::Phred33), Some(17.5));
+    assert_eq!(owned.expected_errors(Encoding::Phred33), ee);
+
+    let rec = reader.next().unwrap().unwrap();
+    assert_eq!(rec.mean_qual(Encoding::Phred33), Some(20.));
+    assert_eq!(rec.min_qual(Encoding::Phred33), Some(20));
+    assert_eq!(rec.frac_qual_ge(Encoding::Phred33, 30), Some(0.));
+    assert!((rec.expected_errors(Encoding::Phred33) - 0.04).abs() < 1e-12);
+    // interpreted as Solexa
+    assert_eq!(rec.min_qual(Encoding::Solexa), Some(1));
+
+    let rec = reader.next().unwrap().unwrap();
+    assert_eq!(rec.mean_qual(Encoding::Phred33), None);
+    assert_eq!(rec.min_qual(Encoding::Phred33), None);
+    assert_eq!(rec.frac_qual_ge(Encoding::Phred33, 30), None);
+    assert_eq!(rec.expected_errors(Encoding::Phred33), 0.);
+
+    // records in a record set, multi-line qualities
+    let fastq = b"@id1\nAC\nGT\n+\n@J\nTh\n";
+    let mut reader = Reader::new(&fastq[..]).set_multiline(true);
+    let mut rset = RecordSet::default();
+    reader.read_record_set(&mut rset).unwrap().unwrap();
+    let rec = rset.into_iter().next().unwrap();
+    assert_eq!(rec.mean_qual(Encoding::Phred64), Some(17.5));
+    assert_eq!(rec.frac_qual_ge(Encoding::Phred64, 20), Some(0.5));
+}
+
+#[test]
+fn test_fastq_header_config() {
+    use seq_io::header::{Delimiter, HeaderConfig};
+
+    let fastq = b"@id1/1\tdesc 1\nA\n+\nI\n@gi|2|x/2 desc\nA\n+\nI\n@id3\nA\n+\nI\n";
+
+    let ids = |config: HeaderConfig| {
+        let mut reader = Reader::new(&fastq[..]).set_header_config(config);
+        let mut out = vec![];
+        while let Some(rec) = reader.next() {
+            let rec = rec.unwrap();
+            let (id, desc) = rec.id_desc().unwrap();
+            assert_eq!(rec.id(), Ok(id));
+            assert_eq!(rec.desc().map(|d| d.unwrap()), desc);
+            assert_eq!(rec.id_desc_bytes().0, rec.id_bytes());
+            out.push((id.to_string(), desc.map(|d| d.to_string())));
+        }
+        // records from a record set
+        let mut reader = Reader::new(&fastq[..]).set_header_config(config);
+        let mut rset = RecordSet::default();
+        reader.read_record_set(&mut rset).unwrap().unwrap();
+        let from_rset: Vec<_> = rset
+            .into_iter()
+            .map(|r| {
+                let (id, desc) = r.id_desc().unwrap();
+                (id.to_string(), desc.map(|d| d.to_string()))
+            })
+            .collect();
+        assert_eq!(out, from_rset);
+        out
+    };
+    let s = |s: &str| s.to_string();
+
+    assert_eq!(
+        ids(HeaderConfig::new()),
+        [
+            (s("id1/1\tdesc"), Some(s("1"))),
+            (s("gi|2|x/2"), Some(s("desc"))),
+            (s("id3"), None),
+        ]
+    );
+    assert_eq!(
+        ids(HeaderConfig::new().set_delimiter(Delimiter::Tab)),
+        [
+            (s("id1/1"), Some(s("desc 1"))),
+            (s("gi|2|x/2 desc"), None),
+            (s("id3"), None),
+        ]
+    );
+    let config = HeaderConfig::new()
+        .set_delimiter(Delimiter::Whitespace)
+        .set_strip_read_number(true);
+    assert_eq!(
+        ids(config),
+        [
+            (s("id1"), Some(s("desc 1"))),
+            (s("gi|2|x"), Some(s("desc"))),
+            (s("id3"), None),
+        ]
+    );
+    assert_eq!(
+        ids(config.set_truncate_pipe(true)),
+        [
+            (s("id1"), Some(s("desc 1"))),
+            (s("gi"), Some(s("desc"))),
+            (s("id3"), None),
+        ]
+    );
+
+    // interleaved pairs
+    let fastq = b"@p/1\nA\n+\nI\n@p/2\nA\n+\nI\n";
+    let config = HeaderConfig::new().set_strip_read_number(true);
+    let mut reader = InterleavedReader::new(Reader::new(&fastq[..]).set_header_config(config));
+    let (r1, r2) = reader.next().unwrap().unwrap();
+    assert_eq!((r1.id(), r2.id()), (Ok("p"), Ok("p")));
+}
+
+#[test]
+fn test_fastq_header_config_errors() {
+    use seq_io::header::{Delimiter, HeaderConfig};
+    use seq_io::validation::Validation;
+
+    let config = HeaderConfig::new()
+        .set_delimiter(Delimiter::Tab)
+        .set_strip_read_number(true);
+    let validation = Validation::new().set_check_sep_id(true);
+
+    // the separator may repeat the ID according to the configuration
+    for fastq in &[
+        &b"@id/1\tdesc x\nA\n+id/1\nI\n"[..],
+        &b"@id/1\tdesc x\nA\n+id\nI\n"[..],
+        &b"@id/1\tdesc x\nA\n+id/1\tdesc x\nI\n"[..],
+    ] {
+        let mut reader = Reader::new(*fastq)
+            .set_header_config(config)
+            .set_validation(validation.clone());
+        assert_eq!(reader.next().unwrap().unwrap().id(), Ok("id"));
+    }
+    let mut reader = Reader::new(&b"@id/1\tdesc x\nA\n+id/1 desc\nI\n"[..])
+        .set_header_config(config)
+        .set_validation(validation);
+    assert_matches!(reader.next().unwrap(), Err(Error::SepIdMismatch { .. }));
+
+    // error positions contain the configured ID
+    let fastq = b"@id/1\tdesc x\nAC\n+\nI\n";
+    let mut reader = Reader::new(&fastq[..]).set_header_config(config);
+    match reader.next().unwrap() {
+        Err(Error::UnequalLengths { pos, .. }) => assert_eq!(pos.id.as_deref(), Some("id")),
+        r => panic!("unexpected result: {:?}", r.map(|r| r.to_owned_record())),
+    }
+}
+
+// Records and errors of `SliceReader` and `Reader` in the same format
+fn slice_reader_results(fastq: &[u8], multiline: bool) -> (Vec<String>, Vec<String>) {
+    let mut reader = Reader::new(fastq).set_multiline(multiline);
+    let mut expected = vec![];
+    while let Some(res) = reader.next() {
+        expected.push(match res {
+            Ok(r) => format!("{:?} {:?}", r.to_owned_record(), reader.position()),
+            Err(e) => format!("{:?}", e),
+        });
+    }
+    let mut reader = SliceReader::new(fastq).set_multiline(multiline);
+    let mut out = vec![];
+    while let Some(res) = reader.next() {
+        out.push(match res {
+            Ok(r) => format!("{:?} {:?}", r.to_owned_record(), reader.position().unwrap()),
+            Err(e) => format!("{:?}", e),
+        });
+    }
+    (out, expected)
+}
+
+#[test]
+fn test_fastq_slice_reader() {
+    let inputs: &[&[u8]] = &[
+        FASTQ,
+        FASTQ_MULTI,
+        FASTQ_MALFORMED,
+        b"",
+        b"\n\n",
+        b"@id\nATGC\n+\n~~~~",
+        b"@id\r\nATGC\r\n+\r\n~~~~\r\n@id2\r\nA\r\n+\r\n~\r\n",
+        b"@id\nATGC\n+\n~~~~\n\n\n",
+        b"@id\nATGC\n+\n~~~~\n@id2\nATGC\n",
+        b"@id\nATGC\n+\n~~~\n",
+        b"@id\nATGC\n-\n~~~~\n",
+        b"@id\nATGC\n+\n~~~~\nid2\nA\n+\n~\n",
+        b"@id\nAT\nGC\n+\n~~\n~~\n@id2\n\n+\n\n",
+        b"@id\nAT\nGC\n+\n~~\n",
+        b"@id\nAT\nGC\n+\n~~~\n~~\n",
+        b"@id\nAT\nGC\n+\n~~\n~~",
+    ];
+    for &fastq in inputs {
+        for &multiline in &[false, true] {
+            let (out, expected) = slice_reader_results(fastq, multiline);
+            assert_eq!(
+                out,
+                expected,
+                "{:?} (multiline: {})",
+                String::from_utf8_lossy(fastq),
+                multiline
+            );
+        }
+    }
+}
+
+#[test]
+fn test_fastq_slice_records() {
+    use seq_io::header::HeaderConfig;
+
+    let config = HeaderConfig::new().set_strip_read_number(true);
+    let records: Vec<SliceRecord> = SliceReader::new(FASTQ_MULTI)
+        .set_multiline(true)
+        .set_header_config(config)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let mut reader = Reader::new(FASTQ_MULTI)
+        .set_multiline(true)
+        .set_header_config(config);
+    for rec in &records {
+        let expected = reader.next().unwrap().unwrap();
+        assert_eq!(rec.id_desc(), expected.id_desc());
+        assert_eq!(rec.seq(), expected.seq());
+        assert_eq!(rec.qual_slice(), expected.qual());
+        assert_eq!(rec.full_seq(), expected.full_seq());
+        assert_eq!(rec.full_qual(), expected.full_qual());
+        assert!(rec.seq_lines().eq(expected.seq_lines()));
+        let mut out = vec![];
+        let mut expected_out = vec![];
+        rec.write(&mut out).unwrap();
+        expected.write(&mut expected_out).unwrap();
+        assert_eq!(out, expected_out);
+        out.clear();
+        expected_out.clear();
+        rec.write_unchanged(&mut out).unwrap();
+        expected.write_unchanged(&mut expected_out).unwrap();
+        assert_eq!(out, expected_out);
+    }
+    assert!(reader.next().is_none());
+
+    let mut reader = SliceReader::new(FASTQ);
+    assert!(reader.position().is_none());
+    let rec = reader.next().unwrap().unwrap();
+    assert_eq!(rec.head_slice(), b"id desc");
+    assert_eq!(reader.position(), Some(&Position::new(1, 0)));
+    assert_eq!(reader.remaining(), &FASTQ[21..]);
+    reader.next().unwrap().unwrap();
+    assert_eq!(reader.position(), Some(&Position::with_record(5, 21, 1)));
+    assert_eq!(reader.remaining(), b"");
+    assert!(reader.next().is_none());
+    // the position of the last record is kept
+    assert_eq!(reader.position(), Some(&Position::with_record(5, 21, 1)));
+    assert_eq!(reader.remaining(), b"");
+
+    let mut reader = SliceReader::new(b"\n\n");
+    assert!(reader.next().is_none());
+    assert!(reader.position().is_none());
+}
+
+#[test]
+fn test_fastq_slice_header_config_errors() {
+    use seq_io::header::{Delimiter, HeaderConfig};
+
+    let config = HeaderConfig::new()
+        .set_delimiter(Delimiter::Tab)
+        .set_strip_read_number(true);
+    let fastq = b"@id/1\tdesc x\nAC\n+\nI\n";
+    let mut reader = SliceReader::new(&fastq[..]).set_header_config(config);
+    match reader.next().unwrap() {
+        Err(Error::UnequalLengths { pos, .. }) => assert_eq!(pos.id.as_deref(), Some("id")),
+        r => panic!("unexpected result: {:?}", r.map(|r| r.to_owned_record())),
+    }
+}
+
+#[test]
+#[cfg(feature = "memmap")]
+fn test_fastq_slice_reader_mmap() {
+    use std::io::Write;
+
+    let path = std::env::temp_dir().join(format!("seq_io_test_{}_mmap.fastq", std::process::id()));
+    std::fs::File::create(&path)
+        .unwrap()
+        .write_all(FASTQ)
+        .unwrap();
+    let data = unsafe { seq_io::mmap::map_file(&path).unwrap() };
+    let records: Vec<_> = SliceReader::new(&data)
+        .map(|r| r.unwrap().to_owned_record())
+        .collect();
+    let expected: Vec<_> = Reader::new(FASTQ).records().map(|r| r.unwrap()).collect();
+    assert_eq!(records, expected);
+    drop(data);
+    std::fs::remove_file(&path).unwrap();
+}
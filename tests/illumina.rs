@@ -0,0 +1,147 @@
+extern crate seq_io;
+#[macro_use]
+extern crate matches;
+
+use seq_io::illumina::*;
+
+#[test]
+fn test_casava() {
+    let h = IlluminaHeader::parse(b"EAS139:136:FC706VJ:2:2104:15343:197393 1:Y:18:ATCACG").unwrap();
+    assert_eq!(
+        h,
+        IlluminaHeader {
+            sra: None,
+            instrument: "EAS139",
+            run: Some(136),
+            flowcell: Some("FC706VJ"),
+            lane: 2,
+            tile: 2104,
+            x: 15343,
+            y: 197393,
+            umi: None,
+            read: Some(1),
+            filtered: Some(true),
+            control: Some(18),
+            index: Some("ATCACG"),
+        }
+    );
+    assert!(!h.is_legacy());
+
+    // UMI, index pair, additional description
+    let h = IlluminaHeader::parse(b"M1:1:FC:1:11:22:33:ACGTACGT 2:N:0:ACG+TTG length=100").unwrap();
+    assert_eq!(h.umi, Some("ACGTACGT"));
+    assert_eq!(h.read, Some(2));
+    assert_eq!(h.filtered, Some(false));
+    assert_eq!(h.control, Some(0));
+    assert_eq!(h.index, Some("ACG+TTG"));
+
+    // no description, /2 suffix
+    let h = IlluminaHeader::parse(b"M1:1:FC:1:11:22:33/2").unwrap();
+    assert_eq!((h.lane, h.tile, h.x, h.y), (1, 11, 22, 33));
+    assert_eq!(h.read, Some(2));
+    assert_eq!(h.filtered, None);
+    assert_eq!(h.index, None);
+
+    // sample number instead of index, empty index
+    let h = IlluminaHeader::parse(b"M1:1:FC:1:11:22:33 1:N:0:2").unwrap();
+    assert_eq!(h.index, Some("2"));
+    let h = IlluminaHeader::parse(b"M1:1:FC:1:11:22:33 1:N:0:").unwrap();
+    assert_eq!(h.index, None);
+}
+
+#[test]
+fn test_legacy() {
+    let h = IlluminaHeader::parse(b"HWUSI-EAS100R:6:73:941:1973#0/1").unwrap();
+    assert!(h.is_legacy());
+    assert_eq!(h.instrument, "HWUSI-EAS100R");
+    assert_eq!((h.run, h.flowcell), (None, None));
+    assert_eq!((h.lane, h.tile, h.x, h.y), (6, 73, 941, 1973));
+    assert_eq!(h.index, Some("0"));
+    assert_eq!(h.read, Some(1));
+
+    let h = IlluminaHeader::parse(b"HWUSI-EAS100R:6:73:941:1973 length=36").unwrap();
+    assert_eq!((h.index, h.read), (None, None));
+}
+
+#[test]
+fn test_sra() {
+    let h =
+        IlluminaHeader::parse(b"SRR001666.1 071112_SLXA-EAS1_s_7:5:1:817:345 length=36").unwrap();
+    assert_eq!(
+        h.sra,
+        Some(SraId {
+            accession: "SRR001666",
+            spot: 1,
+            read: None
+        })
+    );
+    assert_eq!(h.instrument, "071112_SLXA-EAS1_s_7");
+    assert_eq!((h.lane, h.tile, h.x, h.y), (5, 1, 817, 345));
+
+    let h = IlluminaHeader::parse(b"ERR123.7.2 M1:1:FC:1:11:22:33 2:N:0:ACGT").unwrap();
+    assert_eq!(h.sra.unwrap().read, Some(2));
+    assert_eq!(h.index, Some("ACGT"));
+
+    assert_eq!(
+        SraId::parse("DRR000001.10"),
+        Some(SraId {
+            accession: "DRR000001",
+            spot: 10,
+            read: None
+        })
+    );
+    assert_eq!(SraId::parse("SRR001666"), None);
+    assert_eq!(SraId::parse("SRR.1"), None);
+    assert_eq!(SraId::parse("SRX001666.1"), None);
+    assert_eq!(SraId::parse("SRR001666.1.x"), None);
+}
+
+#[test]
+fn test_errors() {
+    assert_eq!(
+        IlluminaHeader::parse(b"SRR001666.1 length=36"),
+        Err(Error::FieldCount(1))
+    );
+    assert_eq!(
+        IlluminaHeader::parse(b"SRR001666.1"),
+        Err(Error::NoIlluminaId)
+    );
+    assert_eq!(IlluminaHeader::parse(b"read1"), Err(Error::FieldCount(1)));
+    assert_eq!(
+        IlluminaHeader::parse(b"a:b:c:d:e:f"),
+        Err(Error::FieldCount(6))
+    );
+    assert_eq!(
+        IlluminaHeader::parse(b"M1:x:FC:1:11:22:33"),
+        Err(Error::InvalidField("run"))
+    );
+    assert_eq!(
+        IlluminaHeader::parse(b"M1:1:FC:1:11:22:33 1:X:0:ACGT"),
+        Err(Error::InvalidField("filtered"))
+    );
+    assert_eq!(
+        IlluminaHeader::parse(b"M1:1:FC:1:11:22:33 1:N"),
+        Err(Error::InvalidField("control"))
+    );
+    assert_matches!(
+        IlluminaHeader::parse(b"M1:1:FC:1:11:22:\xff"),
+        Err(Error::Utf8(_))
+    );
+}
+
+#[test]
+fn test_records() {
+    use seq_io::{fasta, fastq};
+
+    let fasta = b">M1:1:FC:1:11:22:33 1:N:0:ACGT\nACGT\n";
+    let mut reader = fasta::Reader::new(&fasta[..]);
+    let rec = reader.next().unwrap().unwrap();
+    let h = fasta::Record::illumina_header(&rec).unwrap();
+    assert_eq!(h.index, Some("ACGT"));
+
+    let fastq = b"@M1:1:FC:1:11:22:33/1\nACGT\n+\nIIII\n";
+    let mut reader = fastq::Reader::new(&fastq[..]);
+    let rec = reader.next().unwrap().unwrap().to_owned_record();
+    let h = fastq::Record::illumina_header(&rec).unwrap();
+    assert_eq!(h.read, Some(1));
+}
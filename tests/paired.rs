@@ -0,0 +1,425 @@
+extern crate seq_io;
+#[macro_use]
+extern crate matches;
+
+use seq_io::paired::{ids_match, pair_id, Error};
+use std::io::Write;
+
+fn fastq_pairs(n: usize, suffix: &str) -> Vec<u8> {
+    let mut out = vec![];
+    for i in 0..n {
+        write!(out, "@read{}{} 1:N:0:ACGT\nACGT\n+\nIIII\n", i, suffix).unwrap();
+    }
+    out
+}
+
+#[test]
+fn test_pair_id() {
+    assert_eq!(pair_id(b"id/1"), b"id");
+    assert_eq!(pair_id(b"id/2 desc"), b"id");
+    assert_eq!(pair_id(b"id/3"), b"id/3");
+    assert_eq!(pair_id(b"M1:2:3\t1:N:0:ACGT"), b"M1:2:3");
+    assert!(ids_match(b"M1:2:3 1:N:0:ACGT", b"M1:2:3 2:N:0:ACGT"));
+    assert!(ids_match(b"id/1", b"id/2"));
+    assert!(!ids_match(b"id1/1", b"id2/2"));
+}
+
+#[test]
+fn test_fastq_paired() {
+    use seq_io::fastq::{PairedReader, Reader, Record};
+    let f1 = fastq_pairs(10, "/1");
+    let f2 = fastq_pairs(10, "/2");
+    for cap in 3..100 {
+        let mut reader = PairedReader::new(
+            Reader::with_capacity(&f1[..], cap),
+            Reader::with_capacity(&f2[..], cap),
+        );
+        let mut i = 0;
+        while let Some(res) = reader.next() {
+            let (r1, r2) = res.unwrap();
+            assert_eq!(r1.id_bytes(), format!("read{}/1", i).as_bytes());
+            assert_eq!(r2.id_bytes(), format!("read{}/2", i).as_bytes());
+            i += 1;
+        }
+        assert_eq!(i, 10);
+        assert_eq!(reader.num_pairs(), 10);
+    }
+}
+
+#[test]
+fn test_fastq_paired_mismatch() {
+    use seq_io::fastq::{PairedReader, Reader};
+    let f1 = fastq_pairs(3, "/1");
+    let f2 = fastq_pairs(2, "/2");
+
+    let mut reader = PairedReader::new(Reader::new(&f1[..]), Reader::new(&f2[..]));
+    assert!(reader.next().unwrap().is_ok());
+    assert!(reader.next().unwrap().is_ok());
+    assert_matches!(
+        reader.next().unwrap(),
+        Err(Error::CountMismatch {
+            pairs: 2,
+            longer: 1
+        })
+    );
+
+    let mut reader = PairedReader::new(Reader::new(&f2[..]), Reader::new(&f1[..]));
+    reader.next().unwrap().unwrap();
+    reader.next().unwrap().unwrap();
+    assert_matches!(
+        reader.next().unwrap(),
+        Err(Error::CountMismatch {
+            pairs: 2,
+            longer: 2
+        })
+    );
+
+    let f2 = b"@read0/2\nA\n+\nI\n@other/2\nA\n+\nI\n";
+    let mut reader = PairedReader::new(Reader::new(&f1[..]), Reader::new(&f2[..]));
+    reader.next().unwrap().unwrap();
+    match reader.next().unwrap() {
+        Err(Error::IdMismatch { pair, id1, id2 }) => {
+            assert_eq!(pair, 2);
+            assert_eq!(id1, "read1");
+            assert_eq!(id2, "other");
+        }
+        _ => panic!("ID mismatch expected"),
+    }
+
+    let mut reader =
+        PairedReader::new(Reader::new(&f1[..]), Reader::new(&f2[..])).set_check_ids(false);
+    reader.next().unwrap().unwrap();
+    reader.next().unwrap().unwrap();
+
+    let f2 = b"@read0/2\nA\n+\nII\n";
+    let mut reader = PairedReader::new(Reader::new(&f1[..]), Reader::new(&f2[..]));
+    assert_matches!(
+        reader.next().unwrap(),
+        Err(Error::Parse {
+            input: 2,
+            error: seq_io::fastq::Error::UnequalLengths { .. }
+        })
+    );
+}
+
+#[test]
+fn test_fastq_paired_recset() {
+    use seq_io::fastq::{PairedReader, Reader, Record, RecordSet};
+    let f1 = fastq_pairs(50, "/1");
+    let f2 = fastq_pairs(50, "/2");
+    let f2_short = fastq_pairs(49, "/2");
+    for cap in (3..2000).step_by(11) {
+        let mut reader = PairedReader::new(
+            Reader::with_capacity(&f1[..], cap),
+            Reader::with_capacity(&f2[..], cap * 2 / 3 + 3),
+        );
+        let mut rset1 = RecordSet::default();
+        let mut rset2 = RecordSet::default();
+        let mut i = 0;
+        while let Some(res) = reader.read_record_set_pair(&mut rset1, &mut rset2) {
+            res.unwrap();
+            assert_eq!(rset1.len(), rset2.len());
+            for (r1, r2) in rset1.into_iter().zip(&rset2) {
+                assert_eq!(r1.id_bytes(), format!("read{}/1", i).as_bytes());
+                assert_eq!(r2.id_bytes(), format!("read{}/2", i).as_bytes());
+                i += 1;
+            }
+        }
+        assert_eq!(i, 50);
+        assert_eq!(reader.num_pairs(), 50);
+
+        // count mismatch
+        let mut reader = PairedReader::new(
+            Reader::with_capacity(&f1[..], cap),
+            Reader::with_capacity(&f2_short[..], cap),
+        );
+        let res = loop {
+            match reader.read_record_set_pair(&mut rset1, &mut rset2) {
+                Some(Ok(())) => {}
+                Some(Err(e)) => break e,
+                None => panic!("error expected"),
+            }
+        };
+        assert_matches!(
+            res,
+            Error::CountMismatch {
+                pairs: 49,
+                longer: 1
+            }
+        );
+    }
+}
+
+#[test]
+fn test_fasta_paired() {
+    use seq_io::fasta::{PairedReader, Reader, Record, RecordSet};
+    let f1 = b">s1/1\nACGT\n>s2/1\nAC\nGT\n";
+    let f2 = b">s1/2\nTTTT\n>s2/2 desc\nGG\n";
+    let mut reader = PairedReader::new(Reader::new(&f1[..]), Reader::new(&f2[..]));
+    let (r1, r2) = reader.next().unwrap().unwrap();
+    assert_eq!(r1.id(), Ok("s1/1"));
+    assert_eq!(r2.seq(), b"TTTT");
+    let (r1, r2) = reader.next().unwrap().unwrap();
+    assert_eq!(r1.full_seq().as_ref(), b"ACGT");
+    assert_eq!(r2.desc(), Some(Ok("desc")));
+    assert!(reader.next().is_none());
+
+    let mut reader = PairedReader::new(Reader::new(&f1[..]), Reader::new(&f2[..11]));
+    let mut rset1 = RecordSet::default();
+    let mut rset2 = RecordSet::default();
+    assert_matches!(
+        reader.read_record_set_pair(&mut rset1, &mut rset2),
+        Some(Err(Error::CountMismatch {
+            pairs: 1,
+            longer: 1
+        }))
+    );
+}
+
+fn interleaved(n: usize) -> Vec<u8> {
+    let mut out = vec![];
+    for i in 0..n {
+        write!(
+            out,
+            "@read{}/1\nACGT\n+\nIIII\n@read{}/2\nTT\n+\nII\n",
+            i, i
+        )
+        .unwrap();
+    }
+    out
+}
+
+#[test]
+fn test_fastq_interleaved() {
+    use seq_io::fastq::{InterleavedReader, Reader, Record};
+    let fq = interleaved(20);
+    for cap in 3..200 {
+        let mut reader = InterleavedReader::new(Reader::with_capacity(&fq[..], cap));
+        let mut i = 0;
+        while let Some(res) = reader.next() {
+            let (r1, r2) = res.unwrap();
+            assert_eq!(r1.id_bytes(), format!("read{}/1", i).as_bytes());
+            assert_eq!(r2.id_bytes(), format!("read{}/2", i).as_bytes());
+            assert_eq!(r2.seq(), b"TT");
+            i += 1;
+        }
+        assert_eq!(i, 20);
+        assert_eq!(reader.num_pairs(), 20);
+    }
+}
+
+#[test]
+fn test_fastq_interleaved_recset() {
+    use seq_io::fastq::{InterleavedReader, Reader, Record, RecordSet};
+    let fq = interleaved(20);
+    for cap in 3..200 {
+        let mut reader = InterleavedReader::new(Reader::with_capacity(&fq[..], cap));
+        let mut rset = RecordSet::default();
+        let mut i = 0;
+        while let Some(res) = reader.read_record_set(&mut rset) {
+            res.unwrap();
+            assert_eq!(rset.len() % 2, 0);
+            for (r1, r2) in rset.pairs() {
+                assert_eq!(r1.id_bytes(), format!("read{}/1", i).as_bytes());
+                assert_eq!(r2.id_bytes(), format!("read{}/2", i).as_bytes());
+                i += 1;
+            }
+        }
+        assert_eq!(i, 20);
+    }
+}
+
+#[test]
+fn test_fastq_interleaved_parallel() {
+    use seq_io::fastq::{InterleavedReader, Reader, Record};
+    use seq_io::parallel::read_parallel;
+    let fq = interleaved(100);
+    for cap in (3..500).step_by(7) {
+        let reader = InterleavedReader::new(Reader::with_capacity(&fq[..], cap));
+        let n = read_parallel(
+            reader,
+            2,
+            2,
+            |rset| rset.pairs().all(|(r1, r2)| ids_match(r1.head(), r2.head())),
+            |rsets| {
+                let mut n = 0;
+                while let Some(res) = rsets.next() {
+                    let (rset, ok) = res.unwrap();
+                    assert!(ok);
+                    assert_eq!(rset.len() % 2, 0);
+                    n += rset.len() / 2;
+                }
+                n
+            },
+        );
+        assert_eq!(n, 100);
+    }
+}
+
+#[test]
+fn test_fastq_interleaved_mismatch() {
+    use seq_io::fastq::{InterleavedReader, Reader, RecordSet};
+    let mut fq = interleaved(2);
+    fq.extend_from_slice(b"@read2/1\nA\n+\nI\n");
+    let mut reader = InterleavedReader::new(Reader::new(&fq[..]));
+    reader.next().unwrap().unwrap();
+    reader.next().unwrap().unwrap();
+    assert_matches!(
+        reader.next().unwrap(),
+        Err(Error::CountMismatch {
+            pairs: 2,
+            longer: 1
+        })
+    );
+    assert!(reader.next().is_none());
+
+    let mut reader = InterleavedReader::new(Reader::new(&fq[..]));
+    let mut rset = RecordSet::default();
+    assert_matches!(
+        reader.read_record_set(&mut rset).unwrap(),
+        Err(Error::CountMismatch {
+            pairs: 2,
+            longer: 1
+        })
+    );
+
+    let fq = b"@a/1\nA\n+\nI\n@b/2\nA\n+\nI\n";
+    let mut reader = InterleavedReader::new(Reader::new(&fq[..]));
+    assert_matches!(
+        reader.next().unwrap(),
+        Err(Error::IdMismatch { pair: 1, .. })
+    );
+    let mut reader = InterleavedReader::new(Reader::new(&fq[..])).set_check_ids(false);
+    reader.next().unwrap().unwrap();
+}
+
+#[test]
+fn test_fastq_write_interleaved() {
+    use seq_io::fastq::{write_interleaved, InterleavedReader, Reader};
+    let f1 = fastq_pairs(5, "/1");
+    let f2 = fastq_pairs(5, "/2");
+    let mut out = vec![];
+    let n = write_interleaved(
+        &mut out,
+        Reader::new(&f1[..]).records().map(Result::unwrap),
+        Reader::new(&f2[..]).records().map(Result::unwrap),
+    )
+    .unwrap();
+    assert_eq!(n, 5);
+    let mut reader = InterleavedReader::new(Reader::new(&out[..]));
+    while let Some(res) = reader.next() {
+        res.unwrap();
+    }
+    assert_eq!(reader.num_pairs(), 5);
+
+    let res = write_interleaved(
+        vec![],
+        Reader::new(&f1[..]).records().map(Result::unwrap),
+        Reader::new(&f2[..]).records().skip(1).map(Result::unwrap),
+    );
+    assert_eq!(res.unwrap_err().kind(), std::io::ErrorKind::InvalidData);
+}
+
+#[test]
+fn test_fastq_parallel_paired() {
+    use seq_io::fastq::{PairedReader, Reader, Record};
+    use seq_io::parallel::parallel_fastq_paired;
+    let f1 = fastq_pairs(100, "/1");
+    let f2 = fastq_pairs(100, "/2");
+    for cap in (3..1000).step_by(13) {
+        let reader = PairedReader::new(
+            Reader::with_capacity(&f1[..], cap),
+            Reader::with_capacity(&f2[..], cap / 2 + 3),
+        );
+        let mut expected = PairedReader::new(Reader::new(&f1[..]), Reader::new(&f2[..]));
+        let mut n = 0;
+        // a single worker thread preserves the order of record sets
+        let res = parallel_fastq_paired(
+            reader,
+            1,
+            2,
+            |r1, r2, out: &mut (String, String)| {
+                *out = (r1.id().unwrap().to_string(), r2.id().unwrap().to_string());
+            },
+            |r1, r2, out| {
+                let (e1, e2) = expected.next().unwrap().unwrap();
+                assert_eq!(r1.id_bytes(), e1.id_bytes());
+                assert_eq!(r2.id_bytes(), e2.id_bytes());
+                assert_eq!(out.0, e1.id().unwrap());
+                assert_eq!(out.1, e2.id().unwrap());
+                n += 1;
+                None::<()>
+            },
+        );
+        assert!(res.unwrap().is_none());
+        assert_eq!(n, 100);
+    }
+}
+
+#[test]
+fn test_fastq_parallel_paired_stop() {
+    use seq_io::fastq::{PairedReader, Reader, Record};
+    use seq_io::parallel::parallel_fastq_paired;
+    let f1 = fastq_pairs(100, "/1");
+    let f2 = fastq_pairs(100, "/2");
+    let reader = PairedReader::new(
+        Reader::with_capacity(&f1[..], 100),
+        Reader::with_capacity(&f2[..], 100),
+    );
+    let res = parallel_fastq_paired(
+        reader,
+        2,
+        2,
+        |_, _, _: &mut ()| {},
+        |r1, _, _| {
+            if r1.id_bytes() == b"read50/1" {
+                return Some(r1.id().unwrap().to_string());
+            }
+            None
+        },
+    );
+    assert_eq!(res.unwrap(), Some("read50/1".to_string()));
+}
+
+#[test]
+fn test_fastq_parallel_paired_err() {
+    use seq_io::fastq::{PairedReader, Reader};
+    use seq_io::parallel::parallel_fastq_paired;
+    let f1 = fastq_pairs(100, "/1");
+    let f2 = fastq_pairs(99, "/2");
+    let reader = PairedReader::new(Reader::new(&f1[..]), Reader::new(&f2[..]));
+    let mut n = 0;
+    let res = parallel_fastq_paired(
+        reader,
+        2,
+        2,
+        |_, _, _: &mut ()| {},
+        |_, _, _| {
+            n += 1;
+            None::<()>
+        },
+    );
+    assert_matches!(res, Err(Error::CountMismatch { longer: 1, .. }));
+}
+
+#[test]
+fn test_fasta_parallel_paired() {
+    use seq_io::fasta::{PairedReader, Reader, Record};
+    use seq_io::parallel::parallel_fasta_paired;
+    let f1 = b">s1/1\nACGT\n>s2/1\nAC\nGT\n";
+    let f2 = b">s1/2\nTTTT\n>s2/2 desc\nGG\n";
+    let reader = PairedReader::new(Reader::new(&f1[..]), Reader::new(&f2[..]));
+    let mut seqs = vec![];
+    parallel_fasta_paired(
+        reader,
+        1,
+        2,
+        |r1, r2, out: &mut usize| *out = r1.full_seq().len() + r2.full_seq().len(),
+        |r1, _, out| {
+            seqs.push((r1.id().unwrap().to_string(), *out));
+            None::<()>
+        },
+    )
+    .unwrap();
+    assert_eq!(seqs, vec![("s1/1".to_string(), 8), ("s2/1".to_string(), 6)]);
+}
@@ -0,0 +1,98 @@
+extern crate seq_io;
+
+use seq_io::tags::*;
+
+#[test]
+fn test_tags() {
+    let desc = b"runid=abc  read=12\tch=100 start_time=2019-01-01T00:00:00Z word =x BC:Z:ACGT xy:i:-3 AB:Q:x";
+    let tags: Vec<_> = Tags::new(desc).collect();
+    let tag = |key: &'static [u8], sam_type, value: &'static [u8]| Tag {
+        key,
+        sam_type,
+        value,
+    };
+    assert_eq!(
+        tags,
+        [
+            tag(b"runid", None, b"abc"),
+            tag(b"read", None, b"12"),
+            tag(b"ch", None, b"100"),
+            tag(b"start_time", None, b"2019-01-01T00:00:00Z"),
+            tag(b"BC", Some(b'Z'), b"ACGT"),
+            tag(b"xy", Some(b'i'), b"-3"),
+        ]
+    );
+
+    let tags = Tags::new(desc);
+    assert_eq!(
+        tags.clone().get(b"read").unwrap().parse_value(),
+        Some(12u32)
+    );
+    assert_eq!(tags.clone().get(b"xy").unwrap().parse_value(), Some(-3i32));
+    assert_eq!(tags.clone().get(b"runid").unwrap().value_str(), Ok("abc"));
+    assert_eq!(
+        tags.clone().get(b"runid").unwrap().parse_value::<u8>(),
+        None
+    );
+    assert_eq!(tags.clone().get(b"word"), None);
+    assert_eq!(Tags::new(b"").next(), None);
+
+    // empty values
+    assert_eq!(Tag::parse(b"k="), Some(tag(b"k", None, b"")));
+    assert_eq!(Tag::parse(b"RG:Z:"), Some(tag(b"RG", Some(b'Z'), b"")));
+    assert_eq!(Tag::parse(b"=v"), None);
+}
+
+#[test]
+fn test_record_tags() {
+    use seq_io::{fasta, fastq};
+
+    let fasta = b">id ch=5 BC:Z:AC\nACGT\n>id2\nACGT\n";
+    let mut reader = fasta::Reader::new(&fasta[..]);
+    let rec = reader.next().unwrap().unwrap();
+    assert_eq!(fasta::Record::tags(&rec).count(), 2);
+    assert_eq!(fasta::Record::tag(&rec, b"BC").unwrap().value, b"AC");
+    let rec = reader.next().unwrap().unwrap();
+    assert_eq!(fasta::Record::tags(&rec).count(), 0);
+
+    let fastq = b"@id ch=5\nACGT\n+\nIIII\n";
+    let mut reader = fastq::Reader::new(&fastq[..]);
+    let rec = reader.next().unwrap().unwrap().to_owned_record();
+    assert_eq!(fastq::Record::tag(&rec, b"ch").unwrap().value, b"5");
+}
+
+#[test]
+fn test_tag_builder() {
+    let mut b = TagBuilder::new();
+    assert_eq!(b.build(None), None);
+    assert_eq!(b.build(Some(b"a b")), Some(&b"a b"[..]));
+
+    b.set(b"ch", b"1").set_sam(b"RG", b'Z', b"s1");
+    assert_eq!(b.build(None), Some(&b"ch=1 RG:Z:s1"[..]));
+    assert_eq!(
+        b.build(Some(b"x\tRG:Z:old ch=0 ch=0")),
+        Some(&b"x\tRG:Z:s1 ch=1 ch=1"[..])
+    );
+
+    // update a value, style of existing tags is replaced
+    b.set(b"ch", b"22");
+    assert_eq!(b.build(Some(b"ch:i:5 y")), Some(&b"ch=22 y RG:Z:s1"[..]));
+
+    // removal
+    b.remove(b"RG");
+    assert_eq!(b.build(Some(b"RG:Z:x")), Some(&b"ch=22"[..]));
+    b.remove(b"ch");
+    assert_eq!(b.build(Some(b"ch=1 RG:Z:x")), None);
+    assert_eq!(b.build(Some(b"ch=1 word RG:Z:x")), Some(&b"word"[..]));
+    b.set(b"ch", b"3");
+    assert_eq!(b.build(Some(b"word ch=1")), Some(&b"word ch=3"[..]));
+    b.clear();
+    assert_eq!(b.build(Some(b"ch=1")), Some(&b"ch=1"[..]));
+
+    // writing FASTA
+    let mut b = TagBuilder::new();
+    b.set(b"length", b"4");
+    let mut out = vec![];
+    seq_io::fasta::write_parts(&mut out, b"id", b.build(None), b"ACGT").unwrap();
+    assert_eq!(&out, b">id length=4\nACGT\n");
+}